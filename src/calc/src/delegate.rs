@@ -1,6 +1,9 @@
+use std::time::Duration;
+
+use druid::widget::Controller;
 use druid::{
-    Command, DelegateCtx, Env, Event, Handled, KeyEvent, Selector, Size, Target, WindowConfig,
-    WindowDesc,
+    Command, DelegateCtx, Env, Event, EventCtx, Handled, KeyEvent, Selector, Size, Target,
+    TimerToken, Widget, WindowConfig, WindowDesc,
 };
 use rust_i18n::t;
 
@@ -10,19 +13,32 @@ use crate::widgets::history_win::HistoryWin;
 use crate::{CalcState, PressedButton};
 
 use druid::AppDelegate;
+use math::number::Radix;
 
 const TEXTBOX_FOCUS: Selector<String> = Selector::new("textbox_focus");
 const APP_FOCUS: Selector<String> = Selector::new("app_focus");
 const SHOW_HISTORY: Selector<String> = Selector::new("show_history");
 const SHOW_HELP: Selector<String> = Selector::new("show_help");
 const SHOW_ABOUT: Selector<String> = Selector::new("show_about");
+/// Ask the `KeyRepeatTimer` to schedule the next repeat of the held key
+const SCHEDULE_REPEAT: Selector<Duration> = Selector::new("schedule_key_repeat");
+/// The repeat timer elapsed
+const REPEAT_KEY: Selector = Selector::new("repeat_key");
 
-const HISTORY_WIN: Size = Size::new(300.0, 300.0);
+const HISTORY_WIN: Size = Size::new(300.0, 350.0);
 const HELP_WIN: Size = Size::new(500.0, 400.0);
 const ABOUT_WIN: Size = Size::new(500.0, 400.0);
 
-/// Handle the menu bar commands for opening windows
-pub struct Delegate;
+/// How long a key has to be held before it starts repeating
+pub const KEY_REPEAT_DELAY: Duration = Duration::from_millis(400);
+/// Time between two repeats of a held key
+pub const KEY_REPEAT_INTERVAL: Duration = Duration::from_millis(60);
+
+/// Handle the menu bar commands for opening windows and the keyboard input
+#[derive(Default)]
+pub struct Delegate {
+    repeat: KeyRepeat,
+}
 
 impl AppDelegate<CalcState> for Delegate {
     #[rustfmt::skip]
@@ -87,6 +103,15 @@ impl AppDelegate<CalcState> for Delegate {
         // Disable keyboard typing into the display - focus is set on any textbox
         } else if let Some(_) = cmd.get(TEXTBOX_FOCUS) {
             data.set_display_focus(false);
+            self.repeat.stop();
+            Handled::Yes
+
+        // Repeat the held key and schedule the next repeat
+        } else if cmd.is(REPEAT_KEY) {
+            if let Some((button, next)) = self.repeat.tick() {
+                data.process_button(&button);
+                ctx.submit_command(SCHEDULE_REPEAT.with(next).to(data.get_main_win_id()));
+            }
             Handled::Yes
         } else {
             Handled::No
@@ -94,20 +119,177 @@ impl AppDelegate<CalcState> for Delegate {
     }
 
     #[rustfmt::skip]
-    fn event(&mut self, _ctx: &mut DelegateCtx, win_id: druid::WindowId, event: Event, data: &mut CalcState, _env: &Env) -> Option<Event> {
+    fn event(&mut self, ctx: &mut DelegateCtx, win_id: druid::WindowId, event: Event, data: &mut CalcState, _env: &Env) -> Option<Event> {
         if data.get_main_win_id() == win_id {
-            if let Event::KeyDown(key) = event.clone() {
-                if data.get_display_focus() {
-                    handle_keyboard_input(data, key);
+            match &event {
+                Event::KeyDown(key) if data.get_display_focus() => {
+                    // Held keys are repeated by our own timer, not by the system
+                    if key.repeat && repeatable(key).is_some() {
+                        return Some(event);
+                    }
+
+                    if let Some(delay) = repeatable(key).and_then(|button| self.repeat.press(button)) {
+                        ctx.submit_command(SCHEDULE_REPEAT.with(delay).to(win_id));
+                    }
+                    handle_keyboard_input(data, key.clone());
+                }
+                Event::KeyUp(key) => {
+                    if let Some(button) = repeatable(key) {
+                        self.repeat.release(&button);
+                    }
                 }
+                Event::WindowDisconnected => self.repeat.stop(),
+                _ => (),
             }
+        } else if matches!(event, Event::KeyDown(_) | Event::MouseDown(_)) {
+            // Another window took the focus, the release of the held key won't reach the main window
+            self.repeat.stop();
         }
         Some(event)
     }
 }
 
+/// Keyboard shortcuts for actions from the menu bar
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Shortcut {
+    /// Ctrl+C
+    CopyResult,
+    /// Ctrl+Shift+C
+    CopyExpression,
+    /// Ctrl+Z
+    Undo,
+    /// Ctrl+Y or Ctrl+Shift+Z
+    Redo,
+}
+
+/// Map key combination to a shortcut
+fn shortcut(key: &KeyEvent) -> Option<Shortcut> {
+    let druid::keyboard_types::Key::Character(ch) = &key.key else {
+        return None;
+    };
+
+    if !key.mods.ctrl() {
+        return None;
+    }
+
+    match (ch.to_ascii_lowercase().as_str(), key.mods.shift()) {
+        ("c", true) => Some(Shortcut::CopyExpression),
+        ("c", false) => Some(Shortcut::CopyResult),
+        ("z", false) => Some(Shortcut::Undo),
+        ("y", _) | ("z", true) => Some(Shortcut::Redo),
+        _ => None,
+    }
+}
+
+/// Constant inserted by the typed character. `e` is the hexadecimal digit in the `Hex` radix,
+/// `p` or `π` inserts pi. After a decimal digit (`exp_allowed`), `e` or `E` starts the exponent.
+fn constant_key(ch: char, radix: Radix, exp_allowed: bool) -> Option<PressedButton> {
+    match ch {
+        'e' | 'E' if exp_allowed && radix == Radix::Dec => Some(PressedButton::Exp),
+        'e' if radix != Radix::Hex => Some(PressedButton::Const("e".to_owned())),
+        'p' | 'π' => Some(PressedButton::Const("pi".to_owned())),
+        _ => None,
+    }
+}
+
+/// Button processed repeatedly while the key is held
+fn repeatable(key: &KeyEvent) -> Option<PressedButton> {
+    match key.key {
+        druid::keyboard_types::Key::ArrowLeft => Some(PressedButton::MoveLeft),
+        druid::keyboard_types::Key::ArrowRight => Some(PressedButton::MoveRight),
+        druid::keyboard_types::Key::Backspace => Some(PressedButton::Delete),
+        _ => None,
+    }
+}
+
+/// State of the held key repeating. Timers are handled by the `KeyRepeatTimer`,
+/// this only decides what to repeat and when.
+#[derive(Debug, Default)]
+struct KeyRepeat {
+    held: Option<PressedButton>,
+}
+
+impl KeyRepeat {
+    /// Start holding the `button`. Returns the delay before the first repeat,
+    /// or `None` if the button is already held.
+    fn press(&mut self, button: PressedButton) -> Option<Duration> {
+        if self.held.as_ref() == Some(&button) {
+            return None;
+        }
+
+        self.held = Some(button);
+        Some(KEY_REPEAT_DELAY)
+    }
+
+    /// Stop repeating, if the released `button` is the held one
+    fn release(&mut self, button: &PressedButton) {
+        if self.held.as_ref() == Some(button) {
+            self.held = None;
+        }
+    }
+
+    /// Stop repeating whatever is held
+    fn stop(&mut self) {
+        self.held = None;
+    }
+
+    /// Timer elapsed. Returns the button to process again and the delay of the next repeat.
+    fn tick(&self) -> Option<(PressedButton, Duration)> {
+        self.held
+            .clone()
+            .map(|button| (button, KEY_REPEAT_INTERVAL))
+    }
+}
+
+/// Controller of the root widget running timers for the held keys repeating.
+/// `AppDelegate` can't request timers on its own.
+#[derive(Default)]
+pub struct KeyRepeatTimer {
+    token: Option<TimerToken>,
+}
+
+impl<W: Widget<CalcState>> Controller<CalcState, W> for KeyRepeatTimer {
+    #[rustfmt::skip]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut CalcState, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(SCHEDULE_REPEAT) => {
+                // Replacing the token drops any pending timer of the previous hold
+                self.token = Some(ctx.request_timer(*cmd.get_unchecked(SCHEDULE_REPEAT)));
+                ctx.set_handled();
+            }
+            Event::Timer(token) if self.token == Some(*token) => {
+                self.token = None;
+                ctx.submit_command(REPEAT_KEY);
+                ctx.set_handled();
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
 /// Handle user keyboard inputs
 fn handle_keyboard_input(data: &mut CalcState, key: KeyEvent) {
+    if let Some(shortcut) = shortcut(&key) {
+        match shortcut {
+            Shortcut::CopyResult => data.copy_result(),
+            Shortcut::CopyExpression => data.copy_expression(),
+            Shortcut::Undo => data.process_button(&PressedButton::Undo),
+            Shortcut::Redo => data.process_button(&PressedButton::Redo),
+        }
+        return;
+    }
+
+    if let druid::keyboard_types::Key::Character(ch) = &key.key {
+        if let Some(button) = ch
+            .chars()
+            .next()
+            .and_then(|val| constant_key(val, data.radix, data.exp_allowed()))
+        {
+            data.process_button(&button);
+            return;
+        }
+    }
+
     match &key.key {
         druid::keyboard_types::Key::ArrowLeft => data.process_button(&PressedButton::MoveLeft),
         druid::keyboard_types::Key::ArrowRight => data.process_button(&PressedButton::MoveRight),
@@ -122,12 +304,14 @@ fn handle_keyboard_input(data: &mut CalcState, key: KeyEvent) {
                 ',' | '.' => data.process_button(&PressedButton::Comma),
                 '(' => data.process_button(&PressedButton::BracketLeft),
                 ')' => data.process_button(&PressedButton::BracketRight),
+                '|' => data.process_button(&PressedButton::AbsBar),
                 '+' => data.process_button(&PressedButton::BinOpt(crate::Opt::Add)),
                 '-' => data.process_button(&PressedButton::BinOpt(crate::Opt::Sub)),
                 '*' => data.process_button(&PressedButton::BinOpt(crate::Opt::Mul)),
                 '/' => data.process_button(&PressedButton::BinOpt(crate::Opt::Div)),
                 '^' => data.process_button(&PressedButton::BinOpt(crate::Opt::Pow)),
                 '!' => data.process_button(&PressedButton::UnaryOpt(crate::Opt::Fact)),
+                '%' => data.process_button(&PressedButton::Percent),
                 '=' => data.process_button(&PressedButton::Evaluate),
                 _ => (),
             },
@@ -158,3 +342,116 @@ fn process_numeric_key(data: &mut CalcState, num: u8) {
         _ => data.process_button(&PressedButton::Num(num)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use druid::Modifiers;
+
+    #[test]
+    fn copy_shortcuts() {
+        let key = |mods, text| shortcut(&KeyEvent::for_test(mods, text));
+
+        assert_eq!(key(Modifiers::CONTROL, "c"), Some(Shortcut::CopyResult));
+        assert_eq!(
+            key(Modifiers::CONTROL | Modifiers::SHIFT, "C"),
+            Some(Shortcut::CopyExpression)
+        );
+        assert_eq!(key(Modifiers::empty(), "c"), None);
+        assert_eq!(key(Modifiers::SHIFT, "C"), None);
+        assert_eq!(key(Modifiers::CONTROL, "v"), None);
+    }
+
+    #[test]
+    fn undo_shortcuts() {
+        let key = |mods, text| shortcut(&KeyEvent::for_test(mods, text));
+
+        assert_eq!(key(Modifiers::CONTROL, "z"), Some(Shortcut::Undo));
+        assert_eq!(key(Modifiers::CONTROL, "y"), Some(Shortcut::Redo));
+        assert_eq!(
+            key(Modifiers::CONTROL | Modifiers::SHIFT, "Z"),
+            Some(Shortcut::Redo)
+        );
+        assert_eq!(key(Modifiers::empty(), "z"), None);
+    }
+
+    #[test]
+    fn constant_keys() {
+        let e = Some(PressedButton::Const("e".to_owned()));
+        let pi = Some(PressedButton::Const("pi".to_owned()));
+
+        assert_eq!(constant_key('e', Radix::Dec, false), e);
+        assert_eq!(constant_key('e', Radix::Bin, false), e);
+        assert_eq!(constant_key('p', Radix::Dec, false), pi);
+        assert_eq!(constant_key('π', Radix::Hex, false), pi);
+        // Hexadecimal digit
+        assert_eq!(constant_key('e', Radix::Hex, false), None);
+        assert_eq!(constant_key('e', Radix::Hex, true), None);
+        assert_eq!(constant_key('E', Radix::Dec, false), None);
+        assert_eq!(constant_key('1', Radix::Dec, false), None);
+    }
+
+    #[test]
+    fn exponent_keys() {
+        let exp = Some(PressedButton::Exp);
+
+        assert_eq!(constant_key('e', Radix::Dec, true), exp);
+        assert_eq!(constant_key('E', Radix::Dec, true), exp);
+        assert_eq!(
+            constant_key('p', Radix::Dec, true),
+            Some(PressedButton::Const("pi".to_owned()))
+        );
+        assert_eq!(constant_key('E', Radix::Hex, true), None);
+    }
+
+    #[test]
+    fn held_key_repeats() {
+        let mut repeat = KeyRepeat::default();
+        assert_eq!(repeat.tick(), None);
+
+        assert_eq!(repeat.press(PressedButton::Delete), Some(KEY_REPEAT_DELAY));
+        assert_eq!(
+            repeat.tick(),
+            Some((PressedButton::Delete, KEY_REPEAT_INTERVAL))
+        );
+        // Repeated key down of the held key doesn't restart the delay
+        assert_eq!(repeat.press(PressedButton::Delete), None);
+
+        // Releasing other key keeps repeating
+        repeat.release(&PressedButton::MoveLeft);
+        assert!(repeat.tick().is_some());
+
+        // Pressing other key takes over
+        assert_eq!(
+            repeat.press(PressedButton::MoveLeft),
+            Some(KEY_REPEAT_DELAY)
+        );
+        assert_eq!(
+            repeat.tick(),
+            Some((PressedButton::MoveLeft, KEY_REPEAT_INTERVAL))
+        );
+
+        repeat.release(&PressedButton::MoveLeft);
+        assert_eq!(repeat.tick(), None);
+
+        repeat.press(PressedButton::MoveRight);
+        repeat.stop();
+        assert_eq!(repeat.tick(), None);
+    }
+
+    #[test]
+    fn repeatable_keys() {
+        let key = |k| repeatable(&KeyEvent::for_test(Modifiers::empty(), k));
+
+        assert_eq!(
+            key(druid::keyboard_types::Key::Backspace),
+            Some(PressedButton::Delete)
+        );
+        assert_eq!(
+            key(druid::keyboard_types::Key::ArrowLeft),
+            Some(PressedButton::MoveLeft)
+        );
+        assert_eq!(key(druid::keyboard_types::Key::Enter), None);
+        assert_eq!(key(druid::keyboard_types::Key::Character("1".into())), None);
+    }
+}
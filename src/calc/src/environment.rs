@@ -1,3 +1,5 @@
+use crate::widgets::FontSizes;
+use crate::{CalcState, Theme};
 use druid::{theme, Color, Env, Key};
 
 pub fn set_dark_envs(env: &mut Env) {
@@ -104,3 +106,76 @@ pub fn set_operation_btn_envs(env: &mut Env) {
         Color::grey8(170),
     );
 }
+
+/// Scale the fonts and switch to high contrast colors, when the accessibility mode is enabled.
+pub fn set_accessibility_envs(env: &mut Env, data: &CalcState) {
+    FontSizes::scaled(data.font_scale()).set_envs(env);
+
+    if data.accessibility() {
+        set_high_contrast_envs(env, data.get_theme(true) == Theme::Dark);
+    }
+}
+
+pub fn set_high_contrast_envs(env: &mut Env, dark: bool) {
+    let (background, text) = if dark {
+        (Color::BLACK, Color::WHITE)
+    } else {
+        (Color::WHITE, Color::BLACK)
+    };
+
+    env.set(theme::WINDOW_BACKGROUND_COLOR, background);
+    env.set(theme::TEXT_COLOR, text);
+    env.set(theme::CURSOR_COLOR, text);
+    env.set(Key::<Color>::new("calc.tab_textcolor"), text);
+    env.set(theme::DISABLED_TEXT_COLOR, Color::grey8(128));
+
+    for (background, hover, active) in DARK_BUTTON_KEYS {
+        env.set(background, Color::BLACK);
+        env.set(hover, Color::grey8(70));
+        env.set(active, Color::grey8(110));
+    }
+
+    for (background, hover, active) in LIGHT_BUTTON_KEYS {
+        env.set(background, Color::WHITE);
+        env.set(hover, Color::grey8(190));
+        env.set(active, Color::grey8(150));
+    }
+}
+
+/// Background, hover and active color keys of the buttons in the dark theme
+const DARK_BUTTON_KEYS: [(Key<Color>, Key<Color>, Key<Color>); 3] = [
+    (
+        Key::new("calc.Dark.digit_btn.background"),
+        Key::new("calc.Dark.digit_btn.hover"),
+        Key::new("calc.Dark.digit_btn.active"),
+    ),
+    (
+        Key::new("calc.Dark.func_btn.background"),
+        Key::new("calc.Dark.func_btn.hover"),
+        Key::new("calc.Dark.func_btn.active"),
+    ),
+    (
+        Key::new("calc.Dark.operation_btn.background"),
+        Key::new("calc.Dark.operation_btn.hover"),
+        Key::new("calc.Dark.operation_btn.active"),
+    ),
+];
+
+/// Background, hover and active color keys of the buttons in the light theme
+const LIGHT_BUTTON_KEYS: [(Key<Color>, Key<Color>, Key<Color>); 3] = [
+    (
+        Key::new("calc.Light.digit_btn.background"),
+        Key::new("calc.Light.digit_btn.hover"),
+        Key::new("calc.Light.digit_btn.active"),
+    ),
+    (
+        Key::new("calc.Light.func_btn.background"),
+        Key::new("calc.Light.func_btn.hover"),
+        Key::new("calc.Light.func_btn.active"),
+    ),
+    (
+        Key::new("calc.Light.operation_btn.background"),
+        Key::new("calc.Light.operation_btn.hover"),
+        Key::new("calc.Light.operation_btn.active"),
+    ),
+];
@@ -3,11 +3,18 @@
 mod tests;
 
 use crate::*;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::ops::Range;
 
 type Btn = PressedButton;
 
 /// Will put continuous underscore to previous character in string.
 pub const CURSOR_CHAR: char = '\u{02f0}';
+/// Default maximum number of buttons in the expression.
+pub const DEFAULT_MAX_LEN: usize = 1000;
+/// Number of edits, which can be undone.
+pub const UNDO_LIMIT: usize = 50;
 
 trait ToExpr {
     fn to_expr(&self) -> Option<ExprItem>;
@@ -40,8 +47,10 @@ impl ToExpr for Opt {
             Self::Pow2 =>    ExprItem::new("²",         "^2",       3,      true,   true),
             Self::Abs =>     ExprItem::new("abs ",      "abs",      3,      false,  false),
             Self::Comb =>    ExprItem::new("C",         "comb",     2,      true,   false),
+            Self::Perm =>    ExprItem::new("P",         "perm",     2,      true,   false),
             Self::Fact =>    ExprItem::new("!",         "!",        3,      true,   true),
             Self::Mod =>     ExprItem::new("mod",       "mod",      2,      true,   true),
+            Self::Percent => ExprItem::new("%",         "/100",     3,      true,   true),
         })
     }
 }
@@ -60,17 +69,21 @@ impl ToExpr for PressedButton {
             Self::BinOpt(opt) | Self::UnaryOpt(opt) => return opt.to_expr(),
             Self::BracketLeft =>  ExprItem::new("(", "(", 4, true, true),
             Self::BracketRight => ExprItem::new(")", ")", 4, true, true),
+            Self::AbsBar =>       ExprItem::new("|", "|", 4, true, true),
             Self::Comma =>        ExprItem::new(",", ".", 0, true, true),  // FIXME: Maybe we should localize this.
+            Self::Exp =>          ExprItem::new("E", "e", 0, true, true),
             Self::Random =>       ExprItem::new("⚄", "random", 0, true, true),
             Self::Const(name) =>  {
                 // Replace known constants with their characters.
                 match name.as_str() {
                     "pi" => ExprItem::new("π", "pi()", 0, true, true),
                     "phi" => ExprItem::new("ϕ", "phi()", 0, true, true),
+                    MEMORY_CONSTANT => ExprItem::new("M", "mem()", 0, true, true),
                     _ => ExprItem::new(name, format!("{name}()"), 0, true, true)
                 }
             },
             Self::Ans => ExprItem::new("Ans", "ans()", 0, true, true),
+            Self::Placeholder => ExprItem::new("▯", "", 0, true, true),
             _ => return None,
         })
     }
@@ -78,7 +91,7 @@ impl ToExpr for PressedButton {
 
 /// Holds strings, which are used to convert buttons
 /// to display/evaluate strings.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct ExprItem {
     disp: String,
     eval: String,
@@ -126,12 +139,31 @@ pub struct ExprManager {
     btn_stack: Vec<Btn>,
     /// Used for invalidating the expression manager (for druid repaint).
     dirty_flipper: bool,
+    /// Displayed symbol of the multiplication.
+    mul_symbol: MulSymbol,
+    /// Tokens of the last converted expression, see `ExprManager::tokenize()`.
+    token_cache: RefCell<TokenCache>,
+    /// Maximum number of buttons in `btn_stack`, further insertions are rejected.
+    max_len: usize,
+    /// States before the last edits, the oldest is dropped after `UNDO_LIMIT` edits.
+    undo_stack: VecDeque<Snapshot>,
+    /// Undone states, cleared by a new edit.
+    redo_stack: Vec<Snapshot>,
+}
+
+/// State of the expression restored by undo and redo.
+#[derive(Debug, Clone)]
+struct Snapshot {
+    btn_stack: Vec<Btn>,
+    cursor_pos: usize,
 }
 
 impl Data for ExprManager {
     // Dont compare `btn_stack` for performance reasons.
     fn same(&self, other: &Self) -> bool {
-        self.dirty_flipper == other.dirty_flipper && self.cursor_pos == other.cursor_pos
+        self.dirty_flipper == other.dirty_flipper
+            && self.cursor_pos == other.cursor_pos
+            && self.mul_symbol == other.mul_symbol
     }
 }
 
@@ -149,36 +181,209 @@ impl ExprManager {
             dirty_flipper: true,
             cursor_pos: 0,
             btn_stack: Vec::new(),
+            mul_symbol: MulSymbol::Dot,
+            token_cache: RefCell::new(TokenCache::new()),
+            max_len: DEFAULT_MAX_LEN,
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+
+    /// Limit the expression to `max_len` buttons.
+    pub const fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Use `symbol` for the multiplication on the display.
+    pub const fn with_mul_symbol(mut self, symbol: MulSymbol) -> Self {
+        self.mul_symbol = symbol;
+        self
+    }
+
+    /// Change the displayed symbol of the multiplication, the evaluate
+    /// string always uses `*`.
+    pub fn set_mul_symbol(&mut self, symbol: MulSymbol) {
+        self.mul_symbol = symbol;
+        self.dirty_flipper = !self.dirty_flipper;
+    }
+
+    /// Display string of the `btn`.
+    fn display_item(&self, btn: &Btn) -> Option<String> {
+        match btn {
+            Btn::BinOpt(Opt::Mul) => Some(self.mul_symbol.symbol().to_owned()),
+            _ => btn.to_expr().map(|item| item.disp),
         }
     }
 
     /// Process pressed button in calculator. This will
     /// edit expression string accordingly.
     ///
+    /// Returns `false` if the button was not inserted, because the expression
+    /// would exceed its maximum length.
+    ///
     /// # Panics
     /// When `btn` is `PressedButton::Evaluate` as `ExprManager`
     /// cannot compute results and thus should never get this.
-    pub fn process_button(&mut self, btn: &PressedButton) {
+    pub fn process_button(&mut self, btn: &PressedButton) -> bool {
+        let before = self.snapshot();
         match btn {
+            Btn::Undo => self.undo(),
+            Btn::Redo => self.redo(),
             Btn::Clear =>  {
                 self.btn_stack.clear();
                 self.cursor_pos = 0;
             },
-            Btn::Delete => {
-                if self.cursor_pos != 0 {
-                    self.btn_stack.remove(self.cursor_pos.saturating_sub(1));
-                    self.move_cursor(true);
-                }
-            }
+            Btn::Delete => self.delete(),
             Btn::MoveRight => self.move_cursor(false),
             Btn::MoveLeft => self.move_cursor(true),
             Btn::Evaluate => panic!("Cannot process `PressedButton::Evaluate`."),
+            Btn::BinOpt(Opt::Root | Opt::Pow) => {
+                if !self.insert_guided(btn) {
+                    return false;
+                }
+            }
+            Btn::Percent => return self.process_button(&Btn::UnaryOpt(Opt::Percent)),
+            // Exponent, which would make the number malformed, is ignored.
+            Btn::Exp if !self.exp_allowed() => return true,
             _ => {
+                // Fill the placeholder under the cursor with an operand,
+                // other buttons are inserted in front of it.
+                if self.btn_stack.get(self.cursor_pos) == Some(&Btn::Placeholder)
+                    && starts_operand(btn)
+                {
+                    self.btn_stack.remove(self.cursor_pos);
+                } else if self.btn_stack.len() >= self.max_len {
+                    return false;
+                }
                 self.btn_stack.insert(self.cursor_pos, btn.clone());
                 self.move_cursor(false);
             }
         };
+        // Record the edit. Moving the cursor alone doesn't count as one.
+        if !matches!(btn, Btn::Undo | Btn::Redo) && self.btn_stack != before.btn_stack {
+            self.record_edit(before);
+        }
         self.invalidate();
+        true
+    }
+
+    /// Current state of the expression.
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            btn_stack: self.btn_stack.clone(),
+            cursor_pos: self.cursor_pos,
+        }
+    }
+
+    /// Remember the state `before` an edit, so it can be undone.
+    fn record_edit(&mut self, before: Snapshot) {
+        if self.undo_stack.len() == UNDO_LIMIT {
+            self.undo_stack.pop_front();
+        }
+        self.undo_stack.push_back(before);
+        self.redo_stack.clear();
+    }
+
+    /// Return to the state before the last edit.
+    fn undo(&mut self) {
+        if let Some(snapshot) = self.undo_stack.pop_back() {
+            self.redo_stack.push(self.snapshot());
+            self.restore(snapshot);
+        }
+    }
+
+    /// Reapply the last undone edit.
+    fn redo(&mut self) {
+        if let Some(snapshot) = self.redo_stack.pop() {
+            self.undo_stack.push_back(self.snapshot());
+            self.restore(snapshot);
+        }
+    }
+
+    /// Replace the expression and the cursor with the `snapshot`.
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.btn_stack = snapshot.btn_stack;
+        self.cursor_pos = snapshot.cursor_pos;
+    }
+
+    /// Check whether the exponent can be inserted at the cursor. The number around
+    /// the cursor has to consist of decimal digits, have a digit before the cursor
+    /// and no exponent yet.
+    pub fn exp_allowed(&self) -> bool {
+        let in_number = |btn: &&Btn| matches!(btn, Btn::Num(_) | Btn::Comma | Btn::Exp);
+        let (before, after) = self.btn_stack.split_at(self.cursor_pos);
+        let mut before = before.iter().rev().take_while(in_number).peekable();
+        let after = after.iter().take_while(in_number);
+
+        matches!(before.peek(), Some(Btn::Num(_)))
+            && before
+                .chain(after)
+                .all(|btn| matches!(btn, Btn::Num(digit) if *digit < 10) || btn == &Btn::Comma)
+    }
+
+    /// Insert binary operation, whose operands are entered in guided way. Missing operands
+    /// are shown as placeholders (`▯ⁿ√▯`) and the cursor is moved to the first one,
+    /// so the index (or base) is entered first and then the radicand (or exponent).
+    /// Returns `false` if the operation doesn't fit into the maximum length.
+    fn insert_guided(&mut self, btn: &Btn) -> bool {
+        let start = self.cursor_pos;
+        // Placeholder under the cursor is replaced by the whole operation.
+        let replaced = usize::from(self.btn_stack.get(start) == Some(&Btn::Placeholder));
+        let has_lhs = start
+            .checked_sub(1)
+            .and_then(|idx| self.btn_stack.get(idx))
+            .is_some_and(ends_operand);
+        let has_rhs = self
+            .btn_stack
+            .get(start + replaced)
+            .is_some_and(starts_operand);
+
+        let mut guided = Vec::new();
+        if !has_lhs {
+            guided.push(Btn::Placeholder);
+        }
+        guided.push(btn.clone());
+        if !has_rhs {
+            guided.push(Btn::Placeholder);
+        }
+
+        if self.btn_stack.len() - replaced + guided.len() > self.max_len {
+            return false;
+        }
+        self.btn_stack.splice(start..start + replaced, guided);
+
+        // Stand on the first placeholder, or after the operation if there is none.
+        self.cursor_pos = if has_lhs { start + 1 } else { start };
+        true
+    }
+
+    /// Delete the item before cursor. Placeholders are deleted together with their operation.
+    fn delete(&mut self) {
+        let Some(idx) = self.cursor_pos.checked_sub(1) else {
+            return;
+        };
+
+        match self.btn_stack[idx] {
+            Btn::Placeholder => {}
+            Btn::BinOpt(Opt::Root | Opt::Pow) => {
+                if self.btn_stack.get(idx + 1) == Some(&Btn::Placeholder) {
+                    self.btn_stack.remove(idx + 1);
+                }
+                self.btn_stack.remove(idx);
+
+                // The placeholder before could be the right operand of another operation.
+                let owned = idx >= 2 && is_guided(&self.btn_stack[idx - 2]);
+                if idx >= 1 && self.btn_stack[idx - 1] == Btn::Placeholder && !owned {
+                    self.btn_stack.remove(idx - 1);
+                    self.cursor_pos -= 1;
+                }
+            }
+            _ => {
+                self.btn_stack.remove(idx);
+            }
+        }
+        self.move_cursor(true);
     }
 
     /// Invalidate the ExprManager, forcing druid to redraw
@@ -196,6 +401,83 @@ impl ExprManager {
         self.cursor_pos = self.cursor_pos.clamp(0, self.btn_stack.len());
     }
 
+    /// Buttons, which compose the expression.
+    pub fn buttons(&self) -> &[Btn] {
+        &self.btn_stack
+    }
+
+    /// Replace the expression with `buttons` and move the cursor to its end.
+    pub fn set_buttons(&mut self, buttons: Vec<Btn>) {
+        self.record_edit(self.snapshot());
+        self.btn_stack = buttons;
+        self.cursor_pos = self.btn_stack.len();
+        self.invalidate();
+    }
+
+    /// Get position of the cursor in the button stack.
+    pub fn cursor_pos(&self) -> usize {
+        self.cursor_pos
+    }
+
+    /// Get index of the bracket matching the bracket at `pos` in the button stack.
+    /// Returns `None` if there is no bracket at `pos` or if it is unmatched.
+    pub fn matching_bracket(&self, pos: usize) -> Option<usize> {
+        // Direction of the scan. Left bracket searches to the right and vice versa.
+        let forward = match self.btn_stack.get(pos)? {
+            Btn::BracketLeft => true,
+            Btn::BracketRight => false,
+            _ => return None,
+        };
+
+        let indices: Box<dyn Iterator<Item = usize>> = if forward {
+            Box::new(pos..self.btn_stack.len())
+        } else {
+            Box::new((0..=pos).rev())
+        };
+
+        // Depth of the nesting relative to the bracket at `pos`.
+        let mut depth = 0i32;
+        for i in indices {
+            let delta = match self.btn_stack[i] {
+                Btn::BracketLeft => 1,
+                Btn::BracketRight => -1,
+                _ => continue,
+            };
+            depth += if forward { delta } else { -delta };
+
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+
+        None
+    }
+
+    /// Get byte range of the button at `idx` in the string returned by
+    /// [`get_display_str`](ExprManager::get_display_str).
+    pub fn display_range(&self, idx: usize, with_cursor: bool) -> Option<Range<usize>> {
+        let cursor_len = CURSOR_CHAR.len_utf8();
+        let mut start = if with_cursor && self.cursor_pos == 0 {
+            cursor_len
+        } else {
+            0
+        };
+
+        for (i, btn) in self.btn_stack.iter().enumerate() {
+            let len = self.display_item(btn).map_or(0, |disp| disp.len());
+            if i == idx {
+                return Some(start..start + len);
+            }
+
+            start += len;
+            if with_cursor && i + 1 == self.cursor_pos {
+                start += cursor_len;
+            }
+        }
+
+        None
+    }
+
     /// Get string to be displayed to [`DisplayUI`](widgets::display::DisplayUI).
     pub fn get_display_str(&self, with_cursor: bool) -> String {
         // By default, the empty Display string is only cursor.
@@ -214,14 +496,14 @@ impl ExprManager {
             disp_str = CURSOR_CHAR.to_string();
         }
         for (i, btn) in self.btn_stack.iter().enumerate() {
-            disp_str += &match btn.to_expr() {
-                Some(item) => {
+            disp_str += &match self.display_item(btn) {
+                Some(disp) => {
                     if with_cursor 
                         && i == self.cursor_pos.saturating_sub(1) 
                         && self.cursor_pos != 0 {
-                        item.disp + &CURSOR_CHAR.to_string()
+                        disp + &CURSOR_CHAR.to_string()
                     } else {
-                        item.disp
+                        disp
                     }
                 },
                 None => {
@@ -252,6 +534,30 @@ impl ExprManager {
         self.to_eval_str(&postfix)
     }
 
+    /// Get the intermediate postfix notation used by `ExprManager::get_eval_str()`,
+    /// each token is represented by its evaluation string. Useful for debugging the conversion.
+    ///
+    /// Empty expression has empty postfix.
+    pub fn get_postfix_debug(&self) -> Result<Vec<String>> {
+        let mut tokens = self.tokenize();
+        let postfix = self.to_postfix(&mut tokens)?;
+
+        Ok(postfix.iter().map(|tok| tok.item.eval.clone()).collect())
+    }
+
+    /// Check whether the current expression can be evaluated by `calc`.
+    /// Empty expression is never evaluable.
+    pub fn is_evaluable(&self, calc: &mut math::Calculator) -> bool {
+        if self.btn_stack.is_empty() {
+            return false;
+        }
+
+        match self.get_eval_str() {
+            Ok(eval_str) => calc.validate(&eval_str).is_ok(),
+            Err(_) => false,
+        }
+    }
+
     /// Pop the operands off the stack, create resulting evaluate string,
     /// and push onto the stack.
     ///
@@ -295,7 +601,10 @@ impl ExprManager {
             1 => {
                 let operand = to_operand(eval_stack.last().unwrap());
                 eval = if token.item.skip_conv {
-                    if let Btn::UnaryOpt(Opt::Fact | Opt::Pow2) = token.btn {
+                    if let Btn::UnaryOpt(Opt::Percent) = token.btn {
+                        // Encapsulated, so the percentage stays a single operand. Case: "5÷10%"
+                        format!("({}{})", operand, token.item.eval)
+                    } else if let Btn::UnaryOpt(Opt::Fact | Opt::Pow2) = token.btn {
                         // Unary notation on the right side.
                         format!("{}{}", operand, token.item.eval)
                     } else {
@@ -313,9 +622,13 @@ impl ExprManager {
                 }
             }
             2 => {
-                let operand2 = to_operand(&eval_stack.pop().unwrap());
+                let rhs = eval_stack.pop().unwrap();
+                let operand2 = to_operand(&rhs);
                 let operand1 = to_operand(eval_stack.last().unwrap());
-                eval = if token.item.skip_conv {
+                eval = if rhs.percent && matches!(token.btn, Btn::BinOpt(Opt::Add | Opt::Sub)) {
+                    // Percentage of the left operand. Case: "200+10%" ~ "200+(200)*(10/100)"
+                    format!("{0}{1}({0})*{2}", operand1, token.item.eval, operand2)
+                } else if token.item.skip_conv {
                     // Binary notation. If we skip convertion.
                     format!("{}{}{}", operand1, token.item.eval, operand2)
                 } else {
@@ -336,6 +649,7 @@ impl ExprManager {
         top.item.skip_conv = token.item.skip_conv;
         top.item.priority = token.item.priority;
         top.arity = token.arity;
+        top.percent = token.btn == Btn::UnaryOpt(Opt::Percent);
 
         Ok(())
     }   // push_func()
@@ -357,7 +671,7 @@ impl ExprManager {
         for token in postfix {
             match token.btn {
                 // Non-operation tokens. Just push them onto the stack.
-                Btn::Num(_) | Btn::Comma | Btn::Const(_) | Btn::Random | Btn::Ans => {
+                Btn::Num(_) | Btn::Comma | Btn::Exp | Btn::Const(_) | Btn::Random | Btn::Ans => {
                     eval_stack.push((*token).clone())
                 }
                 // Operation tokens. This will pop the non-operation tokens (number depends on `token.arity`)
@@ -391,7 +705,9 @@ impl ExprManager {
 
         for token in tokens.iter_mut() {
             match token.btn {
-                Btn::Num(_) | Btn::Comma | Btn::Const(_) | Btn::Ans => postfix.push(token),
+                Btn::Num(_) | Btn::Comma | Btn::Exp | Btn::Const(_) | Btn::Ans => {
+                    postfix.push(token)
+                }
                 Btn::Random => {
                     token.btn = Btn::Const("random".to_string());
                     token.arity = 0;
@@ -414,6 +730,7 @@ impl ExprManager {
                     opt_stack.push(token);
                 }
                 Btn::UnaryOpt(_) | Btn::BracketLeft => opt_stack.push(token),
+                Btn::Placeholder => return Err("Operand is missing in the placeholder".into()),
                 Btn::BracketRight => {
                     // Pop all operators until the left bracket from the operator stack.
                     while opt_stack.last().is_some()
@@ -455,107 +772,319 @@ impl ExprManager {
         Ok(postfix)
     }
 
-    /// Tokenize the `btn_stack`.
+    /// Tokenize the `btn_stack`. Tokens of the unchanged prefix of the stack are reused
+    /// from the previous call, only the buttons from the first edited one are tokenized again.
     fn tokenize(&self) -> Vec<Token> {
-        let mut tokens: Vec<Token> = Vec::new();
-        for btn in &self.btn_stack {
-            let Some(btn_expr) = btn.to_expr() else {
-                continue;
-            };
+        let mut cache = self.token_cache.borrow_mut();
+        cache.update(&self.btn_stack);
+        cache.tokenizer.tokens.clone()
+    }
+} // ExprManager
+
+/// Converts buttons to tokens one by one, see `ExprManager::tokenize()`.
+#[derive(Debug, Clone)]
+struct Tokenizer {
+    tokens: Vec<Token>,
+    /// Number of absolute value bars, which are not closed yet.
+    open_bars: u32,
+    /// Flags if the last token is closing absolute value bar.
+    closed_abs: bool,
+}
+
+impl Tokenizer {
+    const fn new() -> Self {
+        Self {
+            tokens: Vec::new(),
+            open_bars: 0,
+            closed_abs: false,
+        }
+    }
+
+    /// Tokenize the `btn` following the already tokenized buttons.
+    fn push(&mut self, btn: &Btn) {
+        let Some(btn_expr) = btn.to_expr() else {
+            return;
+        };
+
+        // Absolute value bars are converted to `abs(` and `)`.
+        if let Btn::AbsBar = btn {
+            let after_operand = self.tokens.last().map_or(false, |tok| {
+                matches!(
+                    tok.btn,
+                    Btn::Num(_)
+                        | Btn::Comma
+                        | Btn::Const(_)
+                        | Btn::Ans
+                        | Btn::Random
+                        | Btn::UnaryOpt(Opt::Fact | Opt::Pow2 | Opt::Percent)
+                        | Btn::BracketRight
+                )
+            });
+
+            self.closed_abs = self.open_bars > 0 && after_operand;
+            if self.closed_abs {
+                self.open_bars -= 1;
+                self.tokens.push(Token::new(
+                    &Btn::BracketRight,
+                    Btn::BracketRight.to_expr().unwrap(),
+                    None,
+                ));
+                return;
+            }
+
+            // Case: "<operand>|" --> "2|-3|" ~ "2*abs(-3)"
+            if after_operand {
+                self.tokens.push(Token::new(
+                    &Btn::BinOpt(Opt::Mul),
+                    Opt::Mul.to_expr().unwrap(),
+                    Some(2),
+                ))
+            }
+
+            self.open_bars += 1;
+            self.tokens.push(Token::new(
+                &Btn::UnaryOpt(Opt::Abs),
+                Opt::Abs.to_expr().unwrap(),
+                Some(1),
+            ));
+            self.tokens.push(Token::new(
+                &Btn::BracketLeft,
+                Btn::BracketLeft.to_expr().unwrap(),
+                None,
+            ));
+            return;
+        }
+
+        // Case: "|<expr>|<operand>" --> "|-3|2" ~ "abs(-3)*2"
+        if self.closed_abs
+            && !matches!(
+                btn,
+                Btn::BinOpt(_)
+                    | Btn::BracketRight
+                    | Btn::UnaryOpt(Opt::Fact | Opt::Pow2 | Opt::Percent)
+            )
+        {
+            self.tokens.push(Token::new(
+                &Btn::BinOpt(Opt::Mul),
+                Opt::Mul.to_expr().unwrap(),
+                Some(2),
+            ))
+        }
+        self.closed_abs = false;
 
-            // Check for implicit multiplication sign. And add it if found.
-            // We need to explicitly handle unary operations (for now only Opt::Fact),
-            // that are on the right side of the operand.
-            match btn {
-                // Case: "<righ unary><number>" --> "5!2" ~ "5!*2"
-                Btn::Num(_) => {
-                    if let Some(tok) = tokens.last() {
-                        if let Btn::UnaryOpt(Opt::Fact | Opt::Pow2) = tok.btn {
-                            tokens.push(Token::new(
+        // Check for implicit multiplication sign. And add it if found.
+        // We need to explicitly handle unary operations (for now only Opt::Fact),
+        // that are on the right side of the operand.
+        match btn {
+            // Case: "<righ unary><number>" --> "5!2" ~ "5!*2"
+            Btn::Num(_) => {
+                if let Some(tok) = self.tokens.last() {
+                    if let Btn::UnaryOpt(Opt::Fact | Opt::Pow2 | Opt::Percent) = tok.btn {
+                        self.tokens.push(Token::new(
+                            &Btn::BinOpt(Opt::Mul),
+                            Opt::Mul.to_expr().unwrap(),
+                            Some(2),
+                        ))
+                    }
+                }
+            }
+            // Ignore right sided unary operations.
+            Btn::UnaryOpt(Opt::Fact | Opt::Pow2 | Opt::Percent) => {}
+            // Case: "<num|const|right unary><left unary|const|'('>" --> "5!sqrt3" ~ "5!*sqrt3"
+            Btn::UnaryOpt(_) | Btn::Const(_) | Btn::BracketLeft | Btn::Ans | Btn::Random => {
+                if let Some(tok) = self.tokens.last() {
+                    match tok.btn {
+                        Btn::Num(_)
+                        | Btn::Const(_)
+                        | Btn::UnaryOpt(Opt::Fact | Opt::Pow2 | Opt::Percent) => {
+                            self.tokens.push(Token::new(
                                 &Btn::BinOpt(Opt::Mul),
                                 Opt::Mul.to_expr().unwrap(),
                                 Some(2),
                             ))
                         }
+                        _ => {}
                     }
-                }
-                // Ignore right sided unary operations.
-                Btn::UnaryOpt(Opt::Fact | Opt::Pow2) => {}
-                // Case: "<num|const|right unary><left unary|const|'('>" --> "5!sqrt3" ~ "5!*sqrt3"
-                Btn::UnaryOpt(_) | Btn::Const(_) | Btn::BracketLeft | Btn::Ans | Btn::Random => {
-                    if let Some(tok) = tokens.last() {
-                        match tok.btn {
-                            Btn::Num(_) | Btn::Const(_) | Btn::UnaryOpt(Opt::Fact | Opt::Pow2) => {
-                                tokens.push(Token::new(
-                                    &Btn::BinOpt(Opt::Mul),
-                                    Opt::Mul.to_expr().unwrap(),
-                                    Some(2),
-                                ))
-                            }
-                            _ => {}
-                        }
-                    };
-                }
-                _ => {}
+                };
             }
+            _ => {}
+        }
 
-            match btn {
-                // Tokenize numbers. Group numbers next to each other into single token.
-                Btn::Num(num) => {
-                    match tokens.last_mut() {
-                        Some(tok) => match tok.btn {
-                            // If the previous token is number or comma, then we merge them together.
-                            Btn::Num(_) | Btn::Comma => tok.item.eval += &num.to_string(),
-                            // Previous number is not a number, so create new token for this one.
-                            _ => tokens.push(Token::new(btn, btn_expr, None)),
-                        },
-                        None => tokens.push(Token::new(btn, btn_expr, Some(0))),
-                    }
+        match btn {
+            // Tokenize numbers. Group numbers next to each other into single token.
+            Btn::Num(num) => {
+                match self.tokens.last_mut() {
+                    Some(tok) => match tok.btn {
+                        // If the previous token is number or comma, then we merge them together.
+                        Btn::Num(_) | Btn::Comma => tok.item.eval += &num.to_string(),
+                        // Previous number is not a number, so create new token for this one.
+                        _ => self.tokens.push(Token::new(btn, btn_expr, None)),
+                    },
+                    None => self.tokens.push(Token::new(btn, btn_expr, Some(0))),
                 }
-                // Tokenize comma. If next to number, then group it into single token.
-                Btn::Comma => {
-                    match tokens.last_mut() {
-                        Some(tok) => match tok.btn {
-                            // If the previous token is number or comma, then we merge them together.
-                            Btn::Num(_) | Btn::Comma => tok.item.eval += &btn_expr.eval,
-                            // Previous number is not a number, so create new token for this one.
-                            _ => tokens.push(Token::new(btn, btn_expr, None)),
-                        },
-                        None => tokens.push(Token::new(btn, btn_expr, Some(0))),
+            }
+            // Tokenize comma and exponent. If next to number, then group it into single token.
+            Btn::Comma | Btn::Exp => {
+                match self.tokens.last_mut() {
+                    Some(tok) => match tok.btn {
+                        // If the previous token is number or comma, then we merge them together.
+                        Btn::Num(_) | Btn::Comma => tok.item.eval += &btn_expr.eval,
+                        // Previous number is not a number, so create new token for this one.
+                        _ => self.tokens.push(Token::new(btn, btn_expr, None)),
+                    },
+                    None => self.tokens.push(Token::new(btn, btn_expr, Some(0))),
+                }
+            }
+            // Tokenize binary operation.
+            // Check for arity of '+' and '-' operators. These could actually
+            // be unary, based on the previous token.
+            Btn::BinOpt(Opt::Add | Opt::Sub) => {
+                // Sign of the exponent is a part of the number. Case: "3E-2"
+                if let Some(tok) = self.tokens.last_mut() {
+                    if matches!(tok.btn, Btn::Num(_) | Btn::Comma) && tok.item.eval.ends_with('e') {
+                        tok.item.eval += &btn_expr.eval;
+                        return;
                     }
                 }
-                // Tokenize binary operation.
-                // Check for arity of '+' and '-' operators. These could actually
-                // be unary, based on the previous token.
-                Btn::BinOpt(Opt::Add | Opt::Sub) => {
-                    let arity = match tokens.last() {
-                        Some(tok) => match tok.btn {
-                            // Case: "1*-3"
-                            Btn::BinOpt(_) => 1,
-                            // If previous token is right-sided unary operation,
-                            // then this is binary, as it has bigger priority.
-                            // Case: "sin 5!-3"
-                            Btn::UnaryOpt(Opt::Fact | Opt::Pow2) => 2,
-                            Btn::UnaryOpt(_) => { 1 },
-                            // Case: "2*(-3)"
-                            Btn::BracketLeft => 1,
-                            _ => 2,
-                        },
-                        None => 1,
-                    };
-                    tokens.push(Token::new(btn, btn_expr, Some(arity)));
-                },
-                Btn::BinOpt(_) => tokens.push(Token::new(btn, btn_expr, Some(2))),
-                Btn::UnaryOpt(_) => tokens.push(Token::new(btn, btn_expr, Some(1))),
-                _ => tokens.push(Token::new(btn, btn_expr, None)),
-            };
-        } // for btn in tokens
-        tokens
-    } // tokenize()
-} // ExprManager
+
+                let arity = match self.tokens.last() {
+                    Some(tok) => match tok.btn {
+                        // Case: "1*-3"
+                        Btn::BinOpt(_) => 1,
+                        // If previous token is right-sided unary operation,
+                        // then this is binary, as it has bigger priority.
+                        // Case: "sin 5!-3"
+                        Btn::UnaryOpt(Opt::Fact | Opt::Pow2 | Opt::Percent) => 2,
+                        Btn::UnaryOpt(_) => 1,
+                        // Case: "2*(-3)"
+                        Btn::BracketLeft => 1,
+                        _ => 2,
+                    },
+                    None => 1,
+                };
+                self.tokens.push(Token::new(btn, btn_expr, Some(arity)));
+            }
+            Btn::BinOpt(_) => self.tokens.push(Token::new(btn, btn_expr, Some(2))),
+            Btn::UnaryOpt(_) => self.tokens.push(Token::new(btn, btn_expr, Some(1))),
+            _ => self.tokens.push(Token::new(btn, btn_expr, None)),
+        };
+    }
+
+    /// Remember the state, so the tokenization can continue from here.
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            len: self.tokens.len(),
+            last: self.tokens.last().cloned(),
+            open_bars: self.open_bars,
+            closed_abs: self.closed_abs,
+        }
+    }
+
+    /// Return to the state of the `checkpoint`, dropping the tokens added after it.
+    fn restore(&mut self, checkpoint: &Checkpoint) {
+        self.tokens.truncate(checkpoint.len);
+        // Numbers are grouped into the last token, so it could have been changed.
+        if let (Some(tok), Some(last)) = (self.tokens.last_mut(), &checkpoint.last) {
+            *tok = last.clone();
+        }
+        self.open_bars = checkpoint.open_bars;
+        self.closed_abs = checkpoint.closed_abs;
+    }
+}
+
+/// State of the `Tokenizer` after a button.
+#[derive(Debug, Clone)]
+struct Checkpoint {
+    len: usize,
+    last: Option<Token>,
+    open_bars: u32,
+    closed_abs: bool,
+}
+
+/// Tokens of the last tokenized buttons with a checkpoint after each button.
+#[derive(Debug, Clone)]
+struct TokenCache {
+    btns: Vec<Btn>,
+    checkpoints: Vec<Checkpoint>,
+    tokenizer: Tokenizer,
+}
+
+impl TokenCache {
+    const fn new() -> Self {
+        Self {
+            btns: Vec::new(),
+            checkpoints: Vec::new(),
+            tokenizer: Tokenizer::new(),
+        }
+    }
+
+    /// Tokenize `btns`, starting from the first button, which differs from the cached ones.
+    fn update(&mut self, btns: &[Btn]) {
+        let unchanged = self
+            .btns
+            .iter()
+            .zip(btns)
+            .take_while(|(cached, btn)| cached == btn)
+            .count();
+
+        self.btns.truncate(unchanged);
+        self.checkpoints.truncate(unchanged);
+        match self.checkpoints.last() {
+            Some(checkpoint) => self.tokenizer.restore(checkpoint),
+            None => self.tokenizer = Tokenizer::new(),
+        }
+
+        for btn in &btns[unchanged..] {
+            self.tokenizer.push(btn);
+            self.btns.push(btn.clone());
+            self.checkpoints.push(self.tokenizer.checkpoint());
+        }
+    }
+}
+
+/// Operation entered in guided way, see `ExprManager::insert_guided()`.
+fn is_guided(btn: &Btn) -> bool {
+    matches!(btn, Btn::BinOpt(Opt::Root | Opt::Pow))
+}
+
+/// Check if `btn` can be the last button of an operand.
+fn ends_operand(btn: &Btn) -> bool {
+    matches!(
+        btn,
+        Btn::Num(_)
+            | Btn::Comma
+            | Btn::Const(_)
+            | Btn::Ans
+            | Btn::Random
+            | Btn::Placeholder
+            | Btn::AbsBar
+            | Btn::BracketRight
+            | Btn::UnaryOpt(Opt::Fact | Opt::Pow2 | Opt::Percent)
+    )
+}
+
+/// Check if `btn` can be the first button of an operand.
+fn starts_operand(btn: &Btn) -> bool {
+    match btn {
+        Btn::UnaryOpt(opt) => !matches!(opt, Opt::Fact | Opt::Pow2 | Opt::Percent),
+        // Sign of the operand.
+        Btn::BinOpt(opt) => matches!(opt, Opt::Add | Opt::Sub),
+        _ => matches!(
+            btn,
+            Btn::Num(_)
+                | Btn::Comma
+                | Btn::Const(_)
+                | Btn::Ans
+                | Btn::Random
+                | Btn::Placeholder
+                | Btn::AbsBar
+                | Btn::BracketLeft
+        ),
+    }
+}
 
 /// Reprezents a single token in expression string.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 struct Token {
     /// When the btn is number, then a single token,
     /// could be composed of multiple buttons (such
@@ -567,6 +1096,8 @@ struct Token {
     item: ExprItem,
     /// Arity of the `btn` operation.
     arity: u32,
+    /// Flags if the compound operation is a percentage, see `Opt::Percent`.
+    percent: bool,
 }
 
 impl Token {
@@ -583,6 +1114,7 @@ impl Token {
             btn: btn.to_owned(),
             item,
             arity: arity.unwrap_or(0),
+            percent: false,
         }
     }
 }
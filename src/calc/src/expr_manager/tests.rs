@@ -1,8 +1,8 @@
 //! Define internal tests for the calculator state.
 
-use crate::Opt;
+use crate::{MulSymbol, Opt};
 
-use super::{Btn, ExprManager, ToExpr};
+use super::{Btn, ExprManager, ToExpr, Token, Tokenizer};
 
 // Wrapper for testing the get_eval_str() method.
 fn convert(btn_stack: Vec<Btn>) -> String {
@@ -11,6 +11,20 @@ fn convert(btn_stack: Vec<Btn>) -> String {
     expr_man.get_eval_str().unwrap()
 }
 
+// Wrapper for testing the get_postfix_debug() method.
+fn postfix(btn_stack: Vec<Btn>) -> Vec<String> {
+    let mut expr_man = ExprManager::new();
+    expr_man.btn_stack = btn_stack;
+    expr_man.get_postfix_debug().unwrap()
+}
+
+// Wrapper for testing the is_evaluable() method.
+fn evaluable(btn_stack: Vec<Btn>) -> bool {
+    let mut expr_man = ExprManager::new();
+    expr_man.btn_stack = btn_stack;
+    expr_man.is_evaluable(&mut math::Calculator::new())
+}
+
 // Convert given string to sequence of `PressedButton` enums.
 // All `@` characters will be replaced with `rep`.
 fn to_opt_seq(str: &str, rep: Vec<&Opt>) -> Vec<Btn> {
@@ -43,8 +57,10 @@ fn to_opt_seq(str: &str, rep: Vec<&Opt>) -> Vec<Btn> {
             '*' => Btn::BinOpt(Opt::Mul),
             '/' => Btn::BinOpt(Opt::Div),
             '!' => Btn::UnaryOpt(Opt::Fact),
+            '%' => Btn::UnaryOpt(Opt::Percent),
             '(' => Btn::BracketLeft,
             ')' => Btn::BracketRight,
+            '|' => Btn::AbsBar,
             '.' => Btn::Comma,
             '@' => {
                 n_rep += 1;
@@ -311,3 +327,431 @@ fn convert_arccotg() {
 fn convert_comb() {
     unary_opt_template(&Opt::Comb, &Opt::Comb)
 }
+
+#[test]
+fn convert_perm() {
+    unary_opt_template(&Opt::Perm, &Opt::Perm)
+}
+
+#[test]
+fn evaluable_complete() {
+    assert!(evaluable(to_opt_seq("1+2", Vec::new())));
+    assert!(evaluable(to_opt_seq("-(1.5*2)", Vec::new())));
+    assert!(evaluable(to_opt_seq("4-$pi$", Vec::new())));
+    assert!(evaluable(to_opt_seq("&9", Vec::from([&Opt::Sqrt]))));
+}
+
+#[test]
+fn evaluable_incomplete() {
+    assert!(!evaluable(Vec::new()));
+    assert!(!evaluable(to_opt_seq("1+", Vec::new())));
+    assert!(!evaluable(to_opt_seq("(1+2", Vec::new())));
+    assert!(!evaluable(to_opt_seq("1+2)", Vec::new())));
+    assert!(!evaluable(to_opt_seq("$unknown$", Vec::new())));
+}
+
+#[test]
+fn matching_bracket_nested() {
+    let mut expr_man = ExprManager::new();
+    // (1+(2))
+    expr_man.btn_stack = to_opt_seq("(1+(2))", Vec::new());
+    assert_eq!(expr_man.matching_bracket(0), Some(6));
+    assert_eq!(expr_man.matching_bracket(6), Some(0));
+    assert_eq!(expr_man.matching_bracket(3), Some(5));
+    assert_eq!(expr_man.matching_bracket(5), Some(3));
+    // Not a bracket.
+    assert_eq!(expr_man.matching_bracket(1), None);
+    // Out of range.
+    assert_eq!(expr_man.matching_bracket(7), None);
+}
+
+#[test]
+fn matching_bracket_unmatched() {
+    let mut expr_man = ExprManager::new();
+    expr_man.btn_stack = to_opt_seq("((1)", Vec::new());
+    assert_eq!(expr_man.matching_bracket(0), None);
+    assert_eq!(expr_man.matching_bracket(1), Some(3));
+
+    expr_man.btn_stack = to_opt_seq("1)+(2", Vec::new());
+    assert_eq!(expr_man.matching_bracket(1), None);
+    assert_eq!(expr_man.matching_bracket(3), None);
+}
+
+#[test]
+fn display_range() {
+    let mut expr_man = ExprManager::new();
+    expr_man.btn_stack = to_opt_seq("(1*2)", Vec::new());
+    expr_man.cursor_pos = 2;
+
+    let disp = expr_man.get_display_str(true);
+    assert_eq!(&disp[expr_man.display_range(0, true).unwrap()], "(");
+    assert_eq!(&disp[expr_man.display_range(2, true).unwrap()], "⋅");
+    assert_eq!(&disp[expr_man.display_range(4, true).unwrap()], ")");
+    assert_eq!(expr_man.display_range(5, true), None);
+
+    let disp = expr_man.get_display_str(false);
+    assert_eq!(&disp[expr_man.display_range(4, false).unwrap()], ")");
+}
+
+#[test]
+fn mul_symbol() {
+    let mut expr_man = ExprManager::new();
+    expr_man.btn_stack = to_opt_seq("2*3*4", Vec::new());
+    assert_eq!(expr_man.get_display_str(false), "2⋅3⋅4");
+
+    expr_man.set_mul_symbol(MulSymbol::Cross);
+    assert_eq!(expr_man.get_display_str(false), "2×3×4");
+    assert_eq!(
+        &expr_man.get_display_str(false)[expr_man.display_range(1, false).unwrap()],
+        "×"
+    );
+
+    expr_man.set_mul_symbol(MulSymbol::Star);
+    assert_eq!(expr_man.get_display_str(false), "2*3*4");
+    assert_eq!(expr_man.get_eval_str().unwrap(), "2*3*4");
+}
+
+#[test]
+fn convert_abs_bars() {
+    assert_eq!(convert(to_opt_seq("|-3|", vec![])), "abs(-3)");
+    assert_eq!(convert(to_opt_seq("2|-3|", vec![])), "2*abs(-3)");
+    assert_eq!(convert(to_opt_seq("|-3||4|", vec![])), "abs(-3)*abs(4)");
+    assert_eq!(convert(to_opt_seq("|-3|2", vec![])), "abs(-3)*2");
+    assert_eq!(convert(to_opt_seq("||-3|-5|", vec![])), "abs(abs(-3)-5)");
+    assert_eq!(convert(to_opt_seq("1-|2-5|!", vec![])), "1-abs(2-5)!");
+    assert!(evaluable(to_opt_seq("2|-3|", vec![])));
+    assert!(!evaluable(to_opt_seq("2|-3", vec![])));
+}
+
+#[test]
+fn postfix_order() {
+    assert_eq!(postfix(vec![]), Vec::<String>::new());
+    assert_eq!(
+        postfix(to_opt_seq("1+2*3", vec![])),
+        ["1", "2", "3", "*", "+"]
+    );
+    assert_eq!(
+        postfix(to_opt_seq("(1+2)*3", vec![])),
+        ["1", "2", "+", "3", "*"]
+    );
+    assert_eq!(
+        postfix(to_opt_seq("2*3@8", vec![&Opt::Root])),
+        ["2", "3", "8", "root", "*"]
+    );
+    assert_eq!(
+        postfix(to_opt_seq("12.5-3!", vec![])),
+        ["12.5", "3", "!", "-"]
+    );
+}
+
+// Process the buttons one by one, as if they were pressed.
+fn press(btns: &[Btn]) -> ExprManager {
+    let mut expr_man = ExprManager::new();
+    for btn in btns {
+        expr_man.process_button(btn);
+    }
+    expr_man
+}
+
+#[test]
+fn guided_root_entry() {
+    let root = Btn::BinOpt(Opt::Root);
+
+    let mut expr_man = press(&[root.clone()]);
+    assert_eq!(expr_man.get_display_str(false), "▯ⁿ√▯");
+    assert_eq!(expr_man.cursor_pos(), 0);
+    assert!(expr_man.get_eval_str().is_err());
+    assert!(!expr_man.is_evaluable(&mut math::Calculator::new()));
+
+    // Index first, then the radicand.
+    expr_man.process_button(&Btn::Num(3));
+    assert_eq!(expr_man.get_display_str(false), "3ⁿ√▯");
+    expr_man.process_button(&Btn::MoveRight);
+    expr_man.process_button(&Btn::Num(2));
+    expr_man.process_button(&Btn::Num(7));
+    assert_eq!(expr_man.get_display_str(false), "3ⁿ√27");
+    assert_eq!(expr_man.get_eval_str().unwrap(), "root(3,27)");
+
+    // Operand before the cursor is the index.
+    let expr_man = press(&[Btn::Num(4), root.clone(), Btn::Num(1), Btn::Num(6)]);
+    assert_eq!(expr_man.get_eval_str().unwrap(), "root(4,16)");
+
+    // Placeholders of a nested operation.
+    let expr_man = press(&[
+        Btn::Num(1),
+        Btn::BinOpt(Opt::Add),
+        root.clone(),
+        Btn::Num(2),
+        Btn::MoveRight,
+        Btn::BracketLeft,
+        Btn::Num(9),
+        Btn::BracketRight,
+    ]);
+    assert_eq!(expr_man.get_display_str(false), "1+2ⁿ√(9)");
+    assert_eq!(expr_man.get_eval_str().unwrap(), "1+root(2,9)");
+}
+
+#[test]
+fn guided_pow_entry() {
+    let pow = Btn::BinOpt(Opt::Pow);
+
+    let expr_man = press(&[Btn::Num(2), pow.clone()]);
+    assert_eq!(expr_man.get_display_str(false), "2^▯");
+    assert_eq!(expr_man.cursor_pos(), 2);
+
+    let expr_man = press(&[
+        pow.clone(),
+        Btn::Num(2),
+        Btn::MoveRight,
+        Btn::BinOpt(Opt::Sub),
+        Btn::Num(3),
+    ]);
+    assert_eq!(expr_man.get_display_str(false), "2^-3");
+    assert_eq!(expr_man.get_eval_str().unwrap(), "2^-3");
+
+    // Existing operands are used as they are.
+    let mut expr_man = press(&[Btn::Num(2), Btn::Num(3), Btn::MoveLeft]);
+    expr_man.process_button(&pow);
+    assert_eq!(expr_man.get_display_str(false), "2^3");
+
+    // Only an operand fills the placeholder.
+    let expr_man = press(&[Btn::Num(2), pow.clone(), Btn::UnaryOpt(Opt::Fact)]);
+    assert_eq!(expr_man.get_display_str(false), "2^!▯");
+    assert_eq!(expr_man.cursor_pos(), 3);
+}
+
+#[test]
+fn guided_entry_delete() {
+    let root = Btn::BinOpt(Opt::Root);
+
+    // Deleting the operation deletes its placeholders.
+    let mut expr_man = press(&[root.clone(), Btn::MoveRight, Btn::MoveRight]);
+    expr_man.process_button(&Btn::Delete);
+    assert_eq!(expr_man.get_display_str(false), "0");
+    assert_eq!(expr_man.cursor_pos(), 0);
+
+    // Placeholder itself can't be deleted.
+    let mut expr_man = press(&[root.clone(), Btn::MoveRight]);
+    expr_man.process_button(&Btn::Delete);
+    assert_eq!(expr_man.get_display_str(false), "▯ⁿ√▯");
+    assert_eq!(expr_man.cursor_pos(), 0);
+
+    let mut expr_man = press(&[Btn::Num(3), root.clone()]);
+    expr_man.process_button(&Btn::Delete);
+    assert_eq!(expr_man.get_display_str(false), "3");
+    assert_eq!(expr_man.cursor_pos(), 1);
+}
+
+#[test]
+fn max_length() {
+    let mut expr_man = ExprManager::new().with_max_len(3);
+    for num in 1..=3 {
+        assert!(expr_man.process_button(&Btn::Num(num)));
+    }
+
+    // Inserts beyond the limit are ignored.
+    assert!(!expr_man.process_button(&Btn::Num(4)));
+    assert!(!expr_man.process_button(&Btn::BinOpt(Opt::Pow)));
+    assert_eq!(expr_man.get_display_str(false), "123");
+    assert_eq!(expr_man.cursor_pos(), 3);
+
+    // Editing is still possible.
+    assert!(expr_man.process_button(&Btn::Delete));
+    assert!(expr_man.process_button(&Btn::MoveLeft));
+    assert!(expr_man.process_button(&Btn::Num(0)));
+    assert_eq!(expr_man.get_display_str(false), "102");
+
+    // Guided operation has to fit with all its placeholders.
+    let mut expr_man = ExprManager::new().with_max_len(3);
+    expr_man.process_button(&Btn::Num(2));
+    assert!(expr_man.process_button(&Btn::BinOpt(Opt::Pow)));
+    assert_eq!(expr_man.get_display_str(false), "2^▯");
+    // Filling the placeholder doesn't make the expression longer.
+    assert!(expr_man.process_button(&Btn::Num(3)));
+    assert_eq!(expr_man.get_display_str(false), "2^3");
+
+    let mut expr_man = ExprManager::new().with_max_len(2);
+    assert!(!expr_man.process_button(&Btn::BinOpt(Opt::Root)));
+    assert_eq!(expr_man.get_display_str(false), "0");
+}
+
+// Tokens of the whole `btns` without the cache.
+fn tokenize_uncached(btns: &[Btn]) -> Vec<Token> {
+    let mut tokenizer = Tokenizer::new();
+    for btn in btns {
+        tokenizer.push(btn);
+    }
+    tokenizer.tokens
+}
+
+// Press `btns` one by one and compare the cached tokens with the full tokenization.
+fn edit_and_compare(expr_man: &mut ExprManager, btns: &[Btn]) {
+    for btn in btns {
+        expr_man.process_button(btn);
+        assert_eq!(
+            expr_man.tokenize(),
+            tokenize_uncached(&expr_man.btn_stack),
+            "{}",
+            expr_man.get_display_str(true)
+        );
+    }
+}
+
+#[test]
+fn cached_tokenization() {
+    let mut expr_man = ExprManager::new();
+    let mut check = |btns: &[Btn]| edit_and_compare(&mut expr_man, btns);
+
+    // Typing at the end.
+    check(&to_opt_seq("12.5+|3-4|!*(2)", Vec::new()));
+    // Editing the middle of a number, inside and around the absolute value.
+    check(&vec![Btn::MoveLeft; 9]);
+    check(&[
+        Btn::Num(7),
+        Btn::Delete,
+        Btn::Delete,
+        Btn::AbsBar,
+        Btn::Num(9),
+    ]);
+    check(&vec![Btn::MoveLeft; 6]);
+    check(&[Btn::Comma, Btn::Num(3), Btn::BinOpt(Opt::Sub), Btn::Delete]);
+    // Editing the beginning.
+    check(&vec![Btn::MoveLeft; 20]);
+    check(&[
+        Btn::BinOpt(Opt::Sub),
+        Btn::Num(1),
+        Btn::Const("pi".to_string()),
+    ]);
+    check(&vec![Btn::MoveRight; 4]);
+    check(&[Btn::Delete, Btn::Delete, Btn::Num(0)]);
+
+    // Converted expression stays the same as with a fresh manager.
+    let mut fresh = ExprManager::new();
+    fresh.btn_stack = expr_man.btn_stack.clone();
+    assert_eq!(
+        expr_man.get_eval_str().unwrap(),
+        fresh.get_eval_str().unwrap()
+    );
+
+    edit_and_compare(
+        &mut expr_man,
+        &[Btn::Clear, Btn::Num(4), Btn::UnaryOpt(Opt::Fact)],
+    );
+}
+
+#[test]
+fn scientific_notation() {
+    let exp = |mantissa: &[Btn], exponent: &[Btn]| {
+        let mut btns = mantissa.to_vec();
+        btns.push(Btn::Exp);
+        btns.extend_from_slice(exponent);
+        press(&btns)
+    };
+
+    let expr_man = exp(&[Btn::Num(3)], &[Btn::Num(2)]);
+    assert_eq!(expr_man.get_display_str(false), "3E2");
+    assert_eq!(expr_man.get_eval_str().unwrap(), "3e2");
+    assert_eq!(
+        math::evaluate(&expr_man.get_eval_str().unwrap()),
+        Ok(math::Number::from(300))
+    );
+
+    let expr_man = exp(
+        &[Btn::Num(2), Btn::Comma, Btn::Num(5)],
+        &[
+            Btn::BinOpt(Opt::Sub),
+            Btn::Num(2),
+            Btn::BinOpt(Opt::Mul),
+            Btn::Num(4),
+        ],
+    );
+    assert_eq!(expr_man.get_eval_str().unwrap(), "2.5e-2*4");
+    assert_eq!(
+        math::evaluate(&expr_man.get_eval_str().unwrap()),
+        Ok(math::Number::new(1, 10).unwrap())
+    );
+
+    // Second exponent of the same number is ignored.
+    let expr_man = exp(&[Btn::Num(3)], &[Btn::Num(2), Btn::Exp, Btn::Num(1)]);
+    assert_eq!(expr_man.get_display_str(false), "3E21");
+    let mut expr_man = exp(&[Btn::Num(3)], &[Btn::Exp]);
+    assert_eq!(expr_man.get_display_str(false), "3E");
+    assert!(!expr_man.is_evaluable(&mut math::Calculator::new()));
+    expr_man.process_button(&Btn::MoveLeft);
+    expr_man.process_button(&Btn::MoveLeft);
+    expr_man.process_button(&Btn::Exp);
+    assert_eq!(expr_man.get_display_str(false), "3E");
+
+    // Exponent needs a mantissa.
+    assert_eq!(press(&[Btn::Exp]).get_display_str(false), "0");
+    let expr_man = press(&[Btn::Num(2), Btn::BinOpt(Opt::Add), Btn::Exp]);
+    assert_eq!(expr_man.get_display_str(false), "2+");
+}
+
+#[test]
+fn percent() {
+    let eval = |expr: &str| {
+        let eval_str = convert(to_opt_seq(expr, Vec::new()));
+        math::evaluate(&eval_str).unwrap()
+    };
+
+    // Standalone percent divides by hundred.
+    assert_eq!(convert(to_opt_seq("50%", Vec::new())), "(50/100)");
+    assert_eq!(eval("50%"), math::Number::new(1, 2).unwrap());
+
+    // Percent after addition or subtraction is taken from the left operand.
+    assert_eq!(
+        convert(to_opt_seq("200+10%", Vec::new())),
+        "200+(200)*(10/100)"
+    );
+    assert_eq!(eval("200+10%"), math::Number::from(220));
+    assert_eq!(eval("200-10%"), math::Number::from(180));
+    assert_eq!(eval("100+50-10%"), math::Number::from(135));
+
+    // Other operations use the plain fraction.
+    assert_eq!(eval("200*10%"), math::Number::from(20));
+    assert_eq!(eval("5/10%"), math::Number::from(50));
+    assert_eq!(eval("10%2"), math::Number::new(1, 5).unwrap());
+
+    // The `%` button is inserted as the operation.
+    let expr_man = press(&[Btn::Num(5), Btn::Percent]);
+    assert_eq!(
+        expr_man.btn_stack,
+        [Btn::Num(5), Btn::UnaryOpt(Opt::Percent)]
+    );
+    assert_eq!(expr_man.get_display_str(false), "5%");
+}
+
+#[test]
+fn undo_redo() {
+    let mut expr_man = press(&to_opt_seq("12+3", Vec::new()));
+    let undo = |expr_man: &mut ExprManager, times| {
+        for _ in 0..times {
+            expr_man.process_button(&Btn::Undo);
+        }
+        expr_man.get_display_str(false)
+    };
+
+    assert_eq!(undo(&mut expr_man, 2), "12");
+    assert_eq!(expr_man.cursor_pos(), 2);
+    expr_man.process_button(&Btn::Redo);
+    assert_eq!(expr_man.get_display_str(false), "12+");
+
+    // Moving the cursor isn't undone, the cursor is restored with the edit.
+    expr_man.process_button(&Btn::MoveLeft);
+    expr_man.process_button(&Btn::Delete);
+    assert_eq!(expr_man.get_display_str(false), "1+");
+    assert_eq!(undo(&mut expr_man, 1), "12+");
+    assert_eq!(expr_man.cursor_pos(), 2);
+
+    // New edit drops the undone ones.
+    expr_man.process_button(&Btn::Num(5));
+    expr_man.process_button(&Btn::Redo);
+    assert_eq!(expr_man.get_display_str(false), "125+");
+
+    // Only the last edits are remembered.
+    let mut expr_man = press(&vec![Btn::Num(1); super::UNDO_LIMIT + 10]);
+    assert_eq!(undo(&mut expr_man, super::UNDO_LIMIT + 10), "1".repeat(10));
+    expr_man.process_button(&Btn::Clear);
+    assert_eq!(undo(&mut expr_man, 1), "1".repeat(10));
+}
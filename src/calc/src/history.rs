@@ -1,5 +1,11 @@
 use druid::{Data, WindowId};
-use serde::{Deserialize, Serialize};
+use math::Number;
+use serde::{de::IntoDeserializer, Deserialize, Serialize};
+
+use crate::PressedButton;
+
+/// How many results can be selected for the comparison.
+const MAX_SELECTED: usize = 2;
 
 /// Contains data about all calculations as well as essential informations
 /// about status of the History window
@@ -7,6 +13,10 @@ use serde::{Deserialize, Serialize};
 pub struct History {
     /// calculation history
     pub data: Vec<(String, String)>,
+    /// Buttons of the expressions in `data`, encoded by `encode_button()`. Entries saved
+    /// before the buttons were stored have no buttons.
+    #[serde(default)]
+    buttons: Vec<Vec<String>>,
     /// indicates whether history recording is enabled or not
     record_history: bool,
 
@@ -16,11 +26,14 @@ pub struct History {
     window_id: Vec<WindowId>,
     #[serde(default, skip)]
     pub confiming_deletition: bool,
+    /// Indices of the entries selected for the comparison, in the order of selection.
+    #[serde(default, skip)]
+    selected: Vec<usize>,
 }
 
 impl Data for History {
     fn same(&self, other: &Self) -> bool {
-        self.data == other.data
+        self.data == other.data && self.buttons == other.buttons && self.selected == other.selected
     }
 }
 
@@ -28,10 +41,12 @@ impl Default for History {
     fn default() -> Self {
         Self {
             data: Vec::new(),
+            buttons: Vec::new(),
             record_history: true,
             is_opened: false,
             window_id: Vec::new(),
             confiming_deletition: false,
+            selected: Vec::new(),
         }
     }
 }
@@ -74,8 +89,202 @@ impl History {
         &self.data
     }
 
+    /// Add the expression, its result and the buttons composing the expression.
+    pub fn push(&mut self, expr: String, result: String, buttons: &[PressedButton]) {
+        // Align the buttons with the entries, which were saved without them.
+        self.buttons.resize(self.data.len(), Vec::new());
+        self.data.push((expr, result));
+        self.buttons
+            .push(buttons.iter().map(encode_button).collect());
+    }
+
+    /// Buttons of the expression at `idx`, `None` if they weren't saved.
+    pub fn buttons(&self, idx: usize) -> Option<Vec<PressedButton>> {
+        let encoded = self.buttons.get(idx).filter(|btns| !btns.is_empty())?;
+        encoded.iter().map(|btn| decode_button(btn)).collect()
+    }
+
     /// Clear all history data
     pub fn clear(&mut self) {
         self.data.clear();
+        self.buttons.clear();
+        self.selected.clear();
+    }
+
+    /// Select the entry at `idx` for the comparison, or unselect it if it's already selected.
+    /// Selecting a third entry unselects the first one.
+    pub fn toggle_selection(&mut self, idx: usize) {
+        if let Some(pos) = self.selected.iter().position(|&selected| selected == idx) {
+            self.selected.remove(pos);
+            return;
+        }
+
+        if self.selected.len() == MAX_SELECTED {
+            self.selected.remove(0);
+        }
+        self.selected.push(idx);
+    }
+
+    /// Indices of the selected entries, the first one is `A` and the second one `B`.
+    pub fn selected(&self) -> &[usize] {
+        &self.selected
+    }
+
+    /// Difference and ratio of the two selected results, empty if two results aren't selected.
+    pub fn comparison(&self) -> Vec<String> {
+        let results = self
+            .selected
+            .iter()
+            .filter_map(|&idx| self.data.get(idx))
+            .map(|(_, res)| res.as_str())
+            .collect::<Vec<_>>();
+
+        match results[..] {
+            [a, b] => compare_results(a, b),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Parse the result as it was displayed. Only decimal numbers, fractions and percentages
+/// are supported, results in other radixes can't be told apart from the decimal ones.
+fn parse_result(result: &str) -> Option<Number> {
+    match result.strip_suffix('%') {
+        Some(percent) => percent
+            .parse::<Number>()
+            .ok()
+            .map(|num| num.shift_radix(math::number::Radix::Dec, -2)),
+        None => result.parse().ok(),
+    }
+}
+
+/// Encode the button as `Variant` or `Variant:argument`, e.g. `BinOpt:Add`. The config
+/// format can't store enum variants with data, so buttons are saved as strings.
+fn encode_button(btn: &PressedButton) -> String {
+    match btn {
+        PressedButton::Num(num) => format!("Num:{num}"),
+        PressedButton::BinOpt(opt) => format!("BinOpt:{opt:?}"),
+        PressedButton::UnaryOpt(opt) => format!("UnaryOpt:{opt:?}"),
+        PressedButton::Const(name) => format!("Const:{name}"),
+        // Debug name of the unit variant is the same as its serde name.
+        other => format!("{other:?}"),
+    }
+}
+
+/// Decode the button encoded by `encode_button()`.
+fn decode_button(encoded: &str) -> Option<PressedButton> {
+    Some(match encoded.split_once(':') {
+        Some(("Num", num)) => PressedButton::Num(num.parse().ok()?),
+        Some(("BinOpt", opt)) => PressedButton::BinOpt(from_name(opt)?),
+        Some(("UnaryOpt", opt)) => PressedButton::UnaryOpt(from_name(opt)?),
+        Some(("Const", name)) => PressedButton::Const(name.to_owned()),
+        Some(_) => return None,
+        None => from_name(encoded)?,
+    })
+}
+
+/// Unit variant of the enum `T` named `name`.
+fn from_name<'de, T: Deserialize<'de>>(name: &'de str) -> Option<T> {
+    let deserializer: serde::de::value::StrDeserializer<serde::de::value::Error> =
+        name.into_deserializer();
+    T::deserialize(deserializer).ok()
+}
+
+/// Lines `A - B = ...` and `A / B = ...` comparing the results `a` and `b`.
+fn compare_results(a: &str, b: &str) -> Vec<String> {
+    let (Some(a), Some(b)) = (parse_result(a), parse_result(b)) else {
+        return vec![String::from("A, B: Invalid number")];
+    };
+
+    let show = |res: math::Result<Number>| match res {
+        Ok(num) => num.to_string(),
+        Err(err) => err.to_string(),
+    };
+
+    vec![
+        format!("A - B = {}", show(a.sub(&b))),
+        format!("A / B = {}", show(a.div(&b))),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_two_results() {
+        assert_eq!(compare_results("3.5", "1/2"), ["A - B = 3", "A / B = 7"]);
+        assert_eq!(
+            compare_results("-1", "3"),
+            ["A - B = -4", "A / B = -0.333333333333"]
+        );
+        assert_eq!(
+            compare_results("50%", "0.25"),
+            ["A - B = 0.25", "A / B = 2"]
+        );
+        assert_eq!(
+            compare_results("1", "0"),
+            ["A - B = 1", "A / B = Division zero"]
+        );
+        assert_eq!(compare_results("Error", "1"), ["A, B: Invalid number"]);
+    }
+
+    #[test]
+    fn select_two_entries() {
+        let mut history = History {
+            data: ["1", "2", "3"]
+                .map(|res| (res.to_owned(), res.to_owned()))
+                .to_vec(),
+            ..Default::default()
+        };
+
+        history.toggle_selection(0);
+        assert!(history.comparison().is_empty());
+        history.toggle_selection(2);
+        assert_eq!(history.selected(), [0, 2]);
+        assert_eq!(
+            history.comparison(),
+            ["A - B = -2", "A / B = 0.333333333333"]
+        );
+
+        // The oldest selection is replaced
+        history.toggle_selection(1);
+        assert_eq!(history.selected(), [2, 1]);
+        assert_eq!(history.comparison()[0], "A - B = 1");
+
+        history.toggle_selection(2);
+        assert_eq!(history.selected(), [1]);
+
+        history.clear();
+        assert!(history.selected().is_empty());
+    }
+
+    #[test]
+    fn reload_buttons() {
+        use crate::Opt;
+
+        // Entry saved before the buttons were stored.
+        let mut history = History {
+            data: vec![("1".to_owned(), "1".to_owned())],
+            ..Default::default()
+        };
+        let buttons = [
+            PressedButton::Num(12),
+            PressedButton::BinOpt(Opt::Add),
+            PressedButton::UnaryOpt(Opt::Sqrt),
+            PressedButton::BracketLeft,
+            PressedButton::Const("my_const".to_owned()),
+            PressedButton::BracketRight,
+            PressedButton::Comma,
+        ];
+        history.push("C+√(my_const),".to_owned(), "Error".to_owned(), &buttons);
+
+        assert_eq!(history.buttons(0), None);
+        assert_eq!(history.buttons(1).as_deref(), Some(&buttons[..]));
+        assert_eq!(history.buttons(2), None);
+        assert_eq!(decode_button("BinOpt:Nope"), None);
+
+        history.clear();
+        assert_eq!(history.buttons(1), None);
     }
 }
@@ -11,7 +11,9 @@ pub mod widgets;
 use druid::{Application, Data, Lens, WindowId};
 use expr_manager::ExprManager;
 use history::History;
-use math::{number::Radix, Number};
+use math::number::{FormattedNumber, Radix};
+use math::Number;
+use rust_i18n::t;
 use serde::{Deserialize, Serialize};
 use std::{cell::RefCell, fmt, rc::Rc};
 
@@ -19,12 +21,18 @@ pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 const APP_NAME: &str = "Calculator";
 
+/// Language used when neither the requested language nor its base language is available.
+const FALLBACK_LANGUAGE: &str = "en";
+
+/// Name of the constant holding the value of the memory register.
+const MEMORY_CONSTANT: &str = "mem";
+
 // Initialize locales in "locales" directory.
 rust_i18n::i18n!("locales");
 
 /// Operations on the calculator.
 #[rustfmt::skip]
-#[derive(Hash, PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(Hash, PartialEq, Eq, Debug, Clone, Copy, Deserialize)]
 pub enum Opt {
     Add, Sub, Mul, Div,
     Sin, Cos, Tg, Cotg,
@@ -34,14 +42,18 @@ pub enum Opt {
     Pow2,
     /// Reprezents `root(3, a)` operation.
     Root3,
-    Abs, Comb, Fact, Mod,
+    Abs, Comb, Perm, Fact, Mod,
+    /// Reprezents `a%` operation, which is `a/100`. When it is the right operand
+    /// of `+` or `-`, it is the percentage of the left operand instead, so
+    /// `200+10%` is `200+200*10/100`. Other operations use the plain `a/100`.
+    Percent,
 }
 
 /// Used to map button presses to functionality.
 /// Now if we want to implement alternative ways
 /// of using our calculator, we just need to
 /// pass this enum as action to the pressed button.
-#[derive(Hash, Debug, PartialEq, Eq, Clone)]
+#[derive(Hash, Debug, PartialEq, Eq, Clone, Deserialize)]
 pub enum PressedButton {
     /// Numpad 0-9 or A-F (10 - 15)
     Num(u8),
@@ -66,13 +78,35 @@ pub enum PressedButton {
     Evaluate,
     BracketLeft,
     BracketRight,
+    /// Absolute value bar `|`. Whether it opens or closes the absolute value
+    /// is decided by its position in the expression.
+    AbsBar,
     /// Floating point.
     Comma,
+    /// Exponent of a number in the scientific notation, `6.022E23`.
+    Exp,
+    /// Percentage of the preceding operand, inserted as `Opt::Percent`.
+    Percent,
     /// Last result.
     Ans,
     /// Operation, which generates random number
     /// on each evaluation.
     Random,
+    /// Empty operand of the `ⁿ√` and `aⁿ` operations, replaced
+    /// by the next button entered at its position.
+    Placeholder,
+    /// Add the last result to the memory register.
+    MemAdd,
+    /// Subtract the last result from the memory register.
+    MemSub,
+    /// Insert the value of the memory register.
+    MemRecall,
+    /// Set the memory register to zero.
+    MemClear,
+    /// Return the expression to the state before the last edit.
+    Undo,
+    /// Reapply the last undone edit.
+    Redo,
 }
 
 /// Color theme of the app.
@@ -90,6 +124,46 @@ impl fmt::Display for Theme {
     }
 }
 
+/// Layout of the buttons.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Data, PartialEq, Default)]
+pub enum LayoutMode {
+    /// Only the numeric keyboard with arithmetic operations.
+    Basic,
+    /// All the buttons including the function tabs.
+    #[default]
+    Scientific,
+}
+
+/// String representations for `LayoutMode`
+impl fmt::Display for LayoutMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Symbol of the multiplication on the display.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Data, PartialEq, Default)]
+pub enum MulSymbol {
+    /// Dot operator `⋅`.
+    #[default]
+    Dot,
+    /// Multiplication sign `×`.
+    Cross,
+    /// Asterisk `*`, same as typed on the keyboard.
+    Star,
+}
+
+impl MulSymbol {
+    /// Get the displayed symbol.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Dot => "⋅",
+            Self::Cross => "×",
+            Self::Star => "*",
+        }
+    }
+}
+
 /// Represents tabs that switch between different function keyboards
 #[derive(Debug, PartialEq, Clone, Copy, Data)]
 pub enum FunctionTabs {
@@ -98,12 +172,51 @@ pub enum FunctionTabs {
     Const,
 }
 
+/// Default multiplier of the font sizes in the accessibility mode.
+const ACCESSIBILITY_FONT_SCALE: f64 = 1.5;
+
+fn default_font_scale() -> f64 {
+    ACCESSIBILITY_FONT_SCALE
+}
+
+fn default_max_input_length() -> usize {
+    expr_manager::DEFAULT_MAX_LEN
+}
+
 /// Holds application configuration, which is saved on the disk.
 /// This is loaded at each start of the application.
 #[derive(Serialize, Deserialize, Lens, Clone)]
 pub struct CalcConfig {
     theme: Theme,
     language: String,
+    /// Display negative results in different color.
+    #[serde(default)]
+    ledger_mode: bool,
+    /// Numeric base used when the app starts.
+    #[serde(default)]
+    default_radix: Radix,
+    /// Angular unit used when the app starts (true = degrees, false = radians).
+    #[serde(default)]
+    default_degrees: bool,
+    /// Layout of the buttons.
+    #[serde(default)]
+    layout_mode: LayoutMode,
+    /// Larger fonts and high contrast colors for low-vision users.
+    #[serde(default)]
+    accessibility: bool,
+    /// Multiplier of the font sizes, when the accessibility mode is enabled.
+    #[serde(default = "default_font_scale")]
+    font_scale: f64,
+    /// Symbol of the multiplication on the display.
+    #[serde(default)]
+    mul_symbol: MulSymbol,
+    /// Display results as percentages (`0.25` as `25%`).
+    #[serde(default)]
+    percent_results: bool,
+    /// Maximum number of buttons in the expression on the display.
+    #[serde(default = "default_max_input_length")]
+    max_input_length: usize,
+    /// Saved as a table, which has to come after all the plain values in the config file.
     history: History,
 }
 
@@ -114,6 +227,15 @@ impl Default for CalcConfig {
             theme: Theme::System,
             language: "en".to_owned(),
             history: History::default(),
+            ledger_mode: false,
+            default_radix: Radix::Dec,
+            default_degrees: false,
+            layout_mode: LayoutMode::Scientific,
+            accessibility: false,
+            font_scale: ACCESSIBILITY_FONT_SCALE,
+            mul_symbol: MulSymbol::Dot,
+            percent_results: false,
+            max_input_length: expr_manager::DEFAULT_MAX_LEN,
         }
     }
 }
@@ -123,6 +245,15 @@ impl Data for CalcConfig {
         self.theme == other.theme
             && self.language == other.language
             && self.history.same(&other.history)
+            && self.ledger_mode == other.ledger_mode
+            && self.default_radix == other.default_radix
+            && self.default_degrees == other.default_degrees
+            && self.layout_mode == other.layout_mode
+            && self.accessibility == other.accessibility
+            && self.font_scale == other.font_scale
+            && self.mul_symbol == other.mul_symbol
+            && self.percent_results == other.percent_results
+            && self.max_input_length == other.max_input_length
     }
 }
 
@@ -178,9 +309,17 @@ pub struct CalcState {
     /// Last computed result, which is displayed on the display.
     #[lens(ignore)]
     result: String,
-    result_as_num: Option<math::number::Number>,
+    result_as_num: Option<FormattedNumber>,
     /// Is `true` if `CalcState::result` is an error message.
     result_is_err: bool,
+    /// Display the last result as an exact fraction instead of the rounded number.
+    fraction_result: bool,
+    /// Memory register, available in expressions as the constant `mem`.
+    #[lens(ignore)]
+    memory: Number,
+    /// Simplification of the fraction typed by user (`6/8 = 3/4`), empty if there is none.
+    #[lens(ignore)]
+    simplification: String,
     /// Use degrees in trigonometric computations, otherwise use radians.
     degrees: bool,
     /// How many decimal places should the result have.
@@ -228,6 +367,8 @@ impl Data for CalcState {
             && self.constants.same(&other.constants)
             && self.precision == other.precision
             && self.degrees == other.degrees
+            && self.simplification == other.simplification
+            && self.memory == other.memory
     }
 }
 
@@ -242,19 +383,38 @@ impl CalcState {
     /// * `languages` - Array of available languages loaded from rust-i18n.
     pub fn new(languages: &[&str]) -> Self {
         let config = confy::load(APP_NAME, None).unwrap_or_default();
+        Self::with_config(languages, config)
+    }
+
+    /// Creates new instance of CalcState from already loaded `config`.
+    ///
+    /// * `languages` - Array of available languages loaded from rust-i18n.
+    pub fn with_config(languages: &[&str], mut config: CalcConfig) -> Self {
+        config.language = resolve_language(languages, &config.language);
+
+        let mut calc = math::Calculator::new();
+        calc.set_angle_mode(angle_mode(config.default_degrees));
+        // ANS is zero until the first result is computed.
+        calc.add_constant("ans", Number::zero());
+        calc.add_constant(MEMORY_CONSTANT, Number::zero());
 
         Self {
-            expr_man: ExprManager::new(),
-            radix: Radix::Dec,
+            expr_man: ExprManager::new()
+                .with_mul_symbol(config.mul_symbol)
+                .with_max_len(config.max_input_length),
+            radix: config.default_radix,
+            degrees: config.default_degrees,
             function_tab: FunctionTabs::Main,
             constants: Constants::new(),
             // Convert array of string slices to vector of strings.
             available_languages: Rc::new(languages.iter().map(|&s| String::from(s)).collect()),
             config,
-            calc: Rc::new(RefCell::new(math::Calculator::new())),
+            calc: Rc::new(RefCell::new(calc)),
             result: String::new(),
             result_is_err: false,
-            degrees: false,
+            fraction_result: false,
+            memory: Number::zero(),
+            simplification: String::new(),
             display_focus: true,
             main_win_id: WindowId::next(),
             precision: 5,
@@ -281,20 +441,26 @@ impl CalcState {
                         return;
                     }
                 };
-                let result = self.calc.borrow_mut().evaluate(&eval_str);
+                let result = self.calc.borrow_mut().evaluate_safe(&eval_str);
 
                 // Set resulting variable according to the resulting value.
                 (self.result, self.result_is_err) = match result {
                     Err(e) => {
                         self.result_as_num = None;
+                        self.simplification.clear();
                         (format!("{:?}", e), true)
                     }
                     Ok(num) => {
-                        self.result_as_num = Some(num.clone());
+                        self.simplification =
+                            fraction_simplification(&eval_str, &num).unwrap_or_default();
+                        let num = FormattedNumber::from(num);
+                        self.fraction_result = false;
+                        let formatted = self.format_result(&num);
+                        self.result_as_num = Some(num);
                         if self.get_history().recording() {
-                            self.save_equation(num.to_string(self.radix, self.precision));
+                            self.save_equation(formatted.clone());
                         }
-                        (num.to_string(self.radix, self.precision), false)
+                        (formatted, false)
                     }
                 };
 
@@ -309,12 +475,172 @@ impl CalcState {
                     self.result_is_err = false;
                 }
             }
+            PressedButton::MemAdd | PressedButton::MemSub => {
+                if self.result_is_err {
+                    return;
+                }
+                let Some(num) = &self.result_as_num else {
+                    return;
+                };
+                let memory = if button == &PressedButton::MemAdd {
+                    self.memory.add(num.number())
+                } else {
+                    self.memory.sub(num.number())
+                };
+                if let Ok(memory) = memory {
+                    self.set_memory(memory);
+                }
+            }
+            PressedButton::MemClear => self.set_memory(Number::zero()),
+            PressedButton::MemRecall => {
+                self.process_button(&PressedButton::Const(MEMORY_CONSTANT.to_owned()))
+            }
+
+            // The exponent means ×10ⁿ, so it's entered only into decimal numbers.
+            PressedButton::Exp if self.radix != Radix::Dec => (),
 
             // Relay other buttons to the expression manager.
-            other => self.expr_man.process_button(other),
+            other => {
+                if !self.expr_man.process_button(other) {
+                    self.result = t!("errors.input_too_long");
+                    self.result_is_err = true;
+                }
+            }
         };
     }
 
+    /// Simplification of the fraction typed by user, like `6/8 = 3/4`.
+    /// Empty if the last expression wasn't a fraction or it was already in lowest terms.
+    pub fn simplification(&self) -> &str {
+        &self.simplification
+    }
+
+    /// Check whether the current expression can be evaluated.
+    pub fn is_evaluable(&self) -> bool {
+        self.expr_man.is_evaluable(&mut self.calc.borrow_mut())
+    }
+
+    /// Check whether the exponent can be inserted at the cursor, see `ExprManager::exp_allowed()`.
+    /// Only decimal numbers have an exponent.
+    pub fn exp_allowed(&self) -> bool {
+        self.radix == Radix::Dec && self.expr_man.exp_allowed()
+    }
+
+    /// Check whether the last computed result is a negative number.
+    pub fn result_is_negative(&self) -> bool {
+        !self.result_is_err
+            && self
+                .result_as_num
+                .as_ref()
+                .map_or(false, |num| num.number().is_negative())
+    }
+
+    /// Is ledger mode (coloring of negative results) enabled?
+    pub fn ledger_mode(&self) -> bool {
+        self.config.ledger_mode
+    }
+
+    /// Enable or disable coloring of negative results. This will be saved at exit.
+    pub fn set_ledger_mode(&mut self, enabled: bool) {
+        self.config.ledger_mode = enabled;
+        self.store_config_data();
+    }
+
+    /// Are results displayed as percentages?
+    pub fn percent_results(&self) -> bool {
+        self.config.percent_results
+    }
+
+    /// Display results as percentages, or as plain numbers. This will be saved at exit.
+    pub fn set_percent_results(&mut self, enabled: bool) {
+        self.config.percent_results = enabled;
+        self.update_result_radix();
+        self.store_config_data();
+    }
+
+    /// Value of the memory register.
+    pub fn memory(&self) -> &Number {
+        &self.memory
+    }
+
+    /// Set the memory register, the constant `mem` is updated too.
+    fn set_memory(&mut self, memory: Number) {
+        self.calc
+            .borrow_mut()
+            .add_constant(MEMORY_CONSTANT, memory.clone());
+        self.memory = memory;
+    }
+
+    /// Is the last result displayed as an exact fraction?
+    pub fn fraction_result(&self) -> bool {
+        self.fraction_result
+    }
+
+    /// Switch the last result between the rounded number and the exact fraction.
+    /// New results are always displayed as numbers.
+    pub fn toggle_fraction_result(&mut self) {
+        if self.result_is_err || self.result_as_num.is_none() {
+            return;
+        }
+        self.fraction_result = !self.fraction_result;
+        self.update_result_radix();
+    }
+
+    /// Format the result according to the radix, precision and percentage display.
+    /// Percentages are shown only in the decimal radix.
+    fn format_result(&self, num: &FormattedNumber) -> String {
+        if self.fraction_result {
+            num.number().to_fraction_string(self.radix)
+        } else if self.config.percent_results && self.radix == Radix::Dec {
+            num.number().to_percent_string(self.precision)
+        } else {
+            num.to_string(self.radix, self.precision)
+        }
+    }
+
+    /// Get current layout of the buttons.
+    pub fn layout_mode(&self) -> LayoutMode {
+        self.config.layout_mode
+    }
+
+    /// Switch layout of the buttons. This will be saved at exit.
+    pub fn set_layout_mode(&mut self, mode: LayoutMode) {
+        self.config.layout_mode = mode;
+        self.store_config_data();
+    }
+
+    /// Get displayed symbol of the multiplication.
+    pub fn mul_symbol(&self) -> MulSymbol {
+        self.config.mul_symbol
+    }
+
+    /// Change displayed symbol of the multiplication. This will be saved at exit.
+    pub fn set_mul_symbol(&mut self, symbol: MulSymbol) {
+        self.config.mul_symbol = symbol;
+        self.expr_man.set_mul_symbol(symbol);
+        self.store_config_data();
+    }
+
+    /// Is accessibility mode (larger fonts and high contrast) enabled?
+    pub fn accessibility(&self) -> bool {
+        self.config.accessibility
+    }
+
+    /// Enable or disable the accessibility mode. This will be saved at exit.
+    pub fn set_accessibility(&mut self, enabled: bool) {
+        self.config.accessibility = enabled;
+        self.store_config_data();
+    }
+
+    /// Multiplier of all the font sizes, `1.0` unless the accessibility mode is enabled.
+    pub fn font_scale(&self) -> f64 {
+        if self.config.accessibility {
+            self.config.font_scale
+        } else {
+            1.0
+        }
+    }
+
     /// Store CalcState::config on the disk using *confy* create.
     pub fn store_config_data(&self) {
         let res = confy::store(APP_NAME, None, &self.config);
@@ -346,11 +672,12 @@ impl CalcState {
 
     /// Change language of the app. This will be saved at exit.
     ///
-    /// * `language` - Must be in the array of languages passed to `CalcState::new()`
+    /// * `language` - Should be in the array of languages passed to `CalcState::new()`,
+    ///   otherwise the closest available language is used (see `resolve_language`)
     pub fn set_language(&mut self, language: &str) {
-        assert!(self.available_languages.contains(&language.to_string()));
-        rust_i18n::set_locale(language);
-        self.config.language = String::from(language);
+        let language = resolve_language(&self.available_languages, language);
+        rust_i18n::set_locale(&language);
+        self.config.language = language;
         self.store_config_data();
     }
 
@@ -370,7 +697,7 @@ impl CalcState {
             return;
         }
         if let Some(num) = &self.result_as_num {
-            self.result = num.to_string(self.radix, self.precision);
+            self.result = self.format_result(num);
         }
     }
 
@@ -378,6 +705,11 @@ impl CalcState {
     pub fn set_radix(&mut self, radix: Radix) {
         self.radix = radix;
         self.update_result_radix();
+
+        if self.config.default_radix != radix {
+            self.config.default_radix = radix;
+            self.store_config_data();
+        }
     }
 
     /// Get function keyboard
@@ -420,9 +752,19 @@ impl CalcState {
         self.constants.values.remove(index);
     }
 
+    /// Remove all user defined constants from math library as well as from `CalcState` data
+    pub fn clear_constants(&mut self) {
+        self.calc.borrow_mut().clear_user_constants();
+        self.constants.keys.clear();
+        self.constants.values.clear();
+        // The memory register is a constant of the math library too, keep it.
+        self.set_memory(self.memory.clone());
+    }
+
     /// Check if the constant already exists
     pub fn is_new_constant(&self, key: String) -> bool {
-        !(self.constants.keys.contains(&key) || key == "e" || key == "pi" || key == "ANS")
+        !(self.constants.keys.contains(&key)
+            || matches!(key.as_str(), "e" | "pi" | "phi" | "ANS" | MEMORY_CONSTANT))
     }
 
     /// Copy the currently displayed result into the system clipboard
@@ -449,16 +791,35 @@ impl CalcState {
 
     /// Save the expression and result to history
     pub fn save_equation(&mut self, result: String) {
-        self.config
-            .history
-            .data
-            .push((self.expr_man.get_display_str(false), result));
+        self.config.history.push(
+            self.expr_man.get_display_str(false),
+            result,
+            self.expr_man.buttons(),
+        );
         self.store_config_data();
     }
 
+    /// Replace the expression on the display with the history entry at `idx` and give
+    /// the display the keyboard focus. Returns `false` if the entry has no saved buttons.
+    pub fn load_history_entry(&mut self, idx: usize) -> bool {
+        let Some(buttons) = self.config.history.buttons(idx) else {
+            return false;
+        };
+
+        self.expr_man.set_buttons(buttons);
+        self.display_focus = true;
+        true
+    }
+
     /// Set angular unit based on `degrees` on either degrees or radians
     pub fn set_angular_unit(&mut self, degrees: bool) {
         self.degrees = degrees;
+        self.calc.borrow_mut().set_angle_mode(angle_mode(degrees));
+
+        if self.config.default_degrees != degrees {
+            self.config.default_degrees = degrees;
+            self.store_config_data();
+        }
     }
 
     /// Get currently set angular unit (true = degrees, false = radians)
@@ -486,11 +847,451 @@ impl CalcState {
         self.display_focus = has_focus;
     }
 
-    /// Update value of ans. Should be called after each calculation
+    /// Update value of ans to the last computed result. Should be called after each calculation
+    ///
+    /// The exact result is used, the displayed one is rounded and in the current radix.
     pub fn update_ans(&mut self) {
-        self.calc.borrow_mut().remove_constant("ans");
-        self.calc
-            .borrow_mut()
-            .add_constant("ans", math::evaluate(&self.result).unwrap());
+        if let Some(num) = &self.result_as_num {
+            self.calc
+                .borrow_mut()
+                .add_constant("ans", num.number().clone());
+        }
+    }
+}
+
+/// Pick the language to use for the `requested` one, trying the requested language, the language
+/// without region (`cz` for `cz-CZ`) and `FALLBACK_LANGUAGE` in this order. A warning is logged,
+/// when the requested language isn't available.
+fn resolve_language(available: &[impl AsRef<str>], requested: &str) -> String {
+    let base = requested.split(['-', '_']).next().unwrap_or(requested);
+    let language = [requested, base]
+        .into_iter()
+        .find(|language| available.iter().any(|a| a.as_ref() == *language))
+        .unwrap_or(FALLBACK_LANGUAGE);
+
+    if language != requested {
+        eprintln!(
+            "warning: Language '{}' is not available, using '{}' instead",
+            requested, language
+        );
+    }
+
+    String::from(language)
+}
+
+/// Angle mode of the math library for the angular unit (true = degrees, false = radians).
+fn angle_mode(degrees: bool) -> math::AngleMode {
+    if degrees {
+        math::AngleMode::Degrees
+    } else {
+        math::AngleMode::Radians
+    }
+}
+
+/// Simplification of a fraction typed by user, like `6/8 = 3/4`. Returns `None` if `eval_str`
+/// isn't a fraction of two integers or it's already in lowest terms.
+fn fraction_simplification(eval_str: &str, result: &Number) -> Option<String> {
+    let (numer, denom) = eval_str.split_once('/')?;
+    let is_integer = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !is_integer(numer) || !is_integer(denom) {
+        return None;
+    }
+
+    let reduced = result.to_fraction_string(Radix::Dec);
+    (reduced != eval_str).then(|| format!("{eval_str} = {reduced}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_from_config() {
+        let config = CalcConfig {
+            default_radix: Radix::Hex,
+            default_degrees: false,
+            ..Default::default()
+        };
+        let state = CalcState::with_config(&["en"], config);
+
+        assert_eq!(state.get_radix(), Radix::Hex);
+        assert!(!state.get_angular_unit());
+
+        let config = CalcConfig {
+            default_radix: Radix::Bin,
+            default_degrees: true,
+            ..Default::default()
+        };
+        let state = CalcState::with_config(&["en"], config);
+
+        assert_eq!(state.get_radix(), Radix::Bin);
+        assert!(state.get_angular_unit());
+    }
+
+    #[test]
+    fn ans_button_uses_last_result() {
+        let mut state = CalcState::with_config(&["en"], CalcConfig::default());
+        // Don't save the history to the disk.
+        state.get_mut_history().toggle_recording();
+        let mut press = |buttons: &[PressedButton]| {
+            for button in buttons {
+                state.process_button(button);
+            }
+            state.result.clone()
+        };
+
+        let ans_plus_one = [
+            PressedButton::Clear,
+            PressedButton::Ans,
+            PressedButton::BinOpt(Opt::Add),
+            PressedButton::Num(1),
+            PressedButton::Evaluate,
+        ];
+
+        let five = [
+            PressedButton::Clear,
+            PressedButton::Num(5),
+            PressedButton::Evaluate,
+        ];
+
+        assert_eq!(press(&ans_plus_one), "1");
+        assert_eq!(press(&five), "5");
+        assert_eq!(press(&ans_plus_one), "6");
+        assert_eq!(press(&ans_plus_one), "7");
+
+        // ANS keeps the exact value, not the rounded displayed one
+        press(&[
+            PressedButton::Clear,
+            PressedButton::Num(1),
+            PressedButton::BinOpt(Opt::Div),
+            PressedButton::Num(3),
+            PressedButton::Evaluate,
+        ]);
+        let times_three = [
+            PressedButton::Clear,
+            PressedButton::Ans,
+            PressedButton::BinOpt(Opt::Mul),
+            PressedButton::Num(3),
+            PressedButton::Evaluate,
+        ];
+        assert_eq!(press(&times_three), "1");
+    }
+
+    #[test]
+    fn memory_register() {
+        let mut state = CalcState::with_config(&["en"], CalcConfig::default());
+        // Don't save the history to the disk.
+        state.get_mut_history().toggle_recording();
+        let press = |state: &mut CalcState, buttons: &[PressedButton]| {
+            for button in buttons {
+                state.process_button(button);
+            }
+        };
+        let evaluate = |state: &mut CalcState, buttons: &[PressedButton]| {
+            press(state, &[PressedButton::Clear]);
+            press(state, buttons);
+            press(state, &[PressedButton::Evaluate]);
+        };
+
+        // Nothing to add without a result.
+        press(&mut state, &[PressedButton::MemAdd]);
+        assert_eq!(state.memory(), &Number::zero());
+
+        evaluate(&mut state, &[PressedButton::Num(5)]);
+        press(&mut state, &[PressedButton::MemAdd, PressedButton::MemAdd]);
+        evaluate(&mut state, &[PressedButton::Num(2)]);
+        press(&mut state, &[PressedButton::MemSub]);
+        assert_eq!(state.memory(), &Number::from(8));
+
+        let recall_plus_one = [
+            PressedButton::MemRecall,
+            PressedButton::BinOpt(Opt::Add),
+            PressedButton::Num(1),
+        ];
+        evaluate(&mut state, &recall_plus_one);
+        assert_eq!(state.result, "9");
+        assert_eq!(state.expr_man.get_display_str(false), "M+1");
+
+        press(&mut state, &[PressedButton::MemClear]);
+        assert_eq!(state.memory(), &Number::zero());
+        evaluate(&mut state, &recall_plus_one);
+        assert_eq!(state.result, "1");
+    }
+
+    #[test]
+    fn load_history_entry() {
+        let mut state = CalcState::with_config(&["en"], CalcConfig::default());
+        let buttons = [
+            PressedButton::Num(2),
+            PressedButton::BinOpt(Opt::Mul),
+            PressedButton::Num(3),
+        ];
+        state
+            .get_mut_history()
+            .push("2⋅3".to_owned(), "6".to_owned(), &buttons);
+        state.set_display_focus(false);
+
+        assert!(!state.load_history_entry(1));
+        assert!(state.load_history_entry(0));
+        assert_eq!(state.expr_man.get_display_str(false), "2⋅3");
+        assert_eq!(state.expr_man.cursor_pos(), 3);
+        assert!(state.get_display_focus());
+    }
+
+    #[test]
+    fn negative_result() {
+        let mut state = CalcState::with_config(&["en"], CalcConfig::default());
+        // Don't save the history to the disk.
+        state.get_mut_history().toggle_recording();
+        let evaluate = |state: &mut CalcState, buttons: &[PressedButton]| {
+            state.process_button(&PressedButton::Clear);
+            for button in buttons {
+                state.process_button(button);
+            }
+            state.process_button(&PressedButton::Evaluate);
+        };
+
+        // Nothing is negative before the first result.
+        assert!(!state.result_is_negative());
+
+        evaluate(
+            &mut state,
+            &[
+                PressedButton::Num(2),
+                PressedButton::BinOpt(Opt::Sub),
+                PressedButton::Num(5),
+            ],
+        );
+        assert_eq!(state.result, "-3");
+        assert!(state.result_is_negative());
+
+        evaluate(&mut state, &[PressedButton::Num(5)]);
+        assert!(!state.result_is_negative());
+
+        // Errors are never negative.
+        evaluate(
+            &mut state,
+            &[
+                PressedButton::BinOpt(Opt::Sub),
+                PressedButton::Num(1),
+                PressedButton::BinOpt(Opt::Div),
+                PressedButton::Num(0),
+            ],
+        );
+        assert!(state.result_is_err);
+        assert!(!state.result_is_negative());
+    }
+
+    #[test]
+    fn percent_results() {
+        let config = CalcConfig {
+            percent_results: true,
+            ..Default::default()
+        };
+        let mut state = CalcState::with_config(&["en"], config);
+        // Don't save the history to the disk.
+        state.get_mut_history().toggle_recording();
+
+        for button in [
+            PressedButton::Num(1),
+            PressedButton::BinOpt(Opt::Div),
+            PressedButton::Num(4),
+            PressedButton::Evaluate,
+        ] {
+            state.process_button(&button);
+        }
+        assert_eq!(state.result, "25%");
+
+        // percentages are decimal only
+        state.radix = Radix::Bin;
+        state.update_result_radix();
+        assert_eq!(state.result, "0.01");
+    }
+
+    #[test]
+    fn fraction_result() {
+        let mut state = CalcState::with_config(&["en"], CalcConfig::default());
+        // Don't save the history to the disk.
+        state.get_mut_history().toggle_recording();
+
+        // Nothing to toggle without a result.
+        state.toggle_fraction_result();
+        assert!(!state.fraction_result());
+
+        let evaluate = |state: &mut CalcState, buttons: &[PressedButton]| {
+            state.process_button(&PressedButton::Clear);
+            for button in buttons {
+                state.process_button(button);
+            }
+            state.process_button(&PressedButton::Evaluate);
+        };
+        let one_third = [
+            PressedButton::Num(1),
+            PressedButton::BinOpt(Opt::Div),
+            PressedButton::Num(3),
+        ];
+        let minus_seven_halves = [
+            PressedButton::BinOpt(Opt::Sub),
+            PressedButton::Num(7),
+            PressedButton::BinOpt(Opt::Div),
+            PressedButton::Num(2),
+        ];
+
+        evaluate(&mut state, &one_third);
+        assert_eq!(state.result, "0.33333");
+        state.toggle_fraction_result();
+        assert_eq!(state.result, "1/3");
+        state.toggle_fraction_result();
+        assert_eq!(state.result, "0.33333");
+
+        state.toggle_fraction_result();
+        evaluate(&mut state, &minus_seven_halves);
+        // New result is displayed as a number.
+        assert_eq!(state.result, "-3.5");
+        state.toggle_fraction_result();
+        assert_eq!(state.result, "-7/2");
+
+        evaluate(&mut state, &[PressedButton::Num(4)]);
+        state.toggle_fraction_result();
+        assert_eq!(state.result, "4");
+    }
+
+    #[test]
+    fn max_input_length() {
+        let config = CalcConfig {
+            max_input_length: 2,
+            ..Default::default()
+        };
+        let mut state = CalcState::with_config(&["en"], config);
+
+        for num in 1..=3 {
+            state.process_button(&PressedButton::Num(num));
+        }
+        assert_eq!(state.expr_man.get_display_str(false), "12");
+        assert!(state.result_is_err);
+
+        // The notification is cleared together with the display.
+        state.process_button(&PressedButton::Clear);
+        assert!(state.result.is_empty());
+        assert!(!state.result_is_err);
+    }
+
+    #[test]
+    fn clear_constants_keeps_memory() {
+        let mut state = CalcState::with_config(&["en"], CalcConfig::default());
+        // Don't save the history to the disk.
+        state.get_mut_history().toggle_recording();
+        let evaluate = |state: &mut CalcState, buttons: &[PressedButton]| {
+            state.process_button(&PressedButton::Clear);
+            for button in buttons {
+                state.process_button(button);
+            }
+            state.process_button(&PressedButton::Evaluate);
+        };
+
+        evaluate(&mut state, &[PressedButton::Num(7)]);
+        state.process_button(&PressedButton::MemAdd);
+        assert!(state.add_constant("a".to_owned(), "2".to_owned()));
+
+        state.clear_constants();
+        assert!(state.is_new_constant("a".to_owned()));
+        evaluate(&mut state, &[PressedButton::MemRecall]);
+        assert!(!state.result_is_err);
+        assert_eq!(state.result, "7");
+    }
+
+    #[test]
+    fn exponent_only_in_decimal() {
+        let exponent = |radix| {
+            let config = CalcConfig {
+                default_radix: radix,
+                ..Default::default()
+            };
+            let mut state = CalcState::with_config(&["en"], config);
+            state.process_button(&PressedButton::Num(1));
+            let allowed = state.exp_allowed();
+            state.process_button(&PressedButton::Exp);
+            (allowed, state.expr_man.get_display_str(false))
+        };
+
+        assert_eq!(exponent(Radix::Dec), (true, "1E".to_owned()));
+        assert_eq!(exponent(Radix::Hex), (false, "1".to_owned()));
+        assert_eq!(exponent(Radix::Oct), (false, "1".to_owned()));
+        assert_eq!(exponent(Radix::Bin), (false, "1".to_owned()));
+    }
+
+    #[test]
+    fn unknown_language_falls_back() {
+        let languages = ["en", "cz", "sk"];
+        let language = |language: &str| {
+            let config = CalcConfig {
+                language: String::from(language),
+                ..Default::default()
+            };
+            let state = CalcState::with_config(&languages, config);
+            state.get_language().to_owned()
+        };
+
+        assert_eq!(language("sk"), "sk");
+        assert_eq!(language("cz-CZ"), "cz");
+        assert_eq!(language("sk_SK"), "sk");
+        assert_eq!(language("xx"), "en");
+        assert_eq!(language(""), "en");
+    }
+
+    #[test]
+    fn angular_unit_used_in_trig() {
+        let sin_90 = |degrees| {
+            let config = CalcConfig {
+                default_degrees: degrees,
+                ..Default::default()
+            };
+            let state = CalcState::with_config(&["en"], config);
+            let mut calc = state.calc.borrow_mut();
+            calc.evaluate("sin(90)")
+        };
+
+        assert_eq!(sin_90(true), Ok(Number::one()));
+        assert_ne!(sin_90(false), Ok(Number::one()));
+    }
+
+    #[test]
+    fn accessibility_scales_fonts() {
+        let config = CalcConfig {
+            accessibility: true,
+            font_scale: 2.0,
+            ..Default::default()
+        };
+        let state = CalcState::with_config(&["en"], config);
+
+        assert_eq!(state.font_scale(), 2.0);
+        let sizes = widgets::FontSizes::scaled(state.font_scale());
+        assert_eq!(sizes.expression, 2.0 * widgets::display::EXPR_TEXT_SIZE);
+        assert_eq!(sizes.result, 2.0 * widgets::display::RESULT_TEXT_SIZE);
+
+        let config = CalcConfig {
+            accessibility: false,
+            font_scale: 2.0,
+            ..Default::default()
+        };
+        let state = CalcState::with_config(&["en"], config);
+
+        assert_eq!(state.font_scale(), 1.0);
+        let sizes = widgets::FontSizes::scaled(state.font_scale());
+        assert_eq!(sizes.expression, widgets::display::EXPR_TEXT_SIZE);
+    }
+
+    #[test]
+    fn fraction_in_lowest_terms() {
+        let simplify = |eval_str| {
+            let num = math::evaluate(eval_str).unwrap();
+            fraction_simplification(eval_str, &num)
+        };
+
+        assert_eq!(simplify("6/8").as_deref(), Some("6/8 = 3/4"));
+        assert_eq!(simplify("10/5").as_deref(), Some("10/5 = 2"));
+        assert_eq!(simplify("3/4"), None);
+        assert_eq!(simplify("6/8+1"), None);
+        assert_eq!(simplify("6.0/8"), None);
     }
 }
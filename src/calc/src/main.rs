@@ -1,5 +1,5 @@
-mod environment;
-use calc::delegate::Delegate;
+use calc::delegate::{Delegate, KeyRepeatTimer};
+use calc::environment::*;
 
 use calc::widgets::menu::CalcMenu;
 use calc::{
@@ -13,7 +13,6 @@ use druid::{
     AppLauncher, Size, Widget, WindowDesc,
 };
 use druid::{Env, Event, EventCtx, WidgetExt};
-use environment::*;
 
 /// Initial size of the window, when the app starts.
 const WINDOW_SIZE: Size = Size::new(400.0, 400.0);
@@ -37,10 +36,14 @@ impl<W: Widget<CalcState>> Controller<CalcState, W> for AppFocusController {
 /// Creates the root widget of app. All other widgets are inside this one.
 fn build_root_widget() -> impl Widget<CalcState> {
     EnvScope::new(
-        |env, data| match data.get_theme(true) {
-            Theme::Dark => set_dark_envs(env),
-            Theme::Light => set_light_envs(env),
-            Theme::System => unreachable!(),
+        |env, data| {
+            match data.get_theme(true) {
+                Theme::Dark => set_dark_envs(env),
+                Theme::Light => set_light_envs(env),
+                Theme::System => unreachable!(),
+            }
+
+            set_accessibility_envs(env, data);
         },
         Container::new(
             Flex::column()
@@ -48,7 +51,8 @@ fn build_root_widget() -> impl Widget<CalcState> {
                 .with_flex_child(ButtonsUI::build_ui(), 3.0),
         )
         .background(theme::WINDOW_BACKGROUND_COLOR)
-        .controller(AppFocusController),
+        .controller(AppFocusController)
+        .controller(KeyRepeatTimer::default()),
     )
 }
 
@@ -79,8 +83,9 @@ fn main() {
             set_digit_btn_envs(env);
             set_func_btn_envs(env);
             set_operation_btn_envs(env);
+            set_accessibility_envs(env, data);
         })
-        .delegate(Delegate)
+        .delegate(Delegate::default())
         .launch(calc_state)
     {
         eprintln!("error: Failed to launch main application. {}", platform_err);
@@ -7,3 +7,52 @@ pub mod display;
 pub mod help;
 pub mod history_win;
 pub mod menu;
+
+use druid::{Env, Key};
+
+/// Font sizes of the main window, all of them are multiplied by `CalcState::font_scale()`.
+/// Widgets read the sizes from the environment, so they change without rebuilding the UI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontSizes {
+    pub button: f64,
+    pub add_const_button: f64,
+    pub tab: f64,
+    pub error: f64,
+    pub expression: f64,
+    pub result: f64,
+    pub status: f64,
+}
+
+impl FontSizes {
+    pub const BUTTON: Key<f64> = Key::new("calc.font_size.button");
+    pub const ADD_CONST_BUTTON: Key<f64> = Key::new("calc.font_size.add_const_button");
+    pub const TAB: Key<f64> = Key::new("calc.font_size.tab");
+    pub const ERROR: Key<f64> = Key::new("calc.font_size.error");
+    pub const EXPRESSION: Key<f64> = Key::new("calc.font_size.expression");
+    pub const RESULT: Key<f64> = Key::new("calc.font_size.result");
+    pub const STATUS: Key<f64> = Key::new("calc.font_size.status");
+
+    /// Default font sizes multiplied by `scale`.
+    pub fn scaled(scale: f64) -> Self {
+        Self {
+            button: buttons_ui::BUTTON_TEXT_SIZE * scale,
+            add_const_button: buttons_ui::ADD_CONST_BUTTON_TEXT_SIZE * scale,
+            tab: buttons_ui::TAB_TEXT_SIZE * scale,
+            error: buttons_ui::ERROR_TEXT_SIZE * scale,
+            expression: display::EXPR_TEXT_SIZE * scale,
+            result: display::RESULT_TEXT_SIZE * scale,
+            status: display::STATUS_TEXT_SIZE * scale,
+        }
+    }
+
+    /// Store the font sizes in the environment.
+    pub fn set_envs(&self, env: &mut Env) {
+        env.set(Self::BUTTON, self.button);
+        env.set(Self::ADD_CONST_BUTTON, self.add_const_button);
+        env.set(Self::TAB, self.tab);
+        env.set(Self::ERROR, self.error);
+        env.set(Self::EXPRESSION, self.expression);
+        env.set(Self::RESULT, self.result);
+        env.set(Self::STATUS, self.status);
+    }
+}
@@ -1,6 +1,7 @@
 //! The buttons UI part of the calculator
 
-use crate::{CalcState, Constants, FunctionTabs, Opt, PressedButton, Theme};
+use crate::widgets::FontSizes;
+use crate::{CalcState, Constants, FunctionTabs, LayoutMode, Opt, PressedButton, Theme};
 use core::fmt;
 use druid::commands::CLOSE_WINDOW;
 use druid::kurbo::Circle;
@@ -21,7 +22,7 @@ const TEXTBOX_FOCUS: Selector<String> = Selector::new("textbox_focus");
 const APP_FOCUS: Selector<String> = Selector::new("app_focus");
 
 const SHOW_ERROR: Selector<String> = Selector::new("error");
-const ERROR_TEXT_SIZE: f64 = 14.0;
+pub(crate) const ERROR_TEXT_SIZE: f64 = 14.0;
 const ERROR_BACKGROUND_COLOR: Color = Color::rgb8(150, 20, 20);
 
 const TEXTBOX_PADDING: f64 = 5.0;
@@ -29,8 +30,8 @@ const KEY_TEXTBOX_WIDTH: f64 = 60.0;
 
 const BUTTON_PADDING: f64 = 1.0;
 const BUTTON_BORDER_RADIUS: f64 = 3.0;
-const BUTTON_TEXT_SIZE: f64 = 16.0;
-const ADD_CONST_BUTTON_TEXT_SIZE: f64 = 22.0;
+pub(crate) const BUTTON_TEXT_SIZE: f64 = 16.0;
+pub(crate) const ADD_CONST_BUTTON_TEXT_SIZE: f64 = 22.0;
 
 const TAB_BOTTOM_MARGIN: f64 = 5.0;
 const TAB_PADDING: Insets = Insets::uniform_xy(8.0, 0.0);
@@ -40,19 +41,47 @@ const TAB_ACTIVE_TEXT_COLOR_DARK: Color = Color::grey8(230);
 const TAB_ACTIVE_TEXT_COLOR_LIGHT: Color = Color::grey8(10);
 const TAB_ACTIVE_COLOR: &Color = &Color::rgb8(189, 197, 242);
 const TAB_HOVER_COLOR: &Color = &Color::grey8(120);
-const TAB_TEXT_SIZE: f64 = 14.0;
+pub(crate) const TAB_TEXT_SIZE: f64 = 14.0;
 
 type Btn = PressedButton;
 pub struct ButtonsUI;
 
 /// Encapsulation of the keyboard user interface
 impl ButtonsUI {
-    /// Render all buttons used to control the display
+    /// Render all buttons used to control the display, which buttons are shown depends
+    /// on the layout mode
     pub fn build_ui() -> impl Widget<CalcState> {
-        Flex::row()
-            .cross_axis_alignment(druid::widget::CrossAxisAlignment::End)
-            .with_flex_child(make_func_part(), 1.)
-            .with_flex_child(make_num_part(), 1.)
+        ViewSwitcher::new(
+            |data: &CalcState, _env| data.layout_mode(),
+            |mode, _data, _env| {
+                let mut row =
+                    Flex::row().cross_axis_alignment(druid::widget::CrossAxisAlignment::End);
+                for part in layout_parts(*mode) {
+                    match part {
+                        LayoutPart::Functions => row.add_flex_child(make_func_part(), 1.),
+                        LayoutPart::Numbers => row.add_flex_child(make_num_part(), 1.),
+                    }
+                }
+                Box::new(row)
+            },
+        )
+    }
+}
+
+// Parts of the buttons UI, which are laid out from left to right
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LayoutPart {
+    // Function tabs with the function keyboards
+    Functions,
+    // Radix tabs with the numeric keyboard and arithmetic operations
+    Numbers,
+}
+
+// Parts shown in the given layout `mode`
+fn layout_parts(mode: LayoutMode) -> &'static [LayoutPart] {
+    match mode {
+        LayoutMode::Basic => &[LayoutPart::Numbers],
+        LayoutMode::Scientific => &[LayoutPart::Functions, LayoutPart::Numbers],
     }
 }
 
@@ -91,11 +120,16 @@ impl<T, W: Widget<T>> Controller<T, W> for ShowContextMenu {
         if let Event::MouseDown(mouse_event) = event {
             if mouse_event.button == MouseButton::Right {
                 let index = Rc::new(self.index);
-                let menu: Menu<CalcState> =
-                    Menu::empty().entry(MenuItem::new(t!("context_menu.remove")).on_activate(
+                let menu: Menu<CalcState> = Menu::empty()
+                    .entry(MenuItem::new(t!("context_menu.remove")).on_activate(
                         move |_ctx, data: &mut CalcState, _env| {
                             data.remove_constant(*index);
                         },
+                    ))
+                    .entry(MenuItem::new(t!("context_menu.remove_all")).on_activate(
+                        |_ctx, data: &mut CalcState, _env| {
+                            data.clear_constants();
+                        },
                     ));
                 ctx.show_context_menu(menu, mouse_event.window_pos);
             }
@@ -114,8 +148,8 @@ fn make_func_part() -> impl Widget<CalcState> {
     let buttons = ViewSwitcher::new(
         |data: &CalcState, _env| data.get_function_tab(),
         |selector, _data, _env| match selector {
-            FunctionTabs::Main => Box::new(make_main_btns()),
-            FunctionTabs::Func => Box::new(make_func_btns()),
+            FunctionTabs::Main => Box::new(ButtonLayout::main().build()),
+            FunctionTabs::Func => Box::new(ButtonLayout::functions().build()),
             FunctionTabs::Const => Box::new(make_const_btns()),
         },
     );
@@ -193,13 +227,13 @@ enum TooltipState {
 
 // A Controller responsible for listening to mouse hovers and launching tooltip windows.
 // https://github.com/linebender/druid/blob/master/druid/examples/sub_window.rs
-struct TooltipController {
+pub(crate) struct TooltipController {
     tip: String,
     state: TooltipState,
 }
 
 impl TooltipController {
-    pub fn new(tip: impl Into<String>) -> Self {
+    pub(crate) fn new(tip: impl Into<String>) -> Self {
         TooltipController {
             tip: tip.into(),
             state: TooltipState::Fresh,
@@ -488,8 +522,10 @@ impl<T, W: Widget<T>> Controller<T, W> for ErrorMessageController {
                 if let Event::Command(cmd) = event {
                     if cmd.is(SHOW_ERROR) {
                         let error_msg = cmd.get_unchecked(SHOW_ERROR).to_string();
-                        let label_size =
-                            std::cmp::min(300, error_msg.len() * (ERROR_TEXT_SIZE as usize) / 2);
+                        let label_size = std::cmp::min(
+                            300,
+                            error_msg.len() * (env.get(FontSizes::ERROR) as usize) / 2,
+                        );
 
                         #[cfg(target_os = "linux")]
                         let label_top_offset = 0.9;
@@ -505,7 +541,7 @@ impl<T, W: Widget<T>> Controller<T, W> for ErrorMessageController {
                         let error_text = Label::<()>::new(error_msg.to_string())
                             .with_text_color(*TAB_ACTIVE_COLOR)
                             .with_text_alignment(druid::TextAlignment::Center)
-                            .with_text_size(ERROR_TEXT_SIZE)
+                            .with_text_size(FontSizes::ERROR)
                             .with_line_break_mode(druid::widget::LineBreaking::WordWrap)
                             .background(ERROR_BACKGROUND_COLOR)
                             .fix_width(label_size as f64);
@@ -534,7 +570,7 @@ impl<T, W: Widget<T>> Controller<T, W> for ErrorMessageController {
 // Button that add constants to the application
 fn make_add_const_btn() -> impl Widget<CalcState> {
     Label::new("+")
-        .with_text_size(ADD_CONST_BUTTON_TEXT_SIZE)
+        .with_text_size(FontSizes::ADD_CONST_BUTTON)
         .padding(BUTTON_PADDING)
         .controller(ErrorMessageController::new())
         .background(get_add_btn_painter())
@@ -589,6 +625,12 @@ fn make_num_part() -> impl Widget<CalcState> {
     operation_button(&mut top_row, "C", Btn::Clear);
     operation_button(&mut top_row, "⌫", Btn::Delete);
 
+    let mut memory_row = Flex::row();
+    operation_button(&mut memory_row, "MC", Btn::MemClear);
+    operation_button(&mut memory_row, "MR", Btn::MemRecall);
+    operation_button(&mut memory_row, "M+", Btn::MemAdd);
+    operation_button(&mut memory_row, "M-", Btn::MemSub);
+
     let mut operations = Flex::column();
     operation_button(&mut operations, "÷", Btn::BinOpt(Opt::Div));
     operation_button(&mut operations, "⨯", Btn::BinOpt(Opt::Mul));
@@ -599,6 +641,7 @@ fn make_num_part() -> impl Widget<CalcState> {
         .with_flex_child(make_radix_tabs(), 1.)
         .with_spacer(TAB_BOTTOM_MARGIN)
         .with_flex_child(top_row, 1.)
+        .with_flex_child(memory_row, 1.)
         .with_flex_child(
             Flex::row()
                 .with_flex_child(make_number_keyboard(), 3.)
@@ -660,7 +703,11 @@ fn make_num_btns() -> impl Widget<CalcState> {
     let row_0 = Flex::row()
         .with_flex_child(generic_button(",", Btn::Comma, BtnType::Operation), 1.)
         .with_flex_child(generic_button("0", Btn::Num(0), BtnType::Digit), 1.)
-        .with_flex_child(generic_button("=", Btn::Evaluate, BtnType::Operation), 1.);
+        .with_flex_child(
+            generic_button("=", Btn::Evaluate, BtnType::Operation)
+                .disabled_if(|data, _env| !data.is_evaluable()),
+            1.,
+        );
 
     Flex::column()
         .main_axis_alignment(druid::widget::MainAxisAlignment::End)
@@ -692,79 +739,137 @@ fn make_number_keyboard() -> impl Widget<CalcState> {
     )
 }
 
-// Buttons in the function tab - Main
-fn make_main_btns() -> impl Widget<CalcState> {
-    let mut first_row = Flex::row();
-    function_button(&mut first_row, "√", Btn::UnaryOpt(Opt::Sqrt));
-    function_button(&mut first_row, "a²", Btn::UnaryOpt(Opt::Pow2));
-    function_button(&mut first_row, "e", Btn::Const("e".to_owned()));
-
-    let mut second_row = Flex::row();
-    function_button(&mut second_row, "ⁿ√", Btn::BinOpt(Opt::Root));
-    function_button(&mut second_row, "aⁿ", Btn::BinOpt(Opt::Pow));
-    function_button(&mut second_row, "π", Btn::Const("pi".to_owned()));
+// Definition of a single button in a `ButtonLayout`
+#[derive(Debug, Clone, PartialEq)]
+struct ButtonDef {
+    label: &'static str,
+    action: Btn,
+    tooltip: Option<String>,
+}
 
-    let mut third_row = Flex::row();
-    function_button(&mut third_row, "³√", Btn::UnaryOpt(Opt::Root3));
-    function_button(&mut third_row, "|a|", Btn::UnaryOpt(Opt::Abs));
-    function_button(&mut third_row, "n!", Btn::UnaryOpt(Opt::Fact));
+impl ButtonDef {
+    fn new(label: &'static str, action: Btn) -> Self {
+        Self {
+            label,
+            action,
+            tooltip: None,
+        }
+    }
 
-    let mut forth_row = Flex::row();
-    function_button(&mut forth_row, "sin", Btn::UnaryOpt(Opt::Sin));
-    function_button(&mut forth_row, "cos", Btn::UnaryOpt(Opt::Cos));
-    function_button(&mut forth_row, "Rnd", Btn::Random);
+    // Show the `tooltip` when hovering over the button
+    fn with_tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
 
-    let mut fifth_row = Flex::row();
-    function_button(&mut fifth_row, "ANS", Btn::Ans);
-    function_button(&mut fifth_row, "(", Btn::BracketLeft);
-    function_button(&mut fifth_row, ")", Btn::BracketRight);
+    // Function button described by the definition
+    fn build(&self) -> Box<dyn Widget<CalcState>> {
+        let button = generic_button(self.label, self.action.clone(), BtnType::Function);
 
-    Flex::column()
-        .with_flex_child(first_row, 1.)
-        .with_flex_child(second_row, 1.)
-        .with_flex_child(third_row, 1.)
-        .with_flex_child(forth_row, 1.)
-        .with_flex_child(fifth_row, 1.)
-}
-
-// Buttons in the function tab - Func
-fn make_func_btns() -> impl Widget<CalcState> {
-    let mut first_row = Flex::row();
-    function_button(&mut first_row, "sin", Btn::UnaryOpt(Opt::Sin));
-    function_button(&mut first_row, "cos", Btn::UnaryOpt(Opt::Cos));
-    function_button(&mut first_row, "ln", Btn::UnaryOpt(Opt::Ln));
-
-    let mut second_row = Flex::row();
-    function_button(&mut second_row, "sin⁻¹", Btn::UnaryOpt(Opt::Arcsin));
-    function_button(&mut second_row, "cos⁻¹", Btn::UnaryOpt(Opt::Arccos));
-    function_button(&mut second_row, "log", Btn::UnaryOpt(Opt::Log));
-
-    let mut third_row = Flex::row();
-    function_button(&mut third_row, "tg", Btn::UnaryOpt(Opt::Tg));
-    function_button(&mut third_row, "cotg", Btn::UnaryOpt(Opt::Cotg));
-    function_button(&mut third_row, "logₙ", Btn::BinOpt(Opt::LogN));
-
-    let mut forth_row = Flex::row();
-    function_button(&mut forth_row, "tg⁻¹", Btn::UnaryOpt(Opt::Arctg));
-    function_button(&mut forth_row, "cotg⁻¹", Btn::UnaryOpt(Opt::Arccotg));
-    function_button(&mut forth_row, "mod", Btn::BinOpt(Opt::Mod));
-
-    let mut fifth_row = Flex::row();
-    function_button(&mut fifth_row, "nCr", Btn::BinOpt(Opt::Comb));
-    function_button(&mut fifth_row, "(", Btn::BracketLeft);
-    function_button(&mut fifth_row, ")", Btn::BracketRight);
+        match &self.tooltip {
+            Some(tooltip) => Box::new(button.controller(TooltipController::new(tooltip.clone()))),
+            None => Box::new(button),
+        }
+    }
+}
 
-    Flex::column()
-        .with_flex_child(first_row, 1.)
-        .with_flex_child(second_row, 1.)
-        .with_flex_child(third_row, 1.)
-        .with_flex_child(forth_row, 1.)
-        .with_flex_child(fifth_row, 1.)
+// Grid of function buttons, the rows are laid out from top to bottom
+#[derive(Debug, Clone, PartialEq)]
+struct ButtonLayout {
+    rows: Vec<Vec<ButtonDef>>,
 }
 
-// Add fucntion button to the `flex` widget
-fn function_button(flex: &mut Flex<CalcState>, text: &str, button: Btn) {
-    flex.add_flex_child(generic_button(text, button, BtnType::Function), 1.);
+impl ButtonLayout {
+    // Buttons in the function tab - Main
+    fn main() -> Self {
+        Self {
+            rows: vec![
+                vec![
+                    ButtonDef::new("√", Btn::UnaryOpt(Opt::Sqrt)),
+                    ButtonDef::new("a²", Btn::UnaryOpt(Opt::Pow2)),
+                    ButtonDef::new("e", Btn::Const("e".to_owned())).with_tooltip("2.71"),
+                ],
+                vec![
+                    ButtonDef::new("ⁿ√", Btn::BinOpt(Opt::Root)),
+                    ButtonDef::new("aⁿ", Btn::BinOpt(Opt::Pow)),
+                    ButtonDef::new("π", Btn::Const("pi".to_owned())).with_tooltip("3.14"),
+                ],
+                vec![
+                    ButtonDef::new("³√", Btn::UnaryOpt(Opt::Root3)),
+                    ButtonDef::new("|a|", Btn::UnaryOpt(Opt::Abs)),
+                    ButtonDef::new("n!", Btn::UnaryOpt(Opt::Fact)),
+                ],
+                vec![
+                    ButtonDef::new("sin", Btn::UnaryOpt(Opt::Sin)),
+                    ButtonDef::new("cos", Btn::UnaryOpt(Opt::Cos)),
+                    ButtonDef::new("Rnd", Btn::Random),
+                ],
+                vec![
+                    ButtonDef::new("ANS", Btn::Ans),
+                    ButtonDef::new("(", Btn::BracketLeft),
+                    ButtonDef::new(")", Btn::BracketRight),
+                ],
+            ],
+        }
+    }
+
+    // Buttons in the function tab - Func
+    fn functions() -> Self {
+        Self {
+            rows: vec![
+                vec![
+                    ButtonDef::new("sin", Btn::UnaryOpt(Opt::Sin)),
+                    ButtonDef::new("cos", Btn::UnaryOpt(Opt::Cos)),
+                    ButtonDef::new("ln", Btn::UnaryOpt(Opt::Ln)),
+                ],
+                vec![
+                    ButtonDef::new("sin⁻¹", Btn::UnaryOpt(Opt::Arcsin)),
+                    ButtonDef::new("cos⁻¹", Btn::UnaryOpt(Opt::Arccos)),
+                    ButtonDef::new("log", Btn::UnaryOpt(Opt::Log)),
+                ],
+                vec![
+                    ButtonDef::new("tg", Btn::UnaryOpt(Opt::Tg)),
+                    ButtonDef::new("cotg", Btn::UnaryOpt(Opt::Cotg)),
+                    ButtonDef::new("logₙ", Btn::BinOpt(Opt::LogN)),
+                ],
+                vec![
+                    ButtonDef::new("tg⁻¹", Btn::UnaryOpt(Opt::Arctg)),
+                    ButtonDef::new("cotg⁻¹", Btn::UnaryOpt(Opt::Arccotg)),
+                    ButtonDef::new("mod", Btn::BinOpt(Opt::Mod)),
+                ],
+                vec![
+                    ButtonDef::new("nCr", Btn::BinOpt(Opt::Comb)),
+                    ButtonDef::new("nPr", Btn::BinOpt(Opt::Perm)),
+                    ButtonDef::new("n!", Btn::UnaryOpt(Opt::Fact)),
+                ],
+                vec![
+                    ButtonDef::new("EXP", Btn::Exp).with_tooltip("×10ⁿ"),
+                    ButtonDef::new("(", Btn::BracketLeft),
+                    ButtonDef::new(")", Btn::BracketRight),
+                ],
+            ],
+        }
+    }
+
+    // Actions of all the buttons, row by row
+    fn actions(&self) -> impl Iterator<Item = &Btn> {
+        self.rows.iter().flatten().map(|def| &def.action)
+    }
+
+    // Column of the rows with the buttons
+    fn build(&self) -> impl Widget<CalcState> {
+        let mut column = Flex::column();
+
+        for row in &self.rows {
+            let mut flex = Flex::row();
+            for def in row {
+                flex.add_flex_child(def.build(), 1.);
+            }
+            column.add_flex_child(flex, 1.);
+        }
+
+        column
+    }
 }
 
 // Add operation button to the `flex` widget
@@ -777,7 +882,7 @@ fn generic_button(text: &str, button: Btn, button_type: BtnType) -> impl Widget<
     Padding::new(
         BUTTON_PADDING,
         Label::new(text)
-            .with_text_size(BUTTON_TEXT_SIZE)
+            .with_text_size(FontSizes::BUTTON)
             .center()
             .background(get_button_painter(button_type))
             .expand()
@@ -807,7 +912,7 @@ fn radix_tab(radix: Radix) -> impl Widget<CalcState> {
             TAB_PADDING,
             Label::new(format!("{:?}", radix))
                 .with_text_color(TAB_TEXT_COLOR)
-                .with_text_size(TAB_TEXT_SIZE)
+                .with_text_size(FontSizes::TAB)
                 .center()
                 .background(get_tab_painter())
                 .expand()
@@ -831,7 +936,7 @@ fn function_tab(text: FunctionTabs) -> impl Widget<CalcState> {
             TAB_PADDING,
             Label::new(format!("{:?}", text))
                 .with_text_color(TAB_TEXT_COLOR)
-                .with_text_size(TAB_TEXT_SIZE)
+                .with_text_size(FontSizes::TAB)
                 .center()
                 .background(get_tab_painter())
                 .expand()
@@ -921,3 +1026,75 @@ fn get_add_btn_painter() -> Painter<CalcState> {
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic_layout_omits_functions() {
+        let basic = layout_parts(LayoutMode::Basic);
+        assert!(!basic.contains(&LayoutPart::Functions));
+        assert_eq!(basic.len(), 1);
+
+        let scientific = layout_parts(LayoutMode::Scientific);
+        assert!(scientific.contains(&LayoutPart::Functions));
+        assert_eq!(scientific.len(), 2);
+        assert_eq!(LayoutMode::default(), LayoutMode::Scientific);
+    }
+
+    #[test]
+    fn default_button_layouts() {
+        let main: Vec<_> = ButtonLayout::main().actions().cloned().collect();
+        assert_eq!(
+            main,
+            [
+                Btn::UnaryOpt(Opt::Sqrt),
+                Btn::UnaryOpt(Opt::Pow2),
+                Btn::Const("e".to_owned()),
+                Btn::BinOpt(Opt::Root),
+                Btn::BinOpt(Opt::Pow),
+                Btn::Const("pi".to_owned()),
+                Btn::UnaryOpt(Opt::Root3),
+                Btn::UnaryOpt(Opt::Abs),
+                Btn::UnaryOpt(Opt::Fact),
+                Btn::UnaryOpt(Opt::Sin),
+                Btn::UnaryOpt(Opt::Cos),
+                Btn::Random,
+                Btn::Ans,
+                Btn::BracketLeft,
+                Btn::BracketRight,
+            ]
+        );
+
+        let functions: Vec<_> = ButtonLayout::functions().actions().cloned().collect();
+        assert_eq!(
+            functions,
+            [
+                Btn::UnaryOpt(Opt::Sin),
+                Btn::UnaryOpt(Opt::Cos),
+                Btn::UnaryOpt(Opt::Ln),
+                Btn::UnaryOpt(Opt::Arcsin),
+                Btn::UnaryOpt(Opt::Arccos),
+                Btn::UnaryOpt(Opt::Log),
+                Btn::UnaryOpt(Opt::Tg),
+                Btn::UnaryOpt(Opt::Cotg),
+                Btn::BinOpt(Opt::LogN),
+                Btn::UnaryOpt(Opt::Arctg),
+                Btn::UnaryOpt(Opt::Arccotg),
+                Btn::BinOpt(Opt::Mod),
+                Btn::BinOpt(Opt::Comb),
+                Btn::BinOpt(Opt::Perm),
+                Btn::UnaryOpt(Opt::Fact),
+                Btn::Exp,
+                Btn::BracketLeft,
+                Btn::BracketRight,
+            ]
+        );
+
+        // every row is complete
+        for layout in [ButtonLayout::main(), ButtonLayout::functions()] {
+            assert!(layout.rows.iter().all(|row| row.len() == 3));
+        }
+    }
+}
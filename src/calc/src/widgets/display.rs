@@ -1,14 +1,28 @@
 //! The display UI part of the calculator
 
+use crate::expr_manager::ExprManager;
+use crate::widgets::buttons_ui::TooltipController;
+use crate::widgets::FontSizes;
 use crate::CalcState;
-use druid::widget::{Align, Flex, ViewSwitcher, Container};
-use druid::{theme, TextLayout, Color, Env, UnitPoint, WidgetExt, FontDescriptor, FontFamily };
+use druid::text::{Attribute, RichText};
+use druid::widget::{Align, Container, Flex, ViewSwitcher};
+use druid::{
+    theme, Color, Env, FontDescriptor, FontFamily, Size, TextLayout, UnitPoint, WidgetExt,
+};
 use druid::{widget::Label, Widget};
 use math::number::Radix;
+use math::Number;
 
 pub const ACTIVE_RADIX_COLOR: Color = Color::GREEN;
 pub const ACTIVE_TRIG_UNITS_COLOR: Color = Color::YELLOW;
 pub const ERROR_MSG_COLOR: Color = Color::RED;
+pub const MATCHING_BRACKET_COLOR: Color = Color::rgb8(80, 160, 255);
+pub const NEGATIVE_RESULT_COLOR: Color = Color::rgb8(230, 90, 90);
+pub const RESULT_TEXT_SIZE: f64 = 28.0;
+pub const EXPR_TEXT_SIZE: f64 = 30.0;
+pub const STATUS_TEXT_SIZE: f64 = 10.0;
+/// Appended to results, which don't fit into the display.
+const ELLIPSIS: char = '…';
 
 /// Widget displaying, **status of calculator**,
 /// **current expression** and the **computed result**.
@@ -30,11 +44,15 @@ impl Display {
     const OVERFLOW_RESERVE: f64 = 10.0;
 
     fn new(str: &str) -> Self {
-        let font  = FontDescriptor::new(FontFamily::SYSTEM_UI)
-            .with_size(30.0);
+        let font = FontDescriptor::new(FontFamily::SYSTEM_UI).with_size(EXPR_TEXT_SIZE);
         let lbl = Label::new(str)
-            .with_font(font.clone());
-        Self { lbl, font, max_width: 0.0 }
+            .with_font(font.clone())
+            .with_text_size(FontSizes::EXPRESSION);
+        Self {
+            lbl,
+            font,
+            max_width: 0.0,
+        }
     }
 }
 
@@ -59,8 +77,18 @@ impl Widget<CalcState> for Display {
     fn paint(&mut self, ctx: &mut druid::PaintCtx, data: &CalcState, env: &Env) {
         let expr = data.expr_man.get_display_str(true);
 
-        let mut text = TextLayout::<String>::from_text(&expr);
+        let mut rich = RichText::new(expr.as_str().into());
+        if let Some((bracket, matching)) = cursor_brackets(&data.expr_man) {
+            for idx in [bracket, matching] {
+                if let Some(range) = data.expr_man.display_range(idx, true) {
+                    rich.add_attribute(range, Attribute::text_color(MATCHING_BRACKET_COLOR));
+                }
+            }
+        }
+
+        let mut text = TextLayout::<RichText>::from_text(rich);
         text.set_font(self.font.clone());
+        text.set_text_size(FontSizes::EXPRESSION);
         text.rebuild_if_needed(ctx.text(), env);
 
         let idx = expr.find(crate::expr_manager::CURSOR_CHAR).unwrap();
@@ -74,6 +102,119 @@ impl Widget<CalcState> for Display {
     }
 }
 
+/// Result of the calculation aligned to the right. Results wider than the display are cut
+/// and ended with `ELLIPSIS`.
+struct ResultDisplay {
+    text: TextLayout<String>,
+    max_width: f64,
+}
+
+impl ResultDisplay {
+    fn new() -> Self {
+        let mut text = TextLayout::new();
+        text.set_text_size(FontSizes::RESULT);
+        Self {
+            text,
+            max_width: 0.0,
+        }
+    }
+
+    fn color(data: &CalcState, env: &Env) -> Color {
+        if data.result_is_err {
+            ERROR_MSG_COLOR
+        } else if data.ledger_mode() && data.result_is_negative() {
+            NEGATIVE_RESULT_COLOR
+        } else {
+            env.get(theme::TEXT_COLOR)
+        }
+    }
+}
+
+impl Widget<CalcState> for ResultDisplay {
+    fn event(
+        &mut self,
+        _ctx: &mut druid::EventCtx,
+        _event: &druid::Event,
+        _data: &mut CalcState,
+        _env: &Env,
+    ) {
+    }
+
+    fn lifecycle(
+        &mut self,
+        _ctx: &mut druid::LifeCycleCtx,
+        _event: &druid::LifeCycle,
+        _data: &CalcState,
+        _env: &Env,
+    ) {
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut druid::UpdateCtx,
+        old_data: &CalcState,
+        data: &CalcState,
+        _env: &Env,
+    ) {
+        if old_data.result != data.result || !old_data.config.same(&data.config) {
+            ctx.request_layout();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut druid::LayoutCtx,
+        bc: &druid::BoxConstraints,
+        data: &CalcState,
+        env: &Env,
+    ) -> Size {
+        self.max_width = bc.max().width;
+
+        self.text.set_text(data.result.clone());
+        self.text.set_text_color(Self::color(data, env));
+        self.text.rebuild_if_needed(ctx.text(), env);
+
+        let len = data.result.chars().count();
+        if let Some(fits) = fitting_len(len, self.text.size().width, self.max_width) {
+            let shortened = data.result.chars().take(fits).chain([ELLIPSIS]).collect();
+            self.text.set_text(shortened);
+            self.text.rebuild_if_needed(ctx.text(), env);
+        }
+
+        bc.constrain(Size::new(self.max_width, self.text.size().height))
+    }
+
+    fn paint(&mut self, ctx: &mut druid::PaintCtx, _data: &CalcState, _env: &Env) {
+        let x = (self.max_width - self.text.size().width).max(0.0);
+        self.text.draw(ctx, (x, 0.0));
+    }
+}
+
+/// Number of characters of the text (`len` characters, `text_width` wide) that fit into
+/// `max_width` together with the `ELLIPSIS`. Returns `None` if the whole text fits.
+fn fitting_len(len: usize, text_width: f64, max_width: f64) -> Option<usize> {
+    if len == 0 || text_width <= max_width {
+        return None;
+    }
+
+    let char_width = text_width / len as f64;
+    Some(((max_width / char_width) as usize).saturating_sub(1))
+}
+
+/// Find the bracket next to the cursor (the one before cursor takes precedence)
+/// and its matching partner.
+fn cursor_brackets(expr_man: &ExprManager) -> Option<(usize, usize)> {
+    let cursor = expr_man.cursor_pos();
+    [cursor.checked_sub(1), Some(cursor)]
+        .into_iter()
+        .flatten()
+        .find_map(|pos| {
+            expr_man
+                .matching_bracket(pos)
+                .map(|matching| (pos, matching))
+        })
+}
+
 #[rustfmt::skip]
 fn get_display() -> impl Widget<CalcState> {
     // With druid it is hard to dynamically change color based on CalcState data state.
@@ -89,13 +230,18 @@ fn get_display() -> impl Widget<CalcState> {
 
     let status_row = Align::left(
         Flex::row()
-            .with_flex_child(Label::new("Dec").with_text_size(10.0).disabled_if(radix_eq(Radix::Dec)).env_scope(radix_env), 1.0)
-            .with_flex_child(Label::new("Hex").with_text_size(10.0).disabled_if(radix_eq(Radix::Hex)).env_scope(radix_env), 1.0)
-            .with_flex_child(Label::new("Oct").with_text_size(10.0).disabled_if(radix_eq(Radix::Oct)).env_scope(radix_env), 1.0)
-            .with_flex_child(Label::new("Bin").with_text_size(10.0).disabled_if(radix_eq(Radix::Bin)).env_scope(radix_env), 1.0)
-            .with_flex_spacer(1.0)
-            .with_flex_child(Label::new("Deg").with_text_size(10.0).disabled_if(tuni_eq(true)).env_scope(tuni_env), 1.0)
-            .with_flex_child(Label::new("Rad").with_text_size(10.0).disabled_if(tuni_eq(false)).env_scope(tuni_env), 1.0),
+            .with_flex_child(Label::new("Dec").with_text_size(FontSizes::STATUS).disabled_if(radix_eq(Radix::Dec)).env_scope(radix_env), 1.0)
+            .with_flex_child(Label::new("Hex").with_text_size(FontSizes::STATUS).disabled_if(radix_eq(Radix::Hex)).env_scope(radix_env), 1.0)
+            .with_flex_child(Label::new("Oct").with_text_size(FontSizes::STATUS).disabled_if(radix_eq(Radix::Oct)).env_scope(radix_env), 1.0)
+            .with_flex_child(Label::new("Bin").with_text_size(FontSizes::STATUS).disabled_if(radix_eq(Radix::Bin)).env_scope(radix_env), 1.0)
+            .with_flex_child(Label::dynamic(|data: &CalcState, _| data.simplification().to_owned()).with_text_size(FontSizes::STATUS), 1.0)
+            // Shown while there is a value in the memory register.
+            .with_flex_child(Label::dynamic(|data: &CalcState, _| if *data.memory() == Number::zero() { "" } else { "M" }.to_owned()).with_text_size(FontSizes::STATUS), 1.0)
+            // Shows how the result is displayed, clicking switches between the number and the exact fraction.
+            // Can't use `disabled_if` like the others, disabled label doesn't receive the clicks.
+            .with_flex_child(Label::dynamic(|data: &CalcState, _| if data.fraction_result() { "a/b" } else { "x.y" }.to_owned()).with_text_size(FontSizes::STATUS).on_click(|_ctx, data: &mut CalcState, _| data.toggle_fraction_result()), 1.0)
+            .with_flex_child(Label::new("Deg").with_text_size(FontSizes::STATUS).disabled_if(tuni_eq(true)).env_scope(tuni_env), 1.0)
+            .with_flex_child(Label::new("Rad").with_text_size(FontSizes::STATUS).disabled_if(tuni_eq(false)).env_scope(tuni_env), 1.0),
     );
 
     let disp = ViewSwitcher::new(
@@ -112,12 +258,19 @@ fn get_display() -> impl Widget<CalcState> {
         disp
     );
 
-    let result_row = Align::right(
-        Label::new(|data: &CalcState, _env: &_| data.result.clone())
-            .with_text_size(28.0)
-            .disabled_if(|data: &CalcState, _| data.result_is_err)
-            .env_scope(gen_env(ERROR_MSG_COLOR))
-            .lens(CalcState::all),
+    // Full result is available in a tooltip and by clicking on it, which copies it to clipboard
+    let result_row = ViewSwitcher::new(
+        |data: &CalcState, _| data.result.clone(),
+        |result, _data, _| {
+            let result_display = ResultDisplay::new()
+                .on_click(|_ctx, data: &mut CalcState, _| data.copy_result());
+
+            if result.is_empty() {
+                return Box::new(result_display);
+            }
+
+            Box::new(result_display.controller(TooltipController::new(result.clone())))
+        }
     );
 
     Container::new(
@@ -131,3 +284,20 @@ fn get_display() -> impl Widget<CalcState> {
     .border(Color::GRAY, 2.0)
     .rounded(5.0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_result_overflows() {
+        let result = "1".repeat(100);
+        // 10 px per character, 300 px wide display
+        let fits = fitting_len(result.len(), 1000.0, 300.0);
+        assert_eq!(fits, Some(29));
+
+        assert_eq!(fitting_len(10, 100.0, 300.0), None);
+        assert_eq!(fitting_len(30, 300.0, 300.0), None);
+        assert_eq!(fitting_len(0, 0.0, 300.0), None);
+    }
+}
@@ -1,11 +1,15 @@
 use druid::{
     widget::{Flex, Label, Padding, Scroll},
-    Color, FontDescriptor, FontWeight, Widget,
+    Color, FontDescriptor, FontWeight, Widget, WidgetExt,
 };
+use math::Calculator;
+use rust_i18n::t;
 
 use crate::CalcState;
 
 const WINDOW_PADDING: f64 = 10.;
+const NAME_COLUMN_WIDTH: f64 = 80.;
+const ARGUMENTS_COLUMN_WIDTH: f64 = 80.;
 pub struct HelpWin;
 
 impl HelpWin {
@@ -147,10 +151,50 @@ impl HelpWin {
            Your constant will now be available in the Const Tab for use in your expressions.",
         ));
 
+        page.add_child(build_section(&t!("help.function_reference")));
+        page.add_child(build_function_reference(&Calculator::new()));
+
         Scroll::new(Padding::new(WINDOW_PADDING, page))
     }
 }
 
+/// Name, number of arguments and localized description of every built-in function
+/// and constant of the `calc`
+fn function_reference(calc: &Calculator) -> Vec<(String, u8, String)> {
+    calc.builtins()
+        .map(|(name, var)| {
+            let description = t!(&format!("functions.{name}"));
+            (name.to_owned(), var.argc(), description)
+        })
+        .collect()
+}
+
+/// Table of the built-in functions generated from the `calc`, so it never gets out of date
+pub fn build_function_reference(calc: &Calculator) -> impl Widget<CalcState> {
+    let row = |name: &str, argc: &str, description: &str| {
+        Flex::row()
+            .with_child(build_text(name).fix_width(NAME_COLUMN_WIDTH))
+            .with_child(build_text(argc).fix_width(ARGUMENTS_COLUMN_WIDTH))
+            .with_child(build_text(description))
+    };
+
+    let mut table = Flex::column().cross_axis_alignment(druid::widget::CrossAxisAlignment::Start);
+    table.add_child(
+        row(
+            &t!("help.function"),
+            &t!("help.arguments"),
+            &t!("help.description"),
+        )
+        .padding((0., 10., 0., 5.)),
+    );
+
+    for (name, argc, description) in function_reference(calc) {
+        table.add_child(row(&name, &argc.to_string(), &description));
+    }
+
+    table
+}
+
 fn build_section(text: &str) -> impl Widget<CalcState> {
     Label::new(format!("\n{}", text))
         .with_font(FontDescriptor::default().with_weight(FontWeight::BOLD))
@@ -169,3 +213,33 @@ fn build_text(text: &str) -> impl Widget<CalcState> {
         .with_font(FontDescriptor::default().with_weight(FontWeight::NORMAL))
         .with_text_size(12.)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_lists_all_builtins() {
+        let calc = Calculator::new();
+        let reference = function_reference(&calc);
+
+        let names: Vec<_> = reference.iter().map(|(name, ..)| name.as_str()).collect();
+        let builtins: Vec<_> = calc.builtins().map(|(name, _)| name).collect();
+        assert_eq!(names, builtins);
+        assert!(names.contains(&"sqrt"));
+        assert!(names.contains(&"if"));
+        assert!(names.contains(&"pi"));
+
+        // Every builtin has a description in every language
+        for locale in crate::available_locales() {
+            for name in &builtins {
+                let key = format!("functions.{name}");
+                assert_ne!(
+                    t!(&key, locale = locale),
+                    format!("{locale}.{key}"),
+                    "missing description of {name} in {locale}"
+                );
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use druid::{
-    theme,
+    commands, theme,
     widget::{
         Container, Controller, EnvScope, Flex, Label, Padding, Painter, Scroll, ViewSwitcher,
     },
@@ -38,6 +38,8 @@ impl HistoryWin {
                 Flex::column()
                     .with_child(build_history())
                     .with_spacer(PADDING)
+                    .with_child(build_comparison())
+                    .with_spacer(PADDING)
                     .with_child(make_clear_btn())
                     .with_spacer(PADDING),
             ))
@@ -49,11 +51,25 @@ impl HistoryWin {
 // Render all notes from history
 fn build_history() -> impl Widget<CalcState> {
     Scroll::new(ViewSwitcher::new(
-        |data: &CalcState, _env| data.get_history().get_data().len(),
+        |data: &CalcState, _env| {
+            let history = data.get_history();
+            let selected = history.selected();
+            (
+                history.get_data().len(),
+                selected.first().copied(),
+                selected.get(1).copied(),
+            )
+        },
         |_selector, _data: &CalcState, _env| {
+            let history = _data.get_history();
             let mut column = Flex::<CalcState>::column();
-            for (expr, res) in _data.get_history().get_data().iter().rev() {
-                column.add_child(make_equation(expr, res));
+            for (idx, (expr, res)) in history.get_data().iter().enumerate().rev() {
+                let marker = match history.selected().iter().position(|&sel| sel == idx) {
+                    Some(0) => "A: ",
+                    Some(_) => "B: ",
+                    None => "",
+                };
+                column.add_child(make_equation(idx, &format!("{marker}{expr}"), res));
             }
 
             Box::new(column)
@@ -64,8 +80,16 @@ fn build_history() -> impl Widget<CalcState> {
     .fix_height(EXPRESSION_LIST_HEIGHT)
 }
 
-// Represets one note in the whole history
-fn make_equation(expr: &str, res: &str) -> impl Widget<CalcState> {
+// Difference and ratio of the two results selected by clicking on them
+fn build_comparison() -> impl Widget<CalcState> {
+    Label::dynamic(|data: &CalcState, _env| data.get_history().comparison().join("\n"))
+        .with_text_size(14.)
+        .with_text_alignment(druid::TextAlignment::Start)
+}
+
+// Represets one note in the whole history, clicking selects it for the comparison
+// and double clicking loads it into the display
+fn make_equation(idx: usize, expr: &str, res: &str) -> impl Widget<CalcState> {
     EnvScope::new(
         |env, _data| {
             env.set(druid::theme::SCROLLBAR_PAD, 5.);
@@ -81,6 +105,38 @@ fn make_equation(expr: &str, res: &str) -> impl Widget<CalcState> {
         )
         .horizontal(),
     )
+    .on_click(move |_ctx, data: &mut CalcState, _env| data.get_mut_history().toggle_selection(idx))
+    .controller(LoadController { idx })
+}
+
+// Load the double clicked entry into the display, close the history window
+// and bring the main window to the front
+struct LoadController {
+    idx: usize,
+}
+
+impl<W: Widget<CalcState>> Controller<CalcState, W> for LoadController {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut CalcState,
+        env: &Env,
+    ) {
+        if let Event::MouseDown(mouse) = event {
+            if mouse.count == 2 && data.load_history_entry(self.idx) {
+                // Undo the selection made by the first click
+                data.get_mut_history().toggle_selection(self.idx);
+                ctx.submit_command(commands::SHOW_WINDOW.to(data.get_main_win_id()));
+                ctx.submit_command(commands::CLOSE_WINDOW.to(ctx.window_id()));
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        child.event(ctx, event, data, env)
+    }
 }
 
 fn make_clear_btn() -> impl Widget<CalcState> {
@@ -5,7 +5,7 @@ use std::rc::Rc;
 use druid::{Command, Env, Menu, MenuItem, Selector, Target, WindowId};
 use rust_i18n::*;
 
-use crate::{CalcState, Theme};
+use crate::{CalcState, LayoutMode, MulSymbol, Theme};
 
 pub struct CalcMenu;
 
@@ -41,6 +41,17 @@ fn make_menu(_window: Option<WindowId>, _data: &CalcState, _env: &Env) -> Menu<C
                         .entry(make_language_button("sk".to_owned()))
                         .entry(make_language_button("vi".to_owned())),
                 )
+                .entry(
+                    Menu::new(t!("options.layout"))
+                        .entry(make_layout_button(LayoutMode::Basic))
+                        .entry(make_layout_button(LayoutMode::Scientific)),
+                )
+                .entry(
+                    Menu::new(t!("options.mul_symbol"))
+                        .entry(make_mul_symbol_button(MulSymbol::Dot))
+                        .entry(make_mul_symbol_button(MulSymbol::Cross))
+                        .entry(make_mul_symbol_button(MulSymbol::Star)),
+                )
                 .entry(
                     Menu::new(t!("options.angular_unit"))
                         .entry(make_angular_unit_button("radians", false))
@@ -54,6 +65,27 @@ fn make_menu(_window: Option<WindowId>, _data: &CalcState, _env: &Env) -> Menu<C
                         })
                         .selected_if(|data, _env| data.get_history().recording() == true),
                 )
+                .entry(
+                    MenuItem::new(t!("options.ledger_mode"))
+                        .on_activate(|_ctx, data: &mut CalcState, _env| {
+                            data.set_ledger_mode(!data.ledger_mode());
+                        })
+                        .selected_if(|data, _env| data.ledger_mode()),
+                )
+                .entry(
+                    MenuItem::new(t!("options.percent_results"))
+                        .on_activate(|_ctx, data: &mut CalcState, _env| {
+                            data.set_percent_results(!data.percent_results());
+                        })
+                        .selected_if(|data, _env| data.percent_results()),
+                )
+                .entry(
+                    MenuItem::new(t!("options.accessibility"))
+                        .on_activate(|_ctx, data: &mut CalcState, _env| {
+                            data.set_accessibility(!data.accessibility());
+                        })
+                        .selected_if(|data, _env| data.accessibility()),
+                )
                 .entry(MenuItem::new(t!("options.show_hist")).command(Command::new(
                     SHOW_HISTORY,
                     "".to_owned(),
@@ -104,6 +136,19 @@ fn make_theme_button(theme: Theme) -> MenuItem<CalcState> {
         .on_activate(move |_ctx, data: &mut CalcState, _env| data.set_theme(theme))
 }
 
+fn make_layout_button(mode: LayoutMode) -> MenuItem<CalcState> {
+    let layout_name = format!("layouts.{}", mode.to_string().to_lowercase());
+    MenuItem::new(t!(&layout_name))
+        .selected_if(move |data: &CalcState, _env| data.layout_mode() == mode)
+        .on_activate(move |_ctx, data: &mut CalcState, _env| data.set_layout_mode(mode))
+}
+
+fn make_mul_symbol_button(symbol: MulSymbol) -> MenuItem<CalcState> {
+    MenuItem::new(symbol.symbol())
+        .selected_if(move |data: &CalcState, _env| data.mul_symbol() == symbol)
+        .on_activate(move |_ctx, data: &mut CalcState, _env| data.set_mul_symbol(symbol))
+}
+
 fn make_language_button(lang: String) -> MenuItem<CalcState> {
     let lang_rc = Rc::new(lang);
     let lang_clone = lang_rc.clone();
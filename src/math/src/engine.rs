@@ -1,14 +1,96 @@
 use crate::error::Error;
 use crate::token::*;
-use crate::Variable;
+use crate::unit::{self, Unit};
+use crate::{LazyArgs, Variable};
 use crate::{Number, Result};
 use std::collections::HashMap;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Notation of math expressions accepted by an `Engine`
+pub enum Notation {
+    /// Operators between operands, `1 + 2`
+    Infix,
+    /// Operators after operands, `1 2 +`
+    Postfix,
+    /// Operators before operands, `+ 1 2`
+    Prefix,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A single sub-computation recorded by a tracing `Engine`
+pub enum Step {
+    /// Binary operation, `lhs op rhs = result`
+    Operation {
+        /// Left operand
+        lhs: Number,
+        /// The operator
+        op: Operator,
+        /// Right operand
+        rhs: Number,
+        /// Result of the operation
+        result: Number,
+    },
+    /// Factorial, `num! = result`
+    Factorial {
+        /// The operand
+        num: Number,
+        /// Result of the factorial
+        result: Number,
+    },
+    /// Function call, `name(args) = result`
+    Function {
+        /// Name of the function
+        name: String,
+        /// Evaluated arguments
+        args: Vec<Number>,
+        /// Result of the function
+        result: Number,
+    },
+}
+
 /// The Engine trait
 /// This trait contains 2 parts. `evaluate` and `validate_tokens`
 /// The `validate_tokens` ensures that the input is valid for the current `Engine`
 ///
 pub trait Engine {
+    /// Notation of the expressions this engine evaluates, infix unless the engine says otherwise
+    fn notation(&self) -> Notation {
+        Notation::Infix
+    }
+
+    /// Whether the engine treats adjacent operands like `2pi()` as multiplication
+    fn supports_implicit_multiplication(&self) -> bool {
+        false
+    }
+
+    /// Unit of the last evaluated result, engines that don't track units always return `None`
+    fn result_unit(&self) -> Option<&Unit> {
+        None
+    }
+
+    /// Enable or disable recording of the sub-computations, see `Engine::steps` \
+    /// Returns `false` if the engine doesn't support tracing
+    fn set_tracing(&mut self, _enabled: bool) -> bool {
+        false
+    }
+
+    /// Whether the sub-computations are being recorded, see `Engine::set_tracing`
+    fn tracing(&self) -> bool {
+        false
+    }
+
+    /// Sub-computations of the last evaluation in the order they were computed,
+    /// empty when tracing is disabled
+    fn steps(&self) -> &[Step] {
+        &[]
+    }
+
+    /// Number of operations (binary operators, factorials and function calls) performed
+    /// by the last evaluation, `None` if the engine doesn't count them
+    fn op_count(&self) -> Option<usize> {
+        None
+    }
+
     /// Validate the given token list to ensure that it's executable
     /// This *only* do the semantic check shouldn't perform any heavy operation
     fn validate_tokens(
@@ -40,24 +122,152 @@ pub trait Engine {
 enum ShuntingYardOperator {
     Operator(Operator),
     OpenParen,
+    /// Opening bar of an absolute value
+    OpenBar,
+    /// Unary minus of an operand, which isn't a number (`-sqrt(4)`, `-(1 + 2)`)
+    Negate,
     Comma,
-    Variable(Variable),
+    Variable(String, Variable),
+}
+
+/// Tracks the absolute value bars `|x|`, the same `|` token both opens and closes them.
+/// A bar closes the innermost absolute value, if there is one and the bar follows an operand
+#[derive(Default)]
+struct AbsBars {
+    /// Open brackets and bars, the innermost is the last
+    openers: Vec<Bracket>,
+    last_opened: bool,
+}
+
+impl AbsBars {
+    /// Whether the `token` ends an operand
+    fn ends_operand(&self, token: Option<&Token>) -> bool {
+        match token {
+            Some(
+                Token::Number(_)
+                | Token::FactorialSign
+                | Token::Id(_)
+                | Token::Bracket(Bracket::ParenRight),
+            ) => true,
+            Some(Token::Bracket(Bracket::VerticalLine)) => !self.last_opened,
+            _ => false,
+        }
+    }
+
+    /// Whether a bar following the `last_token` opens an absolute value
+    fn opens(&self, last_token: Option<&Token>) -> bool {
+        self.openers.last() != Some(&Bracket::VerticalLine) || !self.ends_operand(last_token)
+    }
+
+    /// Process a bar following the `last_token`, returns whether it opens an absolute value
+    fn push(&mut self, last_token: Option<&Token>) -> bool {
+        let opens = self.opens(last_token);
+        if opens {
+            self.openers.push(Bracket::VerticalLine);
+        } else {
+            self.openers.pop();
+        }
+
+        self.last_opened = opens;
+        opens
+    }
+
+    /// Process an opening bracket, bars inside it can't close the absolute values outside
+    fn open_paren(&mut self) {
+        self.openers.push(Bracket::ParenLeft);
+    }
+
+    /// Process a closing bracket
+    ///
+    /// # Error
+    /// Error::InvalidToken if an absolute value opened inside the bracket isn't closed, like `(|2)|`
+    fn close_paren(&mut self) -> Result<()> {
+        match self.openers.pop() {
+            Some(Bracket::VerticalLine) => Err(Error::InvalidToken),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Check that all the absolute value bars are closed and don't cross the brackets
+fn validate_bars(tokens: &[Token]) -> Result<()> {
+    let mut bars = AbsBars::default();
+    let mut last_token = None;
+
+    for token in tokens {
+        match token {
+            Token::Bracket(Bracket::VerticalLine) => {
+                bars.push(last_token);
+            }
+            Token::Bracket(Bracket::ParenLeft) => bars.open_paren(),
+            Token::Bracket(Bracket::ParenRight) => bars.close_paren()?,
+            _ => (),
+        }
+
+        last_token = Some(token);
+    }
+
+    if bars.openers.contains(&Bracket::VerticalLine) {
+        return Err(Error::InvalidToken);
+    }
+
+    Ok(())
 }
 
+/// Operand of the `ShuntingYardEngine` together with its unit
+type Operand = (Number, Option<Unit>);
+
 #[derive(Default)]
 /// An modification of the shunting yard algorithm for evaluate infix math notation that allows
 /// functions/constants being used
+///
+/// ```
+/// # use math::engine::{Engine, Notation, ShuntingYardEngine};
+/// let engine = ShuntingYardEngine::default();
+/// assert_eq!(engine.notation(), Notation::Infix);
+/// assert!(engine.supports_implicit_multiplication());
+/// ```
 pub struct ShuntingYardEngine {
     operators: Vec<ShuntingYardOperator>,
-    operands: Vec<Number>,
+    operands: Vec<Operand>,
+    unit: Option<Unit>,
+    steps: Option<Vec<Step>>,
+    op_count: usize,
 }
 
 impl Engine for ShuntingYardEngine {
+    fn supports_implicit_multiplication(&self) -> bool {
+        true
+    }
+
+    fn result_unit(&self) -> Option<&Unit> {
+        self.unit.as_ref()
+    }
+
+    fn set_tracing(&mut self, enabled: bool) -> bool {
+        self.steps = enabled.then(Vec::new);
+        true
+    }
+
+    fn tracing(&self) -> bool {
+        self.steps.is_some()
+    }
+
+    fn steps(&self) -> &[Step] {
+        self.steps.as_deref().unwrap_or_default()
+    }
+
+    fn op_count(&self) -> Option<usize> {
+        Some(self.op_count)
+    }
+
     fn validate_tokens(
         &mut self,
         tokens: &[Token],
         variables: &HashMap<String, Variable>,
     ) -> Result<()> {
+        validate_bars(tokens)?;
+
         let mut iter = tokens.iter().peekable();
         let mut arg_counts = Vec::new();
 
@@ -75,14 +285,18 @@ impl Engine for ShuntingYardEngine {
                 (Token::Number(_), Some(Token::Number(_))) => return Err(Error::MissingOperator),
 
                 (Token::Id(id), next) => {
-                    if next != Some(&&Token::Bracket(Bracket::ParenLeft)) {
-                        return Err(Error::InvalidToken);
-                    }
-
                     let Some(var) = variables.get(id) else {
                         return Err(Error::InvalidToken);
                     };
 
+                    if next != Some(&&Token::Bracket(Bracket::ParenLeft)) {
+                        if var.unit().is_some() {
+                            continue;
+                        }
+
+                        return Err(Error::InvalidToken);
+                    }
+
                     iter.next();
 
                     let argc = var.argc();
@@ -137,14 +351,113 @@ impl Engine for ShuntingYardEngine {
         tokens: &[Token],
         variables: &HashMap<String, Variable>,
     ) -> Result<Number> {
+        self.unit = None;
+        self.op_count = 0;
+        if let Some(steps) = &mut self.steps {
+            steps.clear();
+        }
+
+        let (num, unit) = self.evaluate_operand(tokens, variables)?;
+        self.unit = unit;
+        Ok(num)
+    }
+}
+
+/// Unary minus binds tighter than multiplication, but not than power, so `-(2)^2` is `-4`
+const NEGATION_PRECEDENCE: u8 = 1;
+
+fn operator_precedence(op: Operator) -> u8 {
+    match op {
+        Operator::Plus | Operator::Minus => 0,
+        Operator::Multiply | Operator::Divide => 1,
+        Operator::Power | Operator::Modulo => 2,
+    }
+}
+
+fn evaluate_expr(
+    (lhs, lhs_unit): Operand,
+    (rhs, rhs_unit): Operand,
+    op: Operator,
+) -> Result<Operand> {
+    let unit = match op {
+        Operator::Plus | Operator::Minus | Operator::Modulo => unit::add(lhs_unit, rhs_unit)?,
+        Operator::Multiply => unit::mul(lhs_unit, rhs_unit)?,
+        Operator::Divide => unit::div(lhs_unit, rhs_unit)?,
+        Operator::Power => unit::pow(lhs_unit, &rhs, rhs_unit)?,
+    };
+
+    let num = match op {
+        Operator::Plus => lhs.add(rhs),
+        Operator::Minus => lhs.sub(rhs),
+        Operator::Multiply => lhs.mul(rhs),
+        Operator::Divide => lhs.div(rhs),
+        Operator::Power => lhs.power(rhs),
+        Operator::Modulo => lhs.modulo(rhs),
+    }?;
+
+    Ok((num, unit))
+}
+
+/// Split the arguments of a function call, `tokens` starts with its opening bracket \
+/// Returns the arguments and index of the closing bracket
+fn split_arguments(tokens: &[Token]) -> (Vec<&[Token]>, usize) {
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut start = 1;
+
+    for (idx, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Bracket(Bracket::ParenLeft) => depth += 1,
+            Token::Bracket(Bracket::ParenRight) => {
+                depth -= 1;
+
+                if depth == 0 {
+                    args.push(&tokens[start..idx]);
+                    return (args, idx);
+                }
+            }
+            Token::Comma if depth == 1 => {
+                args.push(&tokens[start..idx]);
+                start = idx + 1;
+            }
+            _ => (),
+        }
+    }
+
+    // Unclosed bracket, all the remaining tokens are arguments
+    args.push(&tokens[start.min(tokens.len())..]);
+    (args, tokens.len().saturating_sub(1))
+}
+
+/// Negate the operand keeping its unit
+fn negate((num, unit): Operand) -> Result<Operand> {
+    Ok((num.mul(-1)?, unit))
+}
+
+/// Missing left operand of unary `+` or `-` is a zero in the unit of the right operand
+fn implicit_zero(op: Operator, rhs: &Operand) -> Option<Operand> {
+    matches!(op, Operator::Plus | Operator::Minus).then(|| (Number::zero(), rhs.1.clone()))
+}
+
+impl ShuntingYardEngine {
+    /// Evaluate the `tokens` to an operand, the recorded steps and the operation count are kept
+    fn evaluate_operand(
+        &mut self,
+        tokens: &[Token],
+        variables: &HashMap<String, Variable>,
+    ) -> Result<Operand> {
         self.operators.clear();
         self.operands.clear();
 
         let mut iter = tokens.iter().peekable();
         let mut last_token = None;
         let mut negate_operand = false;
+        let mut bars = AbsBars::default();
 
         while let Some(token) = iter.next() {
+            // Last token processed in this iteration
+            let mut current = token;
+
             match token {
                 Token::Number(val) => {
                     let mut num = val.clone();
@@ -153,7 +466,7 @@ impl Engine for ShuntingYardEngine {
                         negate_operand = false;
                     }
 
-                    self.store_operand(num);
+                    self.store_operand((num, None));
                 }
                 Token::Operator(op) => {
                     let mut op = *op;
@@ -171,42 +484,90 @@ impl Engine for ShuntingYardEngine {
                     }
 
                     // Handle the `+` `-` sign of a number
-                    if matches!(
-                        (last_token, op, iter.peek()),
-                        (
-                            None | Some(
-                                &Token::Comma
-                                    | &Token::Bracket(Bracket::ParenLeft)
-                                    | &Token::Operator(
-                                        Operator::Multiply
-                                            | Operator::Divide
-                                            | Operator::Power
-                                            | Operator::Modulo
-                                    )
+                    let sign_position = match last_token {
+                        None
+                        | Some(
+                            &Token::Comma
+                            | &Token::Bracket(Bracket::ParenLeft)
+                            | &Token::Operator(
+                                Operator::Multiply
+                                | Operator::Divide
+                                | Operator::Power
+                                | Operator::Modulo,
                             ),
-                            Operator::Plus | Operator::Minus,
-                            Some(Token::Number(_)),
-                        )
-                    ) {
-                        negate_operand = op == Operator::Minus;
+                        ) => true,
+                        Some(&Token::Bracket(Bracket::VerticalLine)) => bars.last_opened,
+                        _ => false,
+                    };
+
+                    if sign_position && matches!(op, Operator::Plus | Operator::Minus) {
+                        if matches!(iter.peek(), Some(Token::Number(_))) {
+                            negate_operand = op == Operator::Minus;
+                        } else if op == Operator::Minus {
+                            self.operators.push(ShuntingYardOperator::Negate);
+                        }
                         continue;
                     }
 
                     self.operator_handle(op)?;
                 }
                 Token::FactorialSign => {
-                    let num = self.operands.pop().unwrap().factorial()?;
-                    self.store_operand(num);
+                    let (num, unit) = self.operands.pop().ok_or(Error::MissingOperand)?;
+                    if unit.is_some() {
+                        return Err(Error::UnitMismatch);
+                    }
+
+                    let result = num.factorial()?;
+                    self.op_count += 1;
+                    self.record(|| Step::Factorial {
+                        num,
+                        result: result.clone(),
+                    });
+                    self.store_operand((result, None));
                 }
 
                 Token::Bracket(Bracket::ParenLeft) => {
+                    bars.open_paren();
                     self.operators.push(ShuntingYardOperator::OpenParen);
                 }
-                Token::Bracket(Bracket::ParenRight) => self.closing_bracket_handle()?,
-                Token::Bracket(Bracket::VerticalLine) => todo!(),
+                Token::Bracket(Bracket::ParenRight) => {
+                    bars.close_paren()?;
+                    self.closing_bracket_handle()?;
+                }
+                Token::Bracket(Bracket::VerticalLine) => {
+                    if bars.push(last_token) {
+                        self.operators.push(ShuntingYardOperator::OpenBar);
+                    } else {
+                        self.closing_bar_handle()?;
+                    }
+                }
                 Token::Id(id) => {
                     let var = variables.get(id).cloned().unwrap();
-                    self.operators.push(ShuntingYardOperator::Variable(var));
+
+                    match var {
+                        Variable::ConstantWithUnit(num, unit)
+                            if iter.peek() != Some(&&Token::Bracket(Bracket::ParenLeft)) =>
+                        {
+                            self.store_operand((num, Some(unit)));
+                        }
+                        Variable::LazyFunction { ptr, .. } => {
+                            // Pass the arguments un-evaluated and skip them up to the closing
+                            // bracket of the function
+                            let rest = &tokens[tokens.len() - iter.len()..];
+                            let (args, end) = split_arguments(rest);
+                            let args = LazyArgs::from_tokens(args, variables, self);
+                            let val = ptr(&args)?;
+                            let unit = args.into_unit();
+                            self.op_count += 1;
+
+                            iter.nth(end);
+                            current = &rest[end];
+                            self.store_operand((val, unit));
+                        }
+                        var => self
+                            .operators
+                            .push(ShuntingYardOperator::Variable(id.clone(), var)),
+                    }
                 }
                 Token::Comma => {
                     if let Some(val) = self.finalize()? {
@@ -216,20 +577,29 @@ impl Engine for ShuntingYardEngine {
                 }
             }
 
-            // Handle the hidden multiply sign in algebraic notation
+            // Handle the hidden multiply sign in algebraic notation, a closing bracket chains
+            // it too, so `3(2)(4)` is `3 * 2 * 4` and `(1 + 2)(3 + 4)` is `3 * 7`
+            // and so does a closing bar `|2||3|`
             if let Some(next_token) = iter.peek() {
-                if token != *next_token {
-                    let left = matches!(
-                        token,
+                let bar = Token::Bracket(Bracket::VerticalLine);
+                if current != *next_token || current == &bar {
+                    let left = match current {
                         Token::Number(_)
-                            | Token::FactorialSign
-                            | Token::Bracket(Bracket::ParenRight)
-                    );
+                        | Token::FactorialSign
+                        | Token::Bracket(Bracket::ParenRight) => true,
+                        Token::Bracket(Bracket::VerticalLine) => !bars.last_opened,
+                        // Constant with a unit used without brackets
+                        Token::Id(_) => *next_token != &Token::Bracket(Bracket::ParenLeft),
+                        _ => false,
+                    };
 
-                    let right = matches!(
-                        next_token,
-                        Token::Number(_) | Token::Id(_) | Token::Bracket(Bracket::ParenLeft)
-                    );
+                    let right = match next_token {
+                        Token::Number(_) | Token::Id(_) | Token::Bracket(Bracket::ParenLeft) => {
+                            true
+                        }
+                        Token::Bracket(Bracket::VerticalLine) => bars.opens(Some(current)),
+                        _ => false,
+                    };
 
                     if left && right {
                         self.operator_handle(Operator::Multiply)?;
@@ -237,55 +607,87 @@ impl Engine for ShuntingYardEngine {
                 }
             }
 
-            last_token.replace(token);
+            last_token.replace(current);
         }
 
         self.finalize()?
             .or_else(|| self.operands.pop())
             .ok_or(Error::MissingOperand)
     }
-}
 
-fn operator_precedence(op: Operator) -> u8 {
-    match op {
-        Operator::Plus | Operator::Minus => 0,
-        Operator::Multiply | Operator::Divide => 1,
-        Operator::Power | Operator::Modulo => 2,
+    /// Evaluate an argument of a lazy function in the middle of the evaluation, see `LazyArgs`
+    pub(crate) fn evaluate_nested(
+        &mut self,
+        tokens: &[Token],
+        variables: &HashMap<String, Variable>,
+    ) -> Result<Operand> {
+        let operators = std::mem::take(&mut self.operators);
+        let operands = std::mem::take(&mut self.operands);
+        let res = self.evaluate_operand(tokens, variables);
+        self.operators = operators;
+        self.operands = operands;
+        res
     }
-}
 
-fn evaluate_expr(lhs: Number, rhs: Number, op: Operator) -> Result<Number> {
-    match op {
-        Operator::Plus => lhs.add(rhs),
-        Operator::Minus => lhs.sub(rhs),
-        Operator::Multiply => lhs.mul(rhs),
-        Operator::Divide => lhs.div(rhs),
-        Operator::Power => lhs.power(rhs),
-        Operator::Modulo => lhs.modulo(rhs),
+    fn store_operand(&mut self, val: Operand) {
+        self.operands.push(val);
     }
-}
 
-impl ShuntingYardEngine {
-    fn store_operand(&mut self, val: Number) {
-        self.operands.push(val);
+    /// Record the step if tracing is enabled, `step` is only built when it's recorded
+    fn record(&mut self, step: impl FnOnce() -> Step) {
+        if let Some(steps) = &mut self.steps {
+            steps.push(step());
+        }
+    }
+
+    /// `evaluate_expr` recording the operation as a step and counting it
+    fn apply(&mut self, lhs: Operand, rhs: Operand, op: Operator) -> Result<Operand> {
+        let operands = self.steps.is_some().then(|| (lhs.0.clone(), rhs.0.clone()));
+        let val = evaluate_expr(lhs, rhs, op)?;
+        self.op_count += 1;
+
+        if let Some((lhs, rhs)) = operands {
+            self.record(|| Step::Operation {
+                lhs,
+                op,
+                rhs,
+                result: val.0.clone(),
+            });
+        }
+
+        Ok(val)
     }
 
     fn operator_handle(&mut self, op: Operator) -> Result<()> {
         let current_precedence = operator_precedence(op);
 
-        while let Some(ShuntingYardOperator::Operator(last_op)) = self.operators.last() {
+        loop {
+            let last_op = match self.operators.last() {
+                Some(ShuntingYardOperator::Negate) if current_precedence <= NEGATION_PRECEDENCE => {
+                    self.operators.pop();
+                    let val = self.operands.pop().ok_or(Error::MissingOperand)?;
+                    let val = negate(val)?;
+                    self.store_operand(val);
+                    continue;
+                }
+                Some(ShuntingYardOperator::Operator(last_op)) => last_op,
+                _ => break,
+            };
+
             let last_precedence = operator_precedence(*last_op);
             if current_precedence > last_precedence {
                 break;
             }
 
-            let rhs = self.operands.pop().unwrap();
+            let rhs = self.operands.pop().ok_or(Error::MissingOperand)?;
             let lhs = self
                 .operands
                 .pop()
-                .or_else(|| matches!(last_op, Operator::Plus | Operator::Minus).then(Number::zero))
+                .or_else(|| implicit_zero(*last_op, &rhs))
                 .ok_or(Error::MissingOperand)?;
-            self.store_operand(evaluate_expr(lhs, rhs, *last_op)?);
+            let last_op = *last_op;
+            let val = self.apply(lhs, rhs, last_op)?;
+            self.store_operand(val);
             self.operators.pop();
         }
 
@@ -298,37 +700,78 @@ impl ShuntingYardEngine {
             self.store_operand(num);
         }
 
-        if let Some(ShuntingYardOperator::Variable(var)) = self.operators.last() {
+        if let Some(ShuntingYardOperator::Variable(name, var)) = self.operators.last() {
             let argc = var.argc();
             let mut argv = Vec::with_capacity(argc as usize);
 
             for _ in 0..argc {
-                argv.insert(0, self.operands.pop().unwrap());
+                let (num, unit) = self.operands.pop().ok_or(Error::MissingOperand)?;
+                if unit.is_some() {
+                    return Err(Error::UnitMismatch);
+                }
+
+                argv.insert(0, num);
             }
 
             let val = var.calc(&argv)?;
+            let unit = var.unit().cloned();
+            self.op_count += 1;
+            let name = name.clone();
+            self.record(|| Step::Function {
+                name,
+                args: argv,
+                result: val.clone(),
+            });
             self.operators.pop();
+            self.store_operand((val, unit));
+        }
+
+        Ok(())
+    }
+
+    /// Apply the absolute value to the operand enclosed by the bars
+    fn closing_bar_handle(&mut self) -> Result<()> {
+        if let Some(val) = self.finalize()? {
             self.store_operand(val);
         }
 
+        let (num, unit) = self.operands.pop().ok_or(Error::MissingOperand)?;
+        let result = num.abs()?;
+        self.op_count += 1;
+        self.record(|| Step::Function {
+            name: String::from("abs"),
+            args: vec![num],
+            result: result.clone(),
+        });
+        self.store_operand((result, unit));
+
         Ok(())
     }
 
-    fn finalize(&mut self) -> Result<Option<Number>> {
+    fn finalize(&mut self) -> Result<Option<Operand>> {
         let mut res = None;
 
         while let Some(operator) = self.operators.pop() {
-            let ShuntingYardOperator::Operator(op) = operator else {
-                break;
+            let op = match operator {
+                ShuntingYardOperator::Operator(op) => op,
+                ShuntingYardOperator::Negate => {
+                    let val = res.take().or_else(|| self.operands.pop());
+                    res.replace(negate(val.ok_or(Error::MissingOperand)?)?);
+                    continue;
+                }
+                _ => break,
             };
 
-            let rhs = res.clone().or_else(|| self.operands.pop()).unwrap();
+            let rhs = res
+                .take()
+                .or_else(|| self.operands.pop())
+                .ok_or(Error::MissingOperand)?;
             let lhs = self
                 .operands
                 .pop()
-                .or_else(|| matches!(op, Operator::Plus | Operator::Minus).then(Number::zero))
+                .or_else(|| implicit_zero(op, &rhs))
                 .ok_or(Error::MissingOperand)?;
-            res.replace(evaluate_expr(lhs, rhs, op)?);
+            res.replace(self.apply(lhs, rhs, op)?);
         }
 
         Ok(res)
@@ -1,6 +1,7 @@
 /// Error
 ///
 ///
+use crate::Number;
 use thiserror::Error;
 
 #[derive(Debug, Error, PartialEq, Eq)]
@@ -22,6 +23,13 @@ pub enum Error {
     /// Logarithm of a number that is less or equal zero
     LogUndefinedNumber,
 
+    #[error(
+        "Real logarithm of {} is undefined, the argument has to be greater than zero",
+        .0.to_string(Default::default(), 6)
+    )]
+    /// Logarithm of the contained number, which is less or equal zero
+    LogNonPositive(Number),
+
     #[error("Zero nth root")]
     /// Zero nth root
     ZeroNthRoot,
@@ -38,6 +46,10 @@ pub enum Error {
     /// Error message
     Message(String),
 
+    #[error("Empty expression")]
+    /// Expression doesn't contain any token
+    EmptyExpression,
+
     #[error("Missing operand to process")]
     /// Missing operand
     MissingOperand,
@@ -54,7 +66,19 @@ pub enum Error {
     /// Invalid Token
     InvalidToken,
 
+    #[error("Mismatched units")]
+    /// Operands have units that can't be combined by the operation
+    UnitMismatch,
+
     #[error("The token at index `{0}` isn't valid")]
     /// Unsupported Token
     UnsupportedToken(usize),
+
+    #[error("Result is too large")]
+    /// Result exceeds the size allowed by `Calculator::set_max_result_bits`
+    Overflow,
+
+    #[error("Constant is used by {}", .0.join(", "))]
+    /// Constant can't be removed, other constants are defined using it
+    ConstantInUse(Vec<String>),
 }
@@ -15,10 +15,20 @@ pub mod token;
 /// Engine to perform math evaluation
 pub mod engine;
 
+/// Units of constants
+pub mod unit;
+
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt;
+use std::panic;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
 pub use engine::Engine;
 pub use number::Number;
+pub use unit::Unit;
 
 /// Result type for this library
 pub type Result<T> = std::result::Result<T, error::Error>;
@@ -29,6 +39,9 @@ pub enum Variable {
     /// A constant, same as a function without parameter
     Constant(Number),
 
+    /// A constant tagged with a unit, it can be used without brackets, like `3meter`
+    ConstantWithUnit(Number, Unit),
+
     /// A function
     Function {
         /// Number of parameters
@@ -36,31 +49,206 @@ pub enum Variable {
         /// Pointer to the function itself
         ptr: fn(&[Number]) -> Result<Number>,
     },
+
+    /// A function receiving its arguments un-evaluated, so it can skip evaluating some of them
+    LazyFunction {
+        /// Number of parameters
+        argc: u8,
+        /// Pointer to the function itself
+        ptr: fn(&LazyArgs) -> Result<Number>,
+    },
+}
+
+/// Arguments of a `Variable::LazyFunction`, each is evaluated only when requested
+pub struct LazyArgs<'a> {
+    inner: LazyArgsInner<'a>,
+}
+
+enum LazyArgsInner<'a> {
+    Tokens {
+        args: Vec<&'a [token::Token]>,
+        variables: &'a HashMap<String, Variable>,
+        engine: RefCell<&'a mut engine::ShuntingYardEngine>,
+        unit: RefCell<Option<Unit>>,
+    },
+    Values(&'a [Number]),
+}
+
+impl<'a> LazyArgs<'a> {
+    /// Arguments as token lists evaluated with the given `variables` by the `engine` evaluating
+    /// the lazy function, so they are traced and counted along with the rest of the expression
+    pub fn from_tokens(
+        args: Vec<&'a [token::Token]>,
+        variables: &'a HashMap<String, Variable>,
+        engine: &'a mut engine::ShuntingYardEngine,
+    ) -> Self {
+        Self {
+            inner: LazyArgsInner::Tokens {
+                args,
+                variables,
+                engine: RefCell::new(engine),
+                unit: RefCell::new(None),
+            },
+        }
+    }
+
+    /// Already evaluated arguments
+    pub fn from_values(values: &'a [Number]) -> Self {
+        Self {
+            inner: LazyArgsInner::Values(values),
+        }
+    }
+
+    /// Number of arguments
+    pub fn len(&self) -> usize {
+        match &self.inner {
+            LazyArgsInner::Tokens { args, .. } => args.len(),
+            LazyArgsInner::Values(values) => values.len(),
+        }
+    }
+
+    /// Whether there are no arguments
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Evaluate the argument at `idx`
+    ///
+    /// # Error
+    /// Error::InvalidArguments if there is no argument at `idx`
+    ///
+    /// ```
+    /// # use math::{LazyArgs, Number, error::Error};
+    /// let values = [Number::from(1), Number::from(2)];
+    /// let args = LazyArgs::from_values(&values);
+    ///
+    /// assert_eq!(args.eval(1), Ok(Number::from(2)));
+    /// assert_eq!(args.eval(2), Err(Error::InvalidArguments));
+    /// ```
+    pub fn eval(&self, idx: usize) -> Result<Number> {
+        match &self.inner {
+            LazyArgsInner::Tokens {
+                args,
+                variables,
+                engine,
+                unit,
+            } => {
+                let tokens = args.get(idx).ok_or(error::Error::InvalidArguments)?;
+                let (num, num_unit) = engine.borrow_mut().evaluate_nested(tokens, variables)?;
+                unit.replace(num_unit);
+                Ok(num)
+            }
+            LazyArgsInner::Values(values) => values
+                .get(idx)
+                .cloned()
+                .ok_or(error::Error::InvalidArguments),
+        }
+    }
+
+    /// Unit of the last evaluated argument, which is the unit of the result of functions
+    /// like `if` returning one of their arguments
+    pub(crate) fn into_unit(self) -> Option<Unit> {
+        match self.inner {
+            LazyArgsInner::Tokens { unit, .. } => unit.into_inner(),
+            LazyArgsInner::Values(_) => None,
+        }
+    }
 }
 
 impl Variable {
     /// Get the number of argument for this variable to work
     pub fn argc(&self) -> u8 {
         match self {
-            Self::Constant(_) => 0,
-            Self::Function { argc, .. } => *argc,
+            Self::Constant(_) | Self::ConstantWithUnit(..) => 0,
+            Self::Function { argc, .. } | Self::LazyFunction { argc, .. } => *argc,
+        }
+    }
+    /// Get the unit of the variable, only constants tagged with a unit have one
+    pub fn unit(&self) -> Option<&Unit> {
+        match self {
+            Self::ConstantWithUnit(_, unit) => Some(unit),
+            _ => None,
         }
     }
     /// Calculate the value of the variable
     pub fn calc(&self, nums: &[Number]) -> Result<Number> {
         match self {
-            Self::Constant(v) => Ok(v.clone()),
+            Self::Constant(v) | Self::ConstantWithUnit(v, _) => Ok(v.clone()),
             Self::Function { ptr, .. } => (ptr)(nums),
+            Self::LazyFunction { ptr, .. } => (ptr)(&LazyArgs::from_values(nums)),
         }
     }
 }
 
+/// Unit of the angles taken by the trigonometric functions and returned by their inverses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AngleMode {
+    /// Full angle is `2 * pi`
+    #[default]
+    Radians,
+    /// Full angle is `360`
+    Degrees,
+}
+
+/// Default of `Calculator::max_result_bits`, about 30 000 decimal digits
+pub const DEFAULT_MAX_RESULT_BITS: u64 = 100_000;
+
+/// Pointer to a built-in function, see `Variable::Function`
+type FunctionPtr = fn(&[Number]) -> Result<Number>;
+
+/// Trigonometric functions and their inverses working with the angles in the `mode`,
+/// with the number of their parameters
+fn trig_functions(mode: AngleMode) -> [(&'static str, u8, FunctionPtr); 13] {
+    match mode {
+        AngleMode::Radians => [
+            ("sin", 1, |nums| nums[0].sin()),
+            ("cos", 1, |nums| nums[0].cos()),
+            ("tg", 1, |nums| nums[0].tg()),
+            ("cotg", 1, |nums| nums[0].cotg()),
+            ("arcsin", 1, |nums| nums[0].arcsin()),
+            ("arccos", 1, |nums| nums[0].arccos()),
+            ("arctg", 1, |nums| nums[0].arctg()),
+            ("arccotg", 1, |nums| nums[0].arccotg()),
+            ("sec", 1, |nums| nums[0].sec()),
+            ("csc", 1, |nums| nums[0].csc()),
+            ("arcsec", 1, |nums| nums[0].arcsec()),
+            ("arccsc", 1, |nums| nums[0].arccsc()),
+            ("atan2", 2, |nums| Number::atan2(&nums[0], &nums[1])),
+        ],
+        AngleMode::Degrees => [
+            ("sin", 1, |nums| nums[0].to_radians()?.sin()),
+            ("cos", 1, |nums| nums[0].to_radians()?.cos()),
+            ("tg", 1, |nums| nums[0].to_radians()?.tg()),
+            ("cotg", 1, |nums| nums[0].to_radians()?.cotg()),
+            ("arcsin", 1, |nums| nums[0].arcsin()?.to_degrees()),
+            ("arccos", 1, |nums| nums[0].arccos()?.to_degrees()),
+            ("arctg", 1, |nums| nums[0].arctg()?.to_degrees()),
+            ("arccotg", 1, |nums| nums[0].arccotg()?.to_degrees()),
+            ("sec", 1, |nums| nums[0].to_radians()?.sec()),
+            ("csc", 1, |nums| nums[0].to_radians()?.csc()),
+            ("arcsec", 1, |nums| nums[0].arcsec()?.to_degrees()),
+            ("arccsc", 1, |nums| nums[0].arccsc()?.to_degrees()),
+            ("atan2", 2, |nums| {
+                Number::atan2(&nums[0], &nums[1])?.to_degrees()
+            }),
+        ],
+    }
+}
+
 /// Calculator struct
 pub struct Calculator {
     tokens: Vec<token::Token>,
     engine: Box<dyn Engine>,
     variables: HashMap<String, Variable>,
+    /// Constants referenced by the constants defined by `Calculator::add_constant_expr`
+    dependencies: HashMap<String, Vec<String>>,
     builtin_keywords: Box<[&'static str]>,
+    last_result_exact: bool,
+    implicit_ans: bool,
+    snap_to_integer: bool,
+    leading_zeros: token::LeadingZeros,
+    angle_mode: AngleMode,
+    max_result_bits: u64,
 }
 
 impl Default for Calculator {
@@ -75,15 +263,23 @@ impl Calculator {
         let mut res = Self {
             tokens: Vec::new(),
             variables: HashMap::new(),
+            dependencies: HashMap::new(),
             engine: Box::<engine::ShuntingYardEngine>::default() as Box<_>,
             builtin_keywords: Box::default(),
+            last_result_exact: true,
+            implicit_ans: false,
+            snap_to_integer: false,
+            leading_zeros: token::LeadingZeros::default(),
+            angle_mode: AngleMode::default(),
+            max_result_bits: DEFAULT_MAX_RESULT_BITS,
         };
 
         res.add_constant("e", Number::e());
         res.add_constant("pi", Number::pi());
+        res.add_constant("phi", Number::phi());
 
         let mut keywords = res.add_builtin_function();
-        keywords.extend_from_slice(&["mod", "e", "pi"]);
+        keywords.extend_from_slice(&["mod", "e", "pi", "phi"]);
         res.builtin_keywords = keywords.into_boxed_slice();
 
         res
@@ -99,22 +295,59 @@ impl Calculator {
 
         add_function("root", 2, |nums| nums[1].root(&nums[0]));
         add_function("sqrt", 1, |nums| nums[0].sqrt());
+        add_function("cbrt", 1, |nums| nums[0].root(Number::from(3)));
         add_function("ln", 1, |nums| nums[0].ln());
         add_function("log2", 1, |nums| nums[0].log2());
         add_function("log10", 1, |nums| nums[0].log10());
-        add_function("log", 2, |nums| nums[1].log(&nums[0]));
-        add_function("sin", 1, |nums| nums[0].sin());
-        add_function("cos", 1, |nums| nums[0].cos());
-        add_function("tg", 1, |nums| nums[0].tg());
-        add_function("cotg", 1, |nums| nums[0].cotg());
-        add_function("arcsin", 1, |nums| nums[0].arcsin());
-        add_function("arccos", 1, |nums| nums[0].arccos());
-        add_function("arctg", 1, |nums| nums[0].arctg());
-        add_function("arccotg", 1, |nums| nums[0].arccotg());
+        add_function("log", 2, |nums| log_cached(&nums[1], &nums[0]));
+        for (name, argc, ptr) in trig_functions(AngleMode::Radians) {
+            add_function(name, argc, ptr);
+        }
         add_function("pow", 2, |nums| nums[0].power(&nums[1]));
         add_function("abs", 1, |nums| nums[0].abs());
+        add_function("floor", 1, |nums| Ok(nums[0].floor()));
+        add_function("ceil", 1, |nums| Ok(nums[0].ceil()));
+        add_function("round", 1, |nums| Ok(nums[0].round()));
+        add_function("trunc", 1, |nums| Ok(nums[0].trunc()));
+        add_function("gamma", 1, |nums| nums[0].gamma());
         add_function("comb", 2, |nums| Number::combination(&nums[0], &nums[1]));
+        add_function("perm", 2, |nums| Number::permutation(&nums[0], &nums[1]));
+        add_function("gcd", 2, |nums| nums[0].gcd(&nums[1]));
+        add_function("lcm", 2, |nums| nums[0].lcm(&nums[1]));
         add_function("random", 0, |_| Ok(Number::random()));
+        add_function("digitsum", 1, |nums| nums[0].digit_sum(number::Radix::Dec));
+        add_function("sigmoid", 1, |nums| nums[0].sigmoid());
+        add_function("logit", 1, |nums| nums[0].logit());
+        // `step(0)` is 1/2, the average of both sides
+        add_function("step", 1, |nums| Ok(nums[0].heaviside(Number::new(1, 2)?)));
+        add_function("sign", 1, |nums| Ok(nums[0].signum()));
+        add_function("isqrt", 1, |nums| nums[0].isqrt());
+        add_function("exp", 1, |nums| nums[0].exp());
+        add_function("sinh", 1, |nums| nums[0].sinh());
+        add_function("cosh", 1, |nums| nums[0].cosh());
+        add_function("tanh", 1, |nums| nums[0].tanh());
+        add_function("coth", 1, |nums| nums[0].coth());
+        add_function("arsinh", 1, |nums| nums[0].arsinh());
+        add_function("arcosh", 1, |nums| nums[0].arcosh());
+        add_function("artanh", 1, |nums| nums[0].artanh());
+
+        // `if(cond, a, b)` is `a` for nonzero `cond`, otherwise `b`. Only the chosen branch is
+        // evaluated, so `if(x, 1/x, 0)` is safe
+        keywords.push("if");
+        self.variables.insert(
+            String::from("if"),
+            Variable::LazyFunction {
+                argc: 3,
+                ptr: |args| {
+                    let branch = if args.eval(0)? != Number::zero() {
+                        1
+                    } else {
+                        2
+                    };
+                    args.eval(branch)
+                },
+            },
+        );
 
         keywords
     }
@@ -130,16 +363,192 @@ impl Calculator {
     /// assert_eq!(calculator.add_constant("sqrt", Number::random()), false);
     /// ```
     pub fn add_constant(&mut self, name: &str, num: impl Into<Number>) -> bool {
+        self.set_variable(name, Variable::Constant(num.into()))
+    }
+
+    /// Same as `Calculator::add_constant`, but the constant is tagged with a `unit`,
+    /// which is tracked through the evaluation, see `Calculator::result_unit`
+    ///
+    /// ```
+    /// # use math::{Calculator, Number};
+    /// let mut calculator = Calculator::new();
+    /// calculator.add_constant_with_unit("meter", 1, "meter");
+    ///
+    /// assert_eq!(calculator.evaluate("3meter * 2"), Ok(Number::from(6)));
+    /// assert_eq!(calculator.result_unit().unwrap().to_string(), "meter");
+    /// ```
+    pub fn add_constant_with_unit(
+        &mut self,
+        name: &str,
+        num: impl Into<Number>,
+        unit: &str,
+    ) -> bool {
+        self.set_variable(
+            name,
+            Variable::ConstantWithUnit(num.into(), Unit::new(unit)),
+        )
+    }
+
+    /// Add new constant or update existing one with the value of the `expr`, same rules as for
+    /// `Calculator::add_constant` apply. The constants referenced by the `expr` are remembered,
+    /// see `Calculator::constant_dependencies`
+    ///
+    /// # Error
+    /// Error::InvalidArguments if the name is a built-in keyword, or the error of the evaluation
+    ///
+    /// ```
+    /// # use math::{Calculator, Number};
+    /// # fn main() -> math::Result<()> {
+    /// let mut calculator = Calculator::new();
+    /// calculator.add_constant("a", 2);
+    ///
+    /// assert_eq!(calculator.add_constant_expr("b", "a() * 3 + 1")?, Number::from(7));
+    /// assert_eq!(calculator.get_constant("b"), Some(Number::from(7)));
+    /// assert_eq!(calculator.constant_dependencies("b"), ["a"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_constant_expr(&mut self, name: &str, expr: &str) -> Result<Number> {
+        let name = name.to_lowercase();
+        if self.builtin_keywords.contains(&name.as_str()) {
+            return Err(error::Error::InvalidArguments);
+        }
+
+        let num = self.evaluate_no_ans(expr)?;
+
+        let mut referenced: Vec<String> = self
+            .tokens
+            .iter()
+            .filter_map(|token| match token {
+                token::Token::Id(id) => Some(id.to_lowercase()),
+                _ => None,
+            })
+            .filter(|id| {
+                matches!(
+                    self.variables.get(id),
+                    Some(Variable::Constant(_) | Variable::ConstantWithUnit(..))
+                )
+            })
+            .collect();
+        referenced.sort();
+        referenced.dedup();
+
+        self.set_variable(&name, Variable::Constant(num.clone()));
+        self.dependencies.insert(name, referenced);
+        Ok(num)
+    }
+
+    /// Get the names of the constants referenced by the defining expression of the constant,
+    /// sorted. Empty for the constants not defined by `Calculator::add_constant_expr`
+    ///
+    /// ```
+    /// # use math::Calculator;
+    /// let mut calculator = Calculator::new();
+    /// calculator.add_constant("r", 2);
+    /// calculator.add_constant_expr("area", "pi() * r()^2").unwrap();
+    ///
+    /// assert_eq!(calculator.constant_dependencies("area"), ["pi", "r"]);
+    /// assert!(calculator.constant_dependencies("r").is_empty());
+    /// ```
+    pub fn constant_dependencies(&self, name: &str) -> Vec<String> {
+        self.dependencies
+            .get(&name.to_lowercase())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Get the names of the constants, whose defining expression references the constant, sorted
+    ///
+    /// ```
+    /// # use math::Calculator;
+    /// let mut calculator = Calculator::new();
+    /// calculator.add_constant("a", 1);
+    /// calculator.add_constant_expr("b", "a() + 1").unwrap();
+    /// calculator.add_constant_expr("c", "a() + b()").unwrap();
+    ///
+    /// assert_eq!(calculator.constant_dependents("a"), ["b", "c"]);
+    /// assert_eq!(calculator.constant_dependents("b"), ["c"]);
+    /// assert!(calculator.constant_dependents("c").is_empty());
+    /// ```
+    pub fn constant_dependents(&self, name: &str) -> Vec<String> {
+        let name = name.to_lowercase();
+        let mut dependents: Vec<String> = self
+            .dependencies
+            .iter()
+            .filter(|(_, referenced)| referenced.contains(&name))
+            .map(|(dependent, _)| dependent.clone())
+            .collect();
+        dependents.sort();
+        dependents
+    }
+
+    /// Add new function or update existing one, taking `argc` arguments, same rules as for
+    /// `Calculator::add_constant` apply. Returns `false` if the name is a built-in keyword
+    ///
+    /// ```
+    /// # use math::{Calculator, Number};
+    /// let mut calculator = Calculator::new();
+    ///
+    /// assert!(calculator.add_function("double", 1, |nums| nums[0].mul(2)));
+    /// assert_eq!(calculator.evaluate("double(21)"), Ok(Number::from(42)));
+    ///
+    /// assert!(calculator.add_function("hypot", 2, |nums| {
+    ///     nums[0].power(2)?.add(nums[1].power(2)?)?.sqrt()
+    /// }));
+    /// assert_eq!(calculator.evaluate("hypot(3, 4)"), Ok(Number::from(5)));
+    ///
+    /// assert!(!calculator.add_function("sqrt", 1, |nums| Ok(nums[0].clone())));
+    /// ```
+    pub fn add_function(
+        &mut self,
+        name: &str,
+        argc: u8,
+        ptr: fn(&[Number]) -> Result<Number>,
+    ) -> bool {
+        self.set_variable(name, Variable::Function { argc, ptr })
+    }
+
+    /// Add new variable (constant or function) or update existing one, same rules as for
+    /// `Calculator::add_constant` apply. Returns `false` if the name is a built-in keyword
+    ///
+    /// ```
+    /// # use math::{Calculator, Number, Variable};
+    /// let mut calculator = Calculator::new();
+    /// let double = Variable::Function {
+    ///     argc: 1,
+    ///     ptr: |nums| nums[0].mul(2),
+    /// };
+    ///
+    /// assert!(calculator.set_variable("Double", double));
+    /// assert_eq!(calculator.evaluate("double(21)"), Ok(Number::from(42)));
+    /// assert!(!calculator.set_variable("sin", Variable::Constant(Number::one())));
+    /// ```
+    pub fn set_variable(&mut self, name: &str, var: Variable) -> bool {
         let name = name.to_lowercase();
 
         if self.builtin_keywords.iter().any(|&v| v == name) {
             return false;
         }
 
-        self.variables.insert(name, Variable::Constant(num.into()));
+        self.dependencies.remove(&name);
+        self.variables.insert(name, var);
         true
     }
 
+    /// Get a variable (constant or function), including the built-in ones
+    ///
+    /// ```
+    /// # use math::{Calculator, Variable};
+    /// let calculator = Calculator::new();
+    ///
+    /// assert!(matches!(calculator.get_variable("PI"), Some(Variable::Constant(..))));
+    /// assert_eq!(calculator.get_variable("log").map(Variable::argc), Some(2));
+    /// assert!(calculator.get_variable("my_var").is_none());
+    /// ```
+    pub fn get_variable(&self, name: &str) -> Option<&Variable> {
+        self.variables.get(&name.to_lowercase())
+    }
+
     /// Remove a constant from the list
     ///
     /// ```
@@ -159,7 +568,10 @@ impl Calculator {
         let val = self.variables.remove(&name)?;
 
         match val {
-            Variable::Constant(num) => Some(num),
+            Variable::Constant(num) | Variable::ConstantWithUnit(num, _) => {
+                self.dependencies.remove(&name);
+                Some(num)
+            }
             _ => {
                 self.variables.insert(name, val);
                 None
@@ -167,6 +579,70 @@ impl Calculator {
         }
     }
 
+    /// Same as `Calculator::remove_constant`, but a constant referenced by other constants
+    /// (see `Calculator::constant_dependents`) isn't removed
+    ///
+    /// # Error
+    /// Error::ConstantInUse with the names of the dependent constants
+    ///
+    /// ```
+    /// # use math::{Calculator, Number, error::Error};
+    /// let mut calculator = Calculator::new();
+    /// calculator.add_constant("a", 1);
+    /// calculator.add_constant_expr("b", "a() + 1").unwrap();
+    ///
+    /// assert_eq!(
+    ///     calculator.remove_constant_checked("a"),
+    ///     Err(Error::ConstantInUse(vec![String::from("b")]))
+    /// );
+    /// assert_eq!(calculator.remove_constant_checked("b"), Ok(Some(Number::from(2))));
+    /// assert_eq!(calculator.remove_constant_checked("a"), Ok(Some(Number::from(1))));
+    /// ```
+    pub fn remove_constant_checked(&mut self, name: &str) -> Result<Option<Number>> {
+        let dependents = self.constant_dependents(name);
+        if !dependents.is_empty() {
+            return Err(error::Error::ConstantInUse(dependents));
+        }
+
+        Ok(self.remove_constant(name))
+    }
+
+    /// Remove a function added by `Calculator::add_function` from the list, returns whether
+    /// it was removed. Built-in functions and constants are kept
+    ///
+    /// ```
+    /// # use math::Calculator;
+    /// let mut calculator = Calculator::new();
+    /// calculator.add_function("double", 1, |nums| nums[0].mul(2));
+    /// calculator.add_constant("my_const", 1);
+    ///
+    /// assert!(calculator.remove_function("double"));
+    /// assert!(calculator.evaluate("double(21)").is_err());
+    ///
+    /// assert!(!calculator.remove_function("double"));
+    /// assert!(!calculator.remove_function("my_const"));
+    /// assert!(!calculator.remove_function("sqrt"));
+    /// assert!(calculator.evaluate("sqrt(4)").is_ok());
+    /// ```
+    pub fn remove_function(&mut self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        if self.builtin_keywords.contains(&name.as_str()) {
+            return false;
+        }
+
+        let Some(val) = self.variables.remove(&name) else {
+            return false;
+        };
+
+        match val {
+            Variable::Function { .. } | Variable::LazyFunction { .. } => true,
+            _ => {
+                self.variables.insert(name, val);
+                false
+            }
+        }
+    }
+
     /// Get a specific constant value
     ///
     /// ```
@@ -180,9 +656,8 @@ impl Calculator {
     /// assert_eq!(calculator.get_constant("my_const"), Some(Number::from(1)));
     /// ```
     pub fn get_constant(&self, name: &str) -> Option<Number> {
-        let name = name.to_lowercase();
-        match self.variables.get(&name)? {
-            Variable::Constant(num) => Some(num.clone()),
+        match self.get_variable(name)? {
+            Variable::Constant(num) | Variable::ConstantWithUnit(num, _) => Some(num.clone()),
             _ => None,
         }
     }
@@ -190,11 +665,140 @@ impl Calculator {
     /// Get an `Iterator` over all constants that the `Calculator` currently holding
     pub fn constants(&self) -> impl Iterator<Item = (&str, Number)> {
         self.variables.iter().filter_map(|(name, var)| match var {
-            Variable::Constant(val) => Some((name.as_str(), val.clone())),
+            Variable::Constant(val) | Variable::ConstantWithUnit(val, _) => {
+                Some((name.as_str(), val.clone()))
+            }
             _ => None,
         })
     }
 
+    /// Get an `Iterator` over all functions, built-in and user defined, with the number of
+    /// their parameters
+    ///
+    /// ```
+    /// # use math::Calculator;
+    /// let mut calculator = Calculator::new();
+    /// calculator.add_function("double", 1, |nums| nums[0].mul(2));
+    /// let functions: Vec<_> = calculator.functions().collect();
+    ///
+    /// assert!(functions.contains(&("sqrt", 1)));
+    /// assert!(functions.contains(&("root", 2)));
+    /// assert!(functions.contains(&("double", 1)));
+    /// assert!(!functions.iter().any(|(name, _)| *name == "pi"));
+    /// ```
+    pub fn functions(&self) -> impl Iterator<Item = (&str, u8)> {
+        self.variables.iter().filter_map(|(name, var)| match var {
+            Variable::Function { argc, .. } | Variable::LazyFunction { argc, .. } => {
+                Some((name.as_str(), *argc))
+            }
+            Variable::Constant(_) | Variable::ConstantWithUnit(..) => None,
+        })
+    }
+
+    /// Get an `Iterator` over the built-in functions and constants in the order they were
+    /// registered, operators like `mod` are skipped
+    ///
+    /// ```
+    /// # use math::Calculator;
+    /// let calculator = Calculator::new();
+    /// let builtins: Vec<_> = calculator.builtins().map(|(name, var)| (name, var.argc())).collect();
+    ///
+    /// assert!(builtins.contains(&("sqrt", 1)));
+    /// assert!(builtins.contains(&("log", 2)));
+    /// assert!(builtins.contains(&("pi", 0)));
+    /// assert!(!builtins.iter().any(|(name, _)| *name == "mod"));
+    /// ```
+    pub fn builtins(&self) -> impl Iterator<Item = (&str, &Variable)> {
+        self.builtin_keywords
+            .iter()
+            .filter_map(|&name| Some((name, self.variables.get(name)?)))
+    }
+
+    /// Get an `Iterator` over constants defined by the user, built-in constants (`e`, `pi`, ...)
+    /// and the last result `ans` are skipped
+    ///
+    /// ```
+    /// # use math::{Number, Calculator};
+    /// let mut calculator = Calculator::new();
+    /// assert_eq!(calculator.user_constants().count(), 0);
+    ///
+    /// calculator.add_constant("my_const", 1);
+    /// calculator.evaluate("1 + 1").unwrap();
+    ///
+    /// let user: Vec<_> = calculator.user_constants().collect();
+    /// assert_eq!(user, vec![("my_const", Number::from(1))]);
+    /// ```
+    pub fn user_constants(&self) -> impl Iterator<Item = (&str, Number)> {
+        self.constants()
+            .filter(|(name, _)| *name != "ans" && !self.builtin_keywords.contains(name))
+    }
+
+    /// Remove all constants defined by the user, see `Calculator::user_constants`
+    ///
+    /// ```
+    /// # use math::{Number, Calculator};
+    /// let mut calculator = Calculator::new();
+    /// calculator.add_constant("a", 1);
+    /// calculator.add_constant("b", 2);
+    /// calculator.add_constant("c", 3);
+    ///
+    /// calculator.clear_user_constants();
+    ///
+    /// assert_eq!(calculator.user_constants().count(), 0);
+    /// assert_eq!(calculator.get_constant("a"), None);
+    /// assert_eq!(calculator.get_constant("e"), Some(Number::e()));
+    /// assert_eq!(calculator.get_constant("pi"), Some(Number::pi()));
+    /// assert!(calculator.evaluate("sqrt(4)").is_ok());
+    /// ```
+    pub fn clear_user_constants(&mut self) {
+        let names: Vec<_> = self
+            .user_constants()
+            .map(|(name, _)| name.to_owned())
+            .collect();
+
+        for name in names {
+            self.variables.remove(&name);
+            self.dependencies.remove(&name);
+        }
+    }
+
+    /// Set the number of decimal places of the built-in constants `e`, `pi` and `phi`, by default
+    /// they are low precision approximations `Number::e()`, `Number::pi()` and `Number::phi()`
+    ///
+    /// ```
+    /// # use math::{Calculator, Number};
+    /// let mut calculator = Calculator::new();
+    /// calculator.set_constant_precision(30);
+    ///
+    /// assert_eq!(calculator.get_constant("pi"), Some(Number::pi_precise(30)));
+    /// assert_eq!(calculator.evaluate("e()"), Ok(Number::e_precise(30)));
+    /// assert_eq!(calculator.evaluate("phi()"), Ok(Number::phi_precise(30)));
+    /// ```
+    pub fn set_constant_precision(&mut self, digits: u32) {
+        let constants = [
+            ("e", Number::e_precise(digits)),
+            ("pi", Number::pi_precise(digits)),
+            ("phi", Number::phi_precise(digits)),
+        ];
+
+        for (name, num) in constants {
+            self.variables
+                .insert(name.to_owned(), Variable::Constant(num));
+        }
+    }
+
+    /// Get the engine of the `Calculator`, so its capabilities can be queried
+    ///
+    /// ```
+    /// # use math::Calculator;
+    /// # use math::engine::Notation;
+    /// let calculator = Calculator::new();
+    /// assert_eq!(calculator.engine().notation(), Notation::Infix);
+    /// ```
+    pub fn engine(&self) -> &dyn Engine {
+        self.engine.as_ref()
+    }
+
     /// Set the engine of the `Calculator`
     /// ```ignore
     /// # use math::Calculator;
@@ -209,26 +813,520 @@ impl Calculator {
 
     /// Evaluate a math expression using the given `Engine` (default is the infix math `ShuntingYardEngine`) \
     /// If the evaluation success, the constant `ANS` will be stored/updated into the variables list of the calculator
+    ///
+    /// # Error
+    /// Error::EmptyExpression if the expression doesn't contain anything but whitespaces \
+    /// Error::Overflow if the result is larger than `Calculator::max_result_bits` allows
     pub fn evaluate(&mut self, s: &str) -> Result<Number> {
+        let ans = self.evaluate_no_ans(s)?;
+        self.add_constant("ans", ans.clone());
+        Ok(ans)
+    }
+
+    /// Same as `Calculator::evaluate`, but the result isn't stored as the constant `ANS`,
+    /// which saves allocating its name and updating the variables list on every call
+    ///
+    /// ```
+    /// # use math::{Calculator, Number};
+    /// let mut calculator = Calculator::new();
+    ///
+    /// assert_eq!(calculator.evaluate_no_ans("1 + 2"), Ok(Number::from(3)));
+    /// assert!(calculator.evaluate("ans()").is_err());
+    /// ```
+    pub fn evaluate_no_ans(&mut self, s: &str) -> Result<Number> {
+        self.tokenize(s)?;
+
+        number::take_inexact();
+        let mut ans = self
+            .engine
+            .execute(self.tokens.as_slice(), &self.variables)?;
+        self.last_result_exact = !number::take_inexact();
+
+        if ans.bits() > self.max_result_bits {
+            return Err(error::Error::Overflow);
+        }
+
+        if self.snap_to_integer && ans.is_close_to_integer(&Number::guarantee_precision()) {
+            ans = ans.round();
+        }
+
+        Ok(ans)
+    }
+
+    /// Same as `Calculator::evaluate`, but a panic during the evaluation is caught and returned
+    /// as `Error::Message` instead of unwinding through the caller \
+    /// This is a safety net for embedding the calculator (in a server, ...), not a substitute
+    /// for fixing the panicking paths. The panic is still reported by the panic hook
+    ///
+    /// ```
+    /// # use math::{Calculator, Number};
+    /// let mut calculator = Calculator::new();
+    ///
+    /// assert_eq!(calculator.evaluate_safe("1 + 2"), Ok(Number::from(3)));
+    /// assert_eq!(calculator.evaluate("ans()"), Ok(Number::from(3)));
+    /// ```
+    pub fn evaluate_safe(&mut self, s: &str) -> Result<Number> {
+        // The engine and tokens are reset at the start of every evaluation,
+        // so a state left behind by the panic isn't observable
+        panic::catch_unwind(panic::AssertUnwindSafe(|| self.evaluate(s))).unwrap_or_else(|err| {
+            let msg = match err.downcast::<String>() {
+                Ok(msg) => *msg,
+                Err(err) => match err.downcast::<&str>() {
+                    Ok(msg) => msg.to_string(),
+                    Err(_) => String::from("unknown panic"),
+                },
+            };
+            Err(error::Error::Message(format!("Evaluation panicked: {msg}")))
+        })
+    }
+
+    /// Whether the last successfully evaluated result is exact, `false` when any operation
+    /// (`sin`, `log`, `gamma`, irrational `power`/`root`, ...) had to approximate
+    ///
+    /// ```
+    /// # use math::Calculator;
+    /// let mut calculator = Calculator::new();
+    /// calculator.evaluate("1 / 3").unwrap();
+    /// assert!(calculator.last_result_exact());
+    ///
+    /// calculator.evaluate("ln(3)").unwrap();
+    /// assert!(!calculator.last_result_exact());
+    /// ```
+    pub fn last_result_exact(&self) -> bool {
+        self.last_result_exact
+    }
+
+    /// Explain step by step how the math expression is evaluated, like
+    /// `First, 2 * 3 = 6. Then 1 + 6 = 7.` \
+    /// Numbers are formatted in decimal with `EXPLAIN_PRECISION` decimal places,
+    /// the result isn't stored as the constant `ANS`
+    ///
+    /// ```
+    /// # use math::Calculator;
+    /// let mut calculator = Calculator::new();
+    /// assert_eq!(calculator.explain("1 + 2 * 3"), "First, 2 * 3 = 6. Then 1 + 6 = 7.");
+    /// assert_eq!(calculator.explain("sqrt(4)!"), "First, sqrt(4) = 2. Then 2! = 2.");
+    /// assert_eq!(calculator.explain("5"), "The result is 5.");
+    /// ```
+    pub fn explain(&mut self, s: &str) -> String {
+        let fmt = |num: &Number| num.to_string(number::Radix::Dec, EXPLAIN_PRECISION);
+
+        let tracing = self.engine.tracing();
+        let supported = tracing || self.engine.set_tracing(true);
+        let res = self.evaluate_no_ans(s);
+        let steps: Vec<String> = self
+            .engine
+            .steps()
+            .iter()
+            .map(|step| match step {
+                engine::Step::Operation {
+                    lhs,
+                    op,
+                    rhs,
+                    result,
+                } => format!("{} {op} {} = {}", fmt(lhs), fmt(rhs), fmt(result)),
+                engine::Step::Factorial { num, result } => {
+                    format!("{}! = {}", fmt(num), fmt(result))
+                }
+                engine::Step::Function { name, args, result } => {
+                    let args: Vec<_> = args.iter().map(fmt).collect();
+                    format!("{name}({}) = {}", args.join(", "), fmt(result))
+                }
+            })
+            .collect();
+
+        if supported && !tracing {
+            self.engine.set_tracing(false);
+        }
+
+        let mut explanation = String::new();
+        for (idx, step) in steps.iter().enumerate() {
+            let prefix = if idx == 0 { "First, " } else { " Then " };
+            explanation.push_str(prefix);
+            explanation.push_str(step);
+            explanation.push('.');
+        }
+
+        let conclusion = match res {
+            Ok(_) if !steps.is_empty() => return explanation,
+            Ok(num) => format!("The result is {}.", fmt(&num)),
+            Err(err) => format!("Evaluation failed: {err}."),
+        };
+
+        if !explanation.is_empty() {
+            explanation.push(' ');
+        }
+        explanation.push_str(&conclusion);
+        explanation
+    }
+
+    /// Whether an expression starting with a binary operator continues from the last result
+    pub fn implicit_ans(&self) -> bool {
+        self.implicit_ans
+    }
+
+    /// Let an expression starting with a binary operator continue from the last result,
+    /// like `* 2` meaning `ans * 2` (disabled by default) \
+    /// While enabled and `ANS` is stored, a leading `+` or `-` is the addition or subtraction
+    /// of `ANS`, not a sign of the first operand. Without a stored `ANS` (nothing evaluated
+    /// yet) the expression is left as it is
+    ///
+    /// ```
+    /// # use math::{Calculator, Number};
+    /// let mut calculator = Calculator::new();
+    /// assert_eq!(calculator.evaluate("-5"), Ok(Number::from(-5)));
+    ///
+    /// calculator.set_implicit_ans(true);
+    /// assert_eq!(calculator.evaluate("-5"), Ok(Number::from(-10)));
+    /// assert_eq!(calculator.evaluate("/ 2"), Ok(Number::from(-5)));
+    /// ```
+    pub fn set_implicit_ans(&mut self, enabled: bool) {
+        self.implicit_ans = enabled;
+    }
+
+    /// Whether results close to an integer are snapped to it
+    pub fn snap_to_integer(&self) -> bool {
+        self.snap_to_integer
+    }
+
+    /// Snap results, which are within `Number::guarantee_precision` of an integer, to that
+    /// integer (disabled by default) \
+    /// This hides the error of approximations like `log(2, 8)` being `2.9999...`, but it's
+    /// also applied to results which are correctly just next to an integer
+    ///
+    /// ```
+    /// # use math::{Calculator, Number};
+    /// let mut calculator = Calculator::new();
+    /// calculator.set_snap_to_integer(true);
+    ///
+    /// assert_eq!(calculator.evaluate("log(2, 8)"), Ok(Number::from(3)));
+    /// assert_eq!(calculator.evaluate("1 + 1/10000000"), Ok(Number::from(1)));
+    /// assert_ne!(calculator.evaluate("1 + 1/1000"), Ok(Number::from(1)));
+    /// ```
+    pub fn set_snap_to_integer(&mut self, enabled: bool) {
+        self.snap_to_integer = enabled;
+    }
+
+    /// Maximum number of bits of the result's numerator and denominator
+    pub fn max_result_bits(&self) -> u64 {
+        self.max_result_bits
+    }
+
+    /// Reject results, whose numerator or denominator has more than `bits` bits,
+    /// with `Error::Overflow`
+    /// (`DEFAULT_MAX_RESULT_BITS` by default) \
+    /// Formatting of enormous numbers like `2^1000000` would take too long to be displayed
+    ///
+    /// ```
+    /// # use math::{Calculator, Number, error::Error};
+    /// let mut calculator = Calculator::new();
+    /// assert_eq!(calculator.evaluate("2^1000000"), Err(Error::Overflow));
+    ///
+    /// calculator.set_max_result_bits(8);
+    /// assert_eq!(calculator.evaluate("255"), Ok(Number::from(255)));
+    /// assert_eq!(calculator.evaluate("256"), Err(Error::Overflow));
+    /// assert_eq!(calculator.evaluate("1/256"), Err(Error::Overflow));
+    /// ```
+    pub fn set_max_result_bits(&mut self, bits: u64) {
+        self.max_result_bits = bits;
+    }
+
+    /// How integers with a leading zero are read
+    pub fn leading_zeros(&self) -> token::LeadingZeros {
+        self.leading_zeros
+    }
+
+    /// Select how integers with a leading zero like `0123` are read, see `token::LeadingZeros`
+    /// (ignored by default)
+    ///
+    /// ```
+    /// # use math::{Calculator, Number, token::LeadingZeros};
+    /// let mut calculator = Calculator::new();
+    /// assert_eq!(calculator.evaluate("017"), Ok(Number::from(17)));
+    ///
+    /// calculator.set_leading_zeros(LeadingZeros::Octal);
+    /// assert_eq!(calculator.evaluate("017"), Ok(Number::from(15)));
+    /// ```
+    pub fn set_leading_zeros(&mut self, leading_zeros: token::LeadingZeros) {
+        self.leading_zeros = leading_zeros;
+    }
+
+    /// Unit of the angles in the trigonometric functions
+    pub fn angle_mode(&self) -> AngleMode {
+        self.angle_mode
+    }
+
+    /// Select the unit of the angles taken by `sin`, `cos`, `tg`, `cotg` and returned by their
+    /// inverses (radians by default)
+    ///
+    /// ```
+    /// # use math::{AngleMode, Calculator, Number};
+    /// let mut calculator = Calculator::new();
+    /// calculator.set_angle_mode(AngleMode::Degrees);
+    ///
+    /// assert_eq!(calculator.evaluate("sin(90)"), Ok(Number::one()));
+    /// assert_eq!(calculator.evaluate("cos(180)"), Ok(Number::from(-1)));
+    /// ```
+    pub fn set_angle_mode(&mut self, mode: AngleMode) {
+        self.angle_mode = mode;
+
+        for (name, argc, ptr) in trig_functions(mode) {
+            self.variables
+                .insert(String::from(name), Variable::Function { argc, ptr });
+        }
+    }
+
+    /// Evaluate a math expression like `Calculator::evaluate` and collect everything known
+    /// about the evaluation: the tokens, whether the result is exact, the sub-computations
+    /// (if the engine supports tracing), warnings and the time it took
+    ///
+    /// ```
+    /// # use math::{Calculator, Number, Warning};
+    /// # fn main() -> math::Result<()> {
+    /// let mut calculator = Calculator::new();
+    /// let report = calculator.evaluate_report("2 * pi()")?;
+    ///
+    /// assert!(report.exact);
+    /// assert_eq!(report.tokens.len(), 5);
+    /// assert_eq!(report.steps.len(), 2); // pi() and the multiplication
+    /// assert_eq!(report.warnings, [Warning::LowPrecisionConstant(String::from("pi"))]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn evaluate_report(&mut self, s: &str) -> Result<EvaluationReport> {
+        let start = Instant::now();
+        let tracing = self.engine.tracing();
+        let supported = tracing || self.engine.set_tracing(true);
+        let res = self.evaluate(s);
+        let duration = start.elapsed();
+
+        let steps = self.engine.steps().to_vec();
+        if supported && !tracing {
+            self.engine.set_tracing(false);
+        }
+
+        let low_precision = [
+            ("e", Number::e()),
+            ("pi", Number::pi()),
+            ("phi", Number::phi()),
+        ];
+        let warnings = low_precision
+            .into_iter()
+            .filter(|(name, num)| {
+                let used = self
+                    .tokens
+                    .iter()
+                    .any(|token| matches!(token, token::Token::Id(id) if id == name));
+                used && self.get_constant(name).as_ref() == Some(num)
+            })
+            .map(|(name, _)| Warning::LowPrecisionConstant(name.to_owned()))
+            .collect();
+
+        Ok(EvaluationReport {
+            result: res?,
+            exact: self.last_result_exact,
+            tokens: self.tokens.clone(),
+            steps,
+            warnings,
+            duration,
+        })
+    }
+
+    /// Unit of the last evaluated result, `None` if it's dimensionless or the engine
+    /// doesn't track units
+    pub fn result_unit(&self) -> Option<&Unit> {
+        self.engine.result_unit()
+    }
+
+    /// Number of operations performed by the last evaluation, `None` if the engine
+    /// doesn't count them \
+    /// Meant for benchmarks and checking that an optimization actually saves work
+    ///
+    /// ```
+    /// # use math::Calculator;
+    /// let mut calculator = Calculator::new();
+    /// calculator.evaluate("sqrt(4) * 2").unwrap();
+    /// assert_eq!(calculator.last_op_count(), Some(2));
+    /// ```
+    pub fn last_op_count(&self) -> Option<usize> {
+        self.engine.op_count()
+    }
+
+    /// Check whether a math expression can be evaluated by the current `Engine` without
+    /// actually evaluating it
+    ///
+    /// ```
+    /// # use math::Calculator;
+    /// let mut calculator = Calculator::new();
+    /// assert!(calculator.validate("1 + sqrt(4)").is_ok());
+    /// assert!(calculator.validate("1 +").is_err());
+    /// assert!(calculator.validate("unknown(1)").is_err());
+    /// ```
+    pub fn validate(&mut self, s: &str) -> Result<()> {
+        self.tokenize(s)?;
+        self.engine.validate_tokens(&self.tokens, &self.variables)
+    }
+
+    fn tokenize(&mut self, s: &str) -> Result<()> {
         self.tokens.clear();
-        let mut scanner = token::Scanner::new(s);
+        let mut scanner = token::Scanner::new(s).with_leading_zeros(self.leading_zeros);
 
         while let Some(token) = scanner.next_token()? {
             self.tokens.push(token);
         }
 
-        let ans = self
-            .engine
-            .execute(self.tokens.as_slice(), &self.variables)?;
+        if self.tokens.is_empty() {
+            return Err(error::Error::EmptyExpression);
+        }
 
-        self.add_constant("ans", ans.clone());
-        Ok(ans)
+        let leading_operator = matches!(self.tokens[0], token::Token::Operator(_));
+        if let Some(ans) = self
+            .get_constant("ans")
+            .filter(|_| self.implicit_ans && leading_operator)
+        {
+            self.tokens.insert(0, token::Token::Number(ans));
+        }
+
+        Ok(())
+    }
+}
+
+/// Number of decimal places of the numbers in `Calculator::explain`
+pub const EXPLAIN_PRECISION: u8 = 10;
+
+/// Result of `Calculator::evaluate_report`
+#[derive(Debug, Clone)]
+pub struct EvaluationReport {
+    /// The evaluated result
+    pub result: Number,
+    /// Whether the result is exact, see `Calculator::last_result_exact`
+    pub exact: bool,
+    /// Tokens of the expression
+    pub tokens: Vec<token::Token>,
+    /// Sub-computations in the order they were computed, empty if the engine doesn't
+    /// support tracing
+    pub steps: Vec<engine::Step>,
+    /// Things which may make the result less accurate than expected
+    pub warnings: Vec<Warning>,
+    /// Time spent on the evaluation
+    pub duration: Duration,
+}
+
+/// Warning of `EvaluationReport`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// The expression used the low precision approximation of the constant,
+    /// see `Calculator::set_constant_precision`
+    LowPrecisionConstant(String),
+}
+
+/// ```
+/// # use math::Warning;
+/// let warning = Warning::LowPrecisionConstant(String::from("pi"));
+/// assert_eq!(warning.to_string(), "Used low precision approximation of pi");
+/// ```
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::LowPrecisionConstant(name) => {
+                write!(f, "Used low precision approximation of {name}")
+            }
+        }
     }
 }
 
+/// Maximum number of bases remembered by `LnCache`
+const LN_CACHE_CAPACITY: usize = 64;
+
+/// Remembers natural logarithms of bases, so repeated logarithms with the same base compute
+/// `ln(base)` only once
+#[derive(Default)]
+struct LnCache {
+    values: Mutex<HashMap<Number, Number>>,
+}
+
+impl LnCache {
+    fn get(&self, base: &Number) -> Result<Number> {
+        self.get_or_insert_with(base, Number::ln)
+    }
+
+    fn get_or_insert_with(
+        &self,
+        base: &Number,
+        ln: impl FnOnce(&Number) -> Result<Number>,
+    ) -> Result<Number> {
+        let mut values = self.values.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(val) = values.get(base) {
+            return Ok(val.clone());
+        }
+
+        if values.len() >= LN_CACHE_CAPACITY {
+            values.clear();
+        }
+
+        let val = ln(base)?;
+        values.insert(base.clone(), val.clone());
+        Ok(val)
+    }
+}
+
+/// `Number::log` with `ln(base)` taken from the shared `LnCache`
+fn log_cached(num: &Number, base: &Number) -> Result<Number> {
+    static LN_CACHE: Lazy<LnCache> = Lazy::new(LnCache::default);
+
+    // Let `Number::log` handle the domain errors and trivial cases
+    let zero = Number::zero();
+    if num <= &zero || base <= &zero || num == &Number::one() || num == base {
+        return num.log(base);
+    }
+
+    num.ln()?.div(LN_CACHE.get(base)?)
+}
+
 /// High level function
 /// for use in the long run, it is recommendded to create (and hold) an instance of `Calculator`
 /// struct itself, as it may reserves the allocation spaces for future evaluation process
 pub fn evaluate(s: &str) -> Result<Number> {
-    Calculator::new().evaluate(s)
+    Calculator::new().evaluate_no_ans(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn ln_cache_reuses_base() -> Result<()> {
+        let cache = LnCache::default();
+        let computed = Cell::new(0);
+        let counting_ln = |base: &Number| {
+            computed.set(computed.get() + 1);
+            base.ln()
+        };
+
+        let first = cache.get_or_insert_with(&Number::from(3), counting_ln)?;
+        let second = cache.get_or_insert_with(&Number::from(3), counting_ln)?;
+        assert_eq!(first, second);
+        assert_eq!(computed.get(), 1);
+
+        cache.get_or_insert_with(&Number::from(5), counting_ln)?;
+        assert_eq!(computed.get(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn log_cached_matches_log() -> Result<()> {
+        let base = Number::from(7);
+        for num in [2, 49, 1000] {
+            let cached = log_cached(&Number::from(num), &base)?;
+            let pure = Number::from(num).log(&base)?;
+            assert!(cached.sub(pure)?.abs()? < Number::guarantee_precision());
+        }
+
+        assert!(log_cached(&Number::zero(), &base).is_err());
+        assert!(log_cached(&base, &Number::from(-2)).is_err());
+        Ok(())
+    }
 }
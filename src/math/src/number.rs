@@ -2,20 +2,32 @@ use crate::error::Error;
 use crate::Result;
 use num::rational::Ratio;
 use num::BigInt;
+use num::BigUint;
+use num::Integer as _;
 use num::Signed as _;
 use num::ToPrimitive;
 use once_cell::sync::OnceCell;
+use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
-use std::fmt::Write as _;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
 
 #[derive(Debug, Clone)]
 /// Represent a number
+///
+/// The value is immutable and shared behind an `Arc`, so `clone` is cheap, it only increments
+/// the reference count, and the clones can be sent to other threads. Every operation returns
+/// a new `Number`, a shared value is never modified. See `Number::deep_clone` for the rare case,
+/// when a copy not sharing the memory is needed
 pub struct Number {
     inner: Arc<Ratio<BigInt>>,
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 /// Radix to use to represent a Number
 pub enum Radix {
@@ -30,6 +42,194 @@ pub enum Radix {
     Hex,
 }
 
+impl Radix {
+    /// Get the numeric base of the radix
+    ///
+    /// ```
+    /// # use math::number::Radix;
+    /// assert_eq!(Radix::Bin.base(), 2);
+    /// assert_eq!(Radix::Hex.base(), 16);
+    /// ```
+    pub fn base(self) -> u32 {
+        match self {
+            Self::Bin => 2,
+            Self::Oct => 8,
+            Self::Dec => 10,
+            Self::Hex => 16,
+        }
+    }
+}
+
+/// Multiply `inner` by `radix^places` by scaling only the numerator (or the denominator when
+/// `places` is negative). The result is *not* reduced
+fn shift_ratio(inner: &Ratio<BigInt>, radix: u32, places: i32) -> Ratio<BigInt> {
+    let factor = BigInt::from(radix).pow(places.unsigned_abs());
+
+    if places >= 0 {
+        Ratio::new_raw(inner.numer() * factor, inner.denom().clone())
+    } else {
+        Ratio::new_raw(inner.numer().clone(), inner.denom() * factor)
+    }
+}
+
+/// Natural logarithm of a positive integer. Integers too large for `f64` are shifted right
+/// first and the shift is added back as a multiple of `ln 2`
+fn ln_bigint(num: &BigInt) -> f64 {
+    let shift = num.bits().saturating_sub(f64::MANTISSA_DIGITS.into());
+    let mantissa = (num >> shift).to_f64().unwrap_or_default();
+
+    mantissa.ln() + shift as f64 * std::f64::consts::LN_2
+}
+
+/// Natural logarithm of a positive rational. When the value doesn't fit into `f64` (or is too
+/// close to zero), the logarithms of the numerator and the denominator are subtracted instead
+fn ln_ratio(ratio: &Ratio<BigInt>) -> f64 {
+    match ratio.to_f64() {
+        Some(f) if f.is_normal() => f.ln(),
+        _ => ln_bigint(ratio.numer()) - ln_bigint(ratio.denom()),
+    }
+}
+
+/// Shared zero handed out by `Number::from_f64` for non-finite floats, compared by pointer
+/// in `Number::try_to_string` to tell it apart from a genuine zero
+static NON_FINITE: OnceCell<Arc<Ratio<BigInt>>> = OnceCell::new();
+
+thread_local! {
+    /// Set when an operation on the current thread approximated its result, see `take_inexact`
+    static INEXACT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Remember that an approximation took place on the current thread
+fn mark_inexact() {
+    INEXACT.with(|inexact| inexact.set(true));
+}
+
+/// Whether any operation approximated its result (dropped to float or truncated a series)
+/// since the last call, the flag is reset afterwards
+pub(crate) fn take_inexact() -> bool {
+    INEXACT.with(|inexact| inexact.replace(false))
+}
+
+/// Precision of the `Display` formatting, when the formatter doesn't specify any
+const DISPLAY_PRECISION: u8 = 12;
+
+/// Number of extra digits used while computing constants in `cached_precise`
+const GUARD_DIGITS: u32 = 10;
+
+/// Decimal places of π used to reduce large arguments of `Number::sin`, the error of `Number::pi`
+/// would be multiplied by the number of full turns in the argument. The same holds for the
+/// powers of e in `Number::exp`
+const REDUCTION_DIGITS: u32 = 40;
+
+/// Largest absolute value of the argument of `Number::exp`, `e^10000` has over 4000 digits
+const MAX_EXP_ARGUMENT: i32 = 10_000;
+
+/// How many digits the repeated tail of a decimal written without brackets has to span,
+/// to be recognized as repeating by `Number::from_repeating_decimal`
+const MIN_REPEATING_DIGITS: usize = 6;
+
+/// Split the fractional digits into the fixed part and the repetend. The repetend is
+/// the shortest pattern repeated (at least twice) at the end over `MIN_REPEATING_DIGITS`
+/// digits, there is no repetend if nothing like that is found
+fn split_repetend(fraction: &str) -> (&str, &str) {
+    let len = fraction.len();
+
+    for period in 1..=len / 2 {
+        let pattern = &fraction[len - period..];
+        let repeats = fraction
+            .as_bytes()
+            .rchunks_exact(period)
+            .take_while(|chunk| *chunk == pattern.as_bytes())
+            .count();
+        let tail = repeats * period;
+
+        if repeats >= 2 && tail >= MIN_REPEATING_DIGITS {
+            return (&fraction[..len - tail], pattern);
+        }
+    }
+
+    (fraction, "")
+}
+
+/// Get a constant with `digits` decimal places from `cache`, computing it on a miss. `compute`
+/// receives the fixed point scale (a power of 10) and returns the constant multiplied by it
+fn cached_precise(
+    cache: &OnceCell<Mutex<HashMap<u32, Number>>>,
+    digits: u32,
+    compute: impl FnOnce(&BigInt) -> BigInt,
+) -> Number {
+    let mut values = cache
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    values
+        .entry(digits)
+        .or_insert_with(|| {
+            let guard = BigInt::from(10u32).pow(GUARD_DIGITS);
+            let value = compute(&(BigInt::from(10u32).pow(digits) * &guard));
+            // Round away the guard digits
+            let value = (value + &guard / 2) / guard;
+
+            Number {
+                inner: Arc::new(Ratio::new(value, BigInt::from(10u32).pow(digits))),
+            }
+        })
+        .clone()
+}
+
+#[derive(Debug, Clone)]
+/// A `Number` remembering its last string representation, so formatting it repeatedly with the
+/// same radix and precision doesn't redo the conversion
+///
+/// ```
+/// # use math::number::{FormattedNumber, Number, Radix};
+/// let num = FormattedNumber::from(Number::new_unchecked(1, 3));
+///
+/// assert_eq!(num.to_string(Radix::Dec, 3), "0.333");
+/// assert_eq!(num.to_string(Radix::Dec, 3), "0.333");
+/// assert_eq!(num.to_string(Radix::Hex, 2), "0.55");
+/// ```
+pub struct FormattedNumber {
+    num: Number,
+    cache: RefCell<Option<(Radix, u8, String)>>,
+}
+
+impl FormattedNumber {
+    /// Wrap the `num`, nothing is formatted yet
+    pub fn new(num: Number) -> Self {
+        Self {
+            num,
+            cache: RefCell::default(),
+        }
+    }
+
+    /// Get the wrapped number
+    pub fn number(&self) -> &Number {
+        &self.num
+    }
+
+    /// Same as `Number::to_string`, the result is reused until `radix` or `precision` changes
+    pub fn to_string(&self, radix: Radix, precision: u8) -> String {
+        let mut cache = self.cache.borrow_mut();
+
+        match &*cache {
+            Some((r, p, s)) if *r == radix && *p == precision => s.clone(),
+            _ => {
+                let s = self.num.to_string(radix, precision);
+                cache.replace((radix, precision, s.clone()));
+                s
+            }
+        }
+    }
+}
+
+impl From<Number> for FormattedNumber {
+    fn from(num: Number) -> Self {
+        Self::new(num)
+    }
+}
+
 impl<T: Into<BigInt>> From<T> for Number {
     fn from(v: T) -> Self {
         let big = v.into();
@@ -49,6 +249,76 @@ impl Default for Number {
     }
 }
 
+/// Exact conversion to the primitive integer types, fails with `Error::InvalidArguments` if the
+/// number isn't an integer and with `Error::OutOfRange` if it doesn't fit into the type
+///
+/// ```
+/// # use math::{Number, error::Error};
+/// # fn main() -> math::Result<()> {
+/// let idx: usize = Number::new(6, 2)?.try_into()?;
+/// assert_eq!(idx, 3);
+/// assert_eq!(u32::try_from(Number::new(1, 2)?), Err(Error::InvalidArguments));
+/// assert_eq!(u32::try_from(Number::from(-1)), Err(Error::OutOfRange));
+/// # Ok(())
+/// # }
+/// ```
+macro_rules! impl_try_from_number {
+    ($($int:ty),*) => {$(
+        impl TryFrom<Number> for $int {
+            type Error = Error;
+
+            fn try_from(value: Number) -> Result<Self> {
+                let (numer, denom) = value.as_ratio();
+                if denom != BigInt::from(1) {
+                    return Err(Error::InvalidArguments);
+                }
+
+                Self::try_from(numer).map_err(|_| Error::OutOfRange)
+            }
+        }
+    )*};
+}
+
+impl_try_from_number!(i32, i64, u32, u64, usize);
+
+#[cfg(feature = "rust_decimal")]
+impl TryFrom<Number> for rust_decimal::Decimal {
+    type Error = Error;
+
+    /// Exact conversion, returns `Error::OutOfRange` if the number has an infinite decimal
+    /// expansion or doesn't fit into the fixed precision of `Decimal`
+    ///
+    /// ```
+    /// # use math::{Number, error::Error};
+    /// # use rust_decimal::Decimal;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Decimal::try_from(Number::new(1, 8)?)?.to_string(), "0.125");
+    /// assert_eq!(Decimal::try_from(Number::new(1, 3)?), Err(Error::OutOfRange));
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn try_from(value: Number) -> Result<Self> {
+        let (numer, denom) = value.as_ratio();
+
+        // Smallest power of ten divisible by the denominator
+        let mut scale = 0;
+        let mut power = BigInt::from(1);
+        while !power.is_multiple_of(&denom) {
+            if scale == rust_decimal::Decimal::MAX_SCALE {
+                return Err(Error::OutOfRange);
+            }
+            power *= 10;
+            scale += 1;
+        }
+
+        let mantissa = (numer * (power / denom))
+            .to_i128()
+            .ok_or(Error::OutOfRange)?;
+        rust_decimal::Decimal::try_from_i128_with_scale(mantissa, scale)
+            .map_err(|_| Error::OutOfRange)
+    }
+}
+
 impl Number {
     /// 0.0
     pub fn zero() -> Self {
@@ -89,6 +359,92 @@ impl Number {
         E.get_or_init(|| Self::new_unchecked(2721, 1001)).clone()
     }
 
+    /// The golden ratio (ϕ), `(1 + √5) / 2`
+    /// 1.61803... ~= 75 025/46 368, ratio of consecutive Fibonacci numbers
+    pub fn phi() -> Self {
+        static PHI: OnceCell<Number> = OnceCell::new();
+        PHI.get_or_init(|| Self::new_unchecked(75025, 46368))
+            .clone()
+    }
+
+    /// The half circle constant (π) computed to `digits` decimal places
+    /// using Machin's formula `π = 16 arctg(1/5) - 4 arctg(1/239)`
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// assert_eq!(Number::pi_precise(20).to_string(Radix::Dec, 20), "3.14159265358979323846");
+    /// assert_eq!(Number::pi_precise(2).to_string(Radix::Dec, 20), "3.14");
+    /// ```
+    pub fn pi_precise(digits: u32) -> Self {
+        static PI: OnceCell<Mutex<HashMap<u32, Number>>> = OnceCell::new();
+
+        cached_precise(&PI, digits, |scale| {
+            let arctg_inv = |x: u32| {
+                let x = BigInt::from(x);
+                let x2 = &x * &x;
+                let mut term = scale / &x;
+                let mut sum = term.clone();
+                let mut n = 1u32;
+
+                while term != num::zero() {
+                    term /= &x2;
+                    n += 2;
+                    if n % 4 == 1 {
+                        sum += &term / n;
+                    } else {
+                        sum -= &term / n;
+                    }
+                }
+
+                sum
+            };
+
+            arctg_inv(5) * 16 - arctg_inv(239) * 4
+        })
+    }
+
+    /// The Euler's number (e) computed to `digits` decimal places
+    /// using the series `e = 1/0! + 1/1! + 1/2! + ...`
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// assert_eq!(Number::e_precise(20).to_string(Radix::Dec, 20), "2.71828182845904523536");
+    /// assert_eq!(Number::e_precise(3).to_string(Radix::Dec, 20), "2.718");
+    /// ```
+    pub fn e_precise(digits: u32) -> Self {
+        static E: OnceCell<Mutex<HashMap<u32, Number>>> = OnceCell::new();
+
+        cached_precise(&E, digits, |scale| {
+            let mut term = scale.clone();
+            let mut sum = BigInt::default();
+            let mut k = 0u32;
+
+            while term != num::zero() {
+                sum += &term;
+                k += 1;
+                term /= k;
+            }
+
+            sum
+        })
+    }
+
+    /// The golden ratio (ϕ) computed to `digits` decimal places as `(1 + √5) / 2`
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// assert_eq!(Number::phi_precise(21).to_string(Radix::Dec, 21), "1.618033988749894848205");
+    /// assert_eq!(Number::phi_precise(3).to_string(Radix::Dec, 20), "1.618");
+    /// ```
+    pub fn phi_precise(digits: u32) -> Self {
+        static PHI: OnceCell<Mutex<HashMap<u32, Number>>> = OnceCell::new();
+
+        cached_precise(&PHI, digits, |scale| {
+            let sqrt_5 = (scale.pow(2) * 5u32).sqrt();
+            (scale + sqrt_5) / 2
+        })
+    }
+
     /// According to the specification, the guarantee_precision is 6 point digits
     pub fn guarantee_precision() -> Self {
         static PRE: OnceCell<Number> = OnceCell::new();
@@ -130,127 +486,863 @@ impl Number {
             return Ok(Self::one());
         }
 
-        if num == num::zero() {
-            return Ok(Self::zero());
+        if num == num::zero() {
+            return Ok(Self::zero());
+        }
+
+        Ok(Self {
+            inner: Arc::new(Ratio::new(num, denom)),
+        })
+    }
+
+    /// Parse an exact fraction written as `"a/b"`, where `a` is an integer with optional
+    /// leading sign and `b` is a non-negative integer
+    ///
+    /// # Error
+    /// Error::DivisionZero if `b` is 0 \
+    /// Error::InvalidToken if either part is not an integer
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # use math::error::Error;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from_ratio_str("355/113")?, Number::new(355, 113)?);
+    /// assert_eq!(Number::from_ratio_str("-1/2")?, Number::new(-1, 2)?);
+    /// assert_eq!(Number::from_ratio_str(" +6 / 4 ")?, Number::new(3, 2)?);
+    /// assert_eq!(Number::from_ratio_str("1/0"), Err(Error::DivisionZero));
+    /// assert_eq!(Number::from_ratio_str("/0"), Err(Error::InvalidToken));
+    /// assert_eq!(Number::from_ratio_str("1.5/2"), Err(Error::InvalidToken));
+    /// assert_eq!(Number::from_ratio_str("1/-2"), Err(Error::InvalidToken));
+    /// assert_eq!(Number::from_ratio_str("12"), Err(Error::InvalidToken));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_ratio_str(s: &str) -> Result<Self> {
+        let (num, denom) = s.split_once('/').ok_or(Error::InvalidToken)?;
+        let num: BigInt = num.trim().parse().map_err(|_| Error::InvalidToken)?;
+        let denom: BigUint = denom.trim().parse().map_err(|_| Error::InvalidToken)?;
+
+        Self::new(num, denom)
+    }
+
+    /// Same as `Number::new` but bypass the zero check for denom
+    pub fn new_unchecked(num: impl Into<BigInt>, denom: impl Into<BigInt>) -> Self {
+        Self {
+            inner: Arc::new(Ratio::new_raw(num.into(), denom.into())),
+        }
+    }
+
+    /// Get the formatted string of a number
+    ///
+    /// ```
+    /// # use math::number::{Number, Radix};
+    /// let pi = Number::pi();
+    /// let zero = Number::zero();
+    /// let neg = Number::new_unchecked(-1, 10);
+    ///
+    /// let precision = 6;
+    ///
+    /// assert_eq!(zero.to_string(Radix::Bin, precision), "0");
+    /// assert_eq!(zero.to_string(Radix::Oct, precision), "0");
+    /// assert_eq!(zero.to_string(Radix::Dec, precision), "0");
+    /// assert_eq!(zero.to_string(Radix::Hex, precision), "0");
+    ///
+    /// assert_eq!(pi.to_string(Default::default(), precision), "3.141593");
+    /// assert_eq!(pi.to_string(Radix::Bin, precision), "11.001001");
+    /// assert_eq!(pi.to_string(Radix::Oct, precision), "3.110376");
+    /// assert_eq!(pi.to_string(Radix::Hex, precision), "3.243F6B");
+    ///
+    /// assert_eq!(neg.to_string(Default::default(), precision), "-0.1");
+    /// assert_eq!(neg.to_string(Radix::Bin, precision), "-0.00011");
+    /// assert_eq!(neg.to_string(Radix::Oct, precision), "-0.063146");
+    /// assert_eq!(neg.to_string(Radix::Hex, precision), "-0.19999A");
+    ///
+    /// assert_eq!(Number::new_unchecked(9, 10).to_string(Radix::Dec, 0), "1");
+    /// ```
+    pub fn to_string(&self, radix: Radix, precision: u8) -> String {
+        self.format(radix, precision, false, false)
+    }
+
+    /// Same as `Number::to_string`, but a negative number rounded to zero keeps its sign,
+    /// so it's shown as `-0` to indicate the true sign of the value
+    ///
+    /// ```
+    /// # use math::number::{Number, Radix};
+    /// # fn main() -> math::Result<()> {
+    /// let tiny = Number::new(-1, 10000)?;
+    /// assert_eq!(tiny.to_string_signed_zero(Radix::Dec, 2), "-0");
+    /// assert_eq!(tiny.to_string(Radix::Dec, 2), "0");
+    /// assert_eq!(tiny.to_string_signed_zero(Radix::Dec, 4), "-0.0001");
+    /// assert_eq!(Number::zero().to_string_signed_zero(Radix::Dec, 2), "0");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_string_signed_zero(&self, radix: Radix, precision: u8) -> String {
+        self.format(radix, precision, true, false)
+    }
+
+    /// Same as `Number::to_string`, but there are always exactly `precision` fractional digits,
+    /// padded with trailing zeros, so the numbers can be aligned in a table. Integers are padded
+    /// as well (`3.00`), only precision 0 has no decimal point
+    ///
+    /// ```
+    /// # use math::number::{Number, Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(3, 2)?.to_string_padded(Radix::Dec, 4), "1.5000");
+    /// assert_eq!(Number::from(3).to_string_padded(Radix::Dec, 2), "3.00");
+    /// assert_eq!(Number::pi().to_string_padded(Radix::Dec, 3), "3.142");
+    /// assert_eq!(Number::new(-1, 1000)?.to_string_padded(Radix::Dec, 2), "0.00");
+    /// assert_eq!(Number::new(3, 2)?.to_string_padded(Radix::Dec, 0), "2");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_string_padded(&self, radix: Radix, precision: u8) -> String {
+        self.format(radix, precision, false, true)
+    }
+
+    /// Display the number as a percentage, the number multiplied by 100 with the `%` sign,
+    /// `precision` is the same as in `Number::to_string` (for the multiplied number)
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(1, 4)?.to_percent_string(2), "25%");
+    /// assert_eq!(Number::new(-1, 10)?.to_percent_string(2), "-10%");
+    /// assert_eq!(Number::new(1, 3)?.to_percent_string(2), "33.33%");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_percent_string(&self, precision: u8) -> String {
+        let mut res = self
+            .shift_radix(Radix::Dec, 2)
+            .to_string(Radix::Dec, precision);
+        res.push('%');
+        res
+    }
+
+    /// Implementation of `Number::to_string`, `signed_zero` keeps the sign of negative numbers
+    /// rounded to zero, `padded` keeps all `precision` fractional digits
+    fn format(&self, radix: Radix, precision: u8, signed_zero: bool, padded: bool) -> String {
+        let num = self.inner.abs();
+        let mut integer = num.to_integer();
+        let mut fract = num.fract();
+
+        let radix_len = radix.base();
+
+        let mut fract_digits = Vec::new();
+
+        if fract != num::zero() {
+            let mut cnt = 0;
+
+            while fract != num::zero() && cnt < precision {
+                let n = shift_ratio(&fract, radix_len, 1);
+                let whole = n.to_integer().to_u32().expect("digit is less than radix");
+                fract = n.fract();
+
+                fract_digits.push(whole);
+                cnt += 1;
+            }
+
+            let n = shift_ratio(&fract, radix_len, 1);
+            let whole = n.to_integer().to_u32().expect("digit is less than radix");
+
+            if whole >= (radix_len / 2) {
+                loop {
+                    match fract_digits.pop() {
+                        Some(mut end) => {
+                            end += 1;
+                            if end < radix_len {
+                                fract_digits.push(end);
+                                break;
+                            }
+                        }
+                        None => {
+                            integer += 1;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut res = match radix {
+            Radix::Bin => format!("{integer:b}"),
+            Radix::Oct => format!("{integer:o}"),
+            Radix::Dec => format!("{integer}"),
+            Radix::Hex => format!("{integer:X}"),
+        };
+
+        if self.inner.is_negative() {
+            res.insert(0, '-');
+        }
+
+        if padded {
+            fract_digits.resize(usize::from(precision), 0);
+        }
+
+        if !fract_digits.is_empty() {
+            res.push('.');
+
+            let digits = fract_digits
+                .into_iter()
+                .map(|v| char::from_digit(v, radix_len).expect("digit is less than radix"))
+                .collect::<String>()
+                .to_ascii_uppercase();
+
+            res.push_str(&digits);
+            if !padded {
+                res = res.trim_end_matches('0').trim_end_matches('.').to_owned();
+            }
+        }
+
+        let rounded_to_zero =
+            res.starts_with('-') && res.chars().all(|c| matches!(c, '-' | '0' | '.'));
+        if rounded_to_zero && !signed_zero {
+            res.remove(0);
+        }
+
+        res
+    }
+
+    /// Get the formatted string of a number in scientific notation `m.mmme±x`, the mantissa
+    /// has one nonzero integer digit and `precision` fractional digits at most. The exponent is
+    /// a decimal power of the radix, mantissa digits are uppercase, so `e` is unambiguous
+    ///
+    /// ```
+    /// # use math::number::{Number, Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from(123456).to_scientific_string(Radix::Dec, 2), "1.23e5");
+    /// assert_eq!(Number::new(-3, 4000)?.to_scientific_string(Radix::Dec, 2), "-7.5e-4");
+    /// assert_eq!(Number::from(99999).to_scientific_string(Radix::Dec, 2), "1e5");
+    /// assert_eq!(Number::from(0x1F00).to_scientific_string(Radix::Hex, 1), "1.Fe3");
+    /// assert_eq!(Number::zero().to_scientific_string(Radix::Dec, 2), "0");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_scientific_string(&self, radix: Radix, precision: u8) -> String {
+        if *self == Self::zero() {
+            return String::from("0");
+        }
+
+        let abs = self.inner.abs();
+        let base = radix.base();
+        let one = Ratio::from_integer(BigInt::from(1));
+
+        let mut exp = if abs >= one {
+            abs.to_integer().to_str_radix(base).len() as i32 - 1
+        } else {
+            // Position of the first nonzero fractional digit
+            let mut exp = 0;
+            let mut shifted = abs.clone();
+            while shifted < one {
+                shifted = shift_ratio(&shifted, base, 1);
+                exp -= 1;
+            }
+            exp
+        };
+
+        let mantissa = |exp: i32| Self {
+            inner: Arc::new(shift_ratio(&abs, base, -exp)),
+        };
+
+        let mut digits = mantissa(exp).to_string(radix, precision);
+        // Rounded up to the radix, like 9.99 to 10
+        if digits.split('.').next().is_some_and(|int| int.len() > 1) {
+            exp += 1;
+            digits = mantissa(exp).to_string(radix, precision);
+        }
+
+        let sign = if self.is_negative() { "-" } else { "" };
+        format!("{sign}{digits}e{exp}")
+    }
+
+    /// Get the formatted string of a number at most `max_width` characters long. The plain
+    /// notation (see `Number::to_string`) is used with as many fractional digits as fit,
+    /// scientific notation (see `Number::to_scientific_string`) if the integer part alone
+    /// doesn't fit or a nonzero number would be shown as `0` \
+    /// The result is longer than `max_width` only when even the scientific notation with a
+    /// single digit mantissa doesn't fit
+    ///
+    /// ```
+    /// # use math::number::{Number, Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(1, 3)?.to_string_fit(Radix::Dec, 6), "0.3333");
+    /// assert_eq!(Number::from(-1234).to_string_fit(Radix::Dec, 5), "-1234");
+    /// assert_eq!(Number::from(123456789).to_string_fit(Radix::Dec, 6), "1.23e8");
+    /// assert_eq!(Number::new(1, 100000)?.to_string_fit(Radix::Dec, 6), "1e-5");
+    /// assert_eq!(Number::from(123456789).to_string_fit(Radix::Dec, 2), "1e8");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_string_fit(&self, radix: Radix, max_width: usize) -> String {
+        let to_precision = |width: usize| u8::try_from(width).unwrap_or(u8::MAX);
+
+        let sign = usize::from(self.is_negative());
+        let integer = self.inner.abs().to_integer().to_str_radix(radix.base());
+        if sign + integer.len() <= max_width {
+            // Room left after the integer part and the point
+            let mut precision = to_precision(max_width.saturating_sub(sign + integer.len() + 1));
+            let mut plain = self.to_string(radix, precision);
+            // Rounding can carry into a new integer digit
+            while plain.len() > max_width && precision > 0 {
+                precision -= 1;
+                plain = self.to_string(radix, precision);
+            }
+
+            let vanished = plain == "0" && *self != Self::zero();
+            if plain.len() <= max_width && !vanished {
+                return plain;
+            }
+        }
+
+        let shortest = self.to_scientific_string(radix, 0);
+        // Room left for the fractional digits of the mantissa and the point
+        let mut precision = to_precision(max_width.saturating_sub(shortest.len() + 1));
+        let mut scientific = self.to_scientific_string(radix, precision);
+        while scientific.len() > max_width && precision > 0 {
+            precision -= 1;
+            scientific = self.to_scientific_string(radix, precision);
+        }
+
+        scientific
+    }
+
+    /// Same as `Number::to_string`, but returns `None` when the number is the `0` placeholder
+    /// produced by a float fallback that overflowed or was not a number
+    ///
+    /// The marker is not kept through further operations, so only the direct result of
+    /// the approximating operation is detected
+    ///
+    /// ```
+    /// # use math::Number;
+    /// assert_eq!(Number::from(0).try_to_string(Default::default(), 6), Some(String::from("0")));
+    /// assert_eq!(Number::from(200).gamma().unwrap().try_to_string(Default::default(), 6), None);
+    /// ```
+    pub fn try_to_string(&self, radix: Radix, precision: u8) -> Option<String> {
+        let non_finite = NON_FINITE
+            .get()
+            .is_some_and(|zero| Arc::ptr_eq(zero, &self.inner));
+
+        (!non_finite).then(|| self.to_string(radix, precision))
+    }
+
+    /// Convert result of a float approximation, non-finite values become the `NON_FINITE` zero
+    fn from_f64(f: f64) -> Self {
+        mark_inexact();
+
+        let inner = match Ratio::from_float(f) {
+            Some(ratio) => Arc::new(ratio),
+            None => NON_FINITE.get_or_init(Default::default).clone(),
+        };
+
+        Self { inner }
+    }
+
+    /// Copy the value into a new allocation, which isn't shared with `self`. Not needed
+    /// for correctness (the value is immutable), but it lets a long living number drop
+    /// its reference to the memory of a shared one
+    ///
+    /// ```
+    /// # use math::Number;
+    /// let num = Number::pi();
+    /// assert_eq!(num.deep_clone(), num);
+    /// ```
+    pub fn deep_clone(&self) -> Self {
+        Self {
+            inner: Arc::new(Ratio::clone(&self.inner)),
+        }
+    }
+
+    /// Number of the clones sharing the value, for verifying the sharing in tests
+    #[cfg(test)]
+    pub(crate) fn strong_count(&self) -> usize {
+        Arc::strong_count(&self.inner)
+    }
+
+    /// Convert to `i32` if the number is an integer in its range
+    pub(crate) fn to_i32(&self) -> Option<i32> {
+        self.inner
+            .is_integer()
+            .then(|| self.inner.numer().to_i32())
+            .flatten()
+    }
+
+    /// Exact `n`th root, `None` if the numerator or the denominator isn't a perfect `n`th power
+    /// (or the root of a negative number is even)
+    pub(crate) fn exact_root(&self, n: u32) -> Option<Self> {
+        if n == 0 || (n.is_even() && self.inner.is_negative()) {
+            return None;
+        }
+
+        let root = |x: &BigInt| {
+            let res = x.nth_root(n);
+            (res.pow(n) == *x).then_some(res)
+        };
+
+        Some(Self {
+            inner: Arc::new(Ratio::new(
+                root(self.inner.numer())?,
+                root(self.inner.denom())?,
+            )),
+        })
+    }
+
+    /// Get the numerator and the denominator of the number in lowest terms,
+    /// the denominator is always positive
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # use num::BigInt;
+    /// let (numer, denom) = Number::new_unchecked(6, -8).as_ratio();
+    /// assert_eq!((numer, denom), (BigInt::from(-3), BigInt::from(4)));
+    /// ```
+    pub fn as_ratio(&self) -> (BigInt, BigInt) {
+        let reduced = self.inner.reduced();
+        (reduced.numer().clone(), reduced.denom().clone())
+    }
+
+    /// Exact conversion from `rust_decimal::Decimal`, which is a mantissa scaled by a power
+    /// of ten. `TryFrom` can't be implemented, it would overlap with `From<T: Into<BigInt>>`
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # use std::str::FromStr;
+    /// # fn main() -> math::Result<()> {
+    /// let dec = rust_decimal::Decimal::from_str("-1.25").unwrap();
+    /// assert_eq!(Number::from_decimal(dec), Number::new(-5, 4)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rust_decimal")]
+    pub fn from_decimal(value: rust_decimal::Decimal) -> Self {
+        Self::new_unchecked(value.mantissa(), BigInt::from(10).pow(value.scale()))
+    }
+
+    /// Parse a decimal number with a repeating part into the exact fraction. The repetend
+    /// is either written in brackets, `0.(3)` or `1.2(45)`, or it's recognized in a long enough
+    /// run of the same pattern at the end, so `0.333333` is `1/3` too. Shorter runs like `0.33`
+    /// are taken as they are
+    ///
+    /// # Error
+    /// Error::InvalidArguments if the string isn't a decimal number like `-12.3(45)`
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from_repeating_decimal("0.(3)")?, Number::new(1, 3)?);
+    /// assert_eq!(Number::from_repeating_decimal("0.1(6)")?, Number::new(1, 6)?);
+    /// assert_eq!(Number::from_repeating_decimal("-2.(142857)")?, Number::new(-15, 7)?);
+    /// assert_eq!(Number::from_repeating_decimal("0.333333")?, Number::new(1, 3)?);
+    /// assert_eq!(Number::from_repeating_decimal("0.33")?, Number::new(33, 100)?);
+    /// assert!(Number::from_repeating_decimal("0.(3").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_repeating_decimal(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+
+        let (fixed, repetend) = match fraction.split_once('(') {
+            Some((fixed, rest)) => match rest.strip_suffix(')') {
+                Some(repetend) if !repetend.is_empty() => (fixed, repetend),
+                _ => return Err(Error::InvalidArguments),
+            },
+            None if fraction.bytes().all(|ch| ch.is_ascii_digit()) => split_repetend(fraction),
+            None => return Err(Error::InvalidArguments),
+        };
+
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(Error::InvalidArguments);
+        }
+
+        let parse = |digits: &str| -> Result<BigInt> {
+            if !digits.bytes().all(|ch| ch.is_ascii_digit()) {
+                return Err(Error::InvalidArguments);
+            }
+            Ok(BigInt::parse_bytes(digits.as_bytes(), 10).unwrap_or_default())
+        };
+
+        let ten = BigInt::from(10);
+        let scale = ten.pow(fixed.len() as u32);
+        let period: BigInt = ten.pow(repetend.len() as u32) - 1;
+
+        // 1.2(45) = 1 + 2/10 + 45/(10 * 99), without a repetend the last term is 0/scale
+        let mut res = Ratio::from(parse(integer)?)
+            + Ratio::new(parse(fixed)?, scale.clone())
+            + Ratio::new(parse(repetend)?, scale * period.max(num::one()));
+
+        if negative {
+            res = -res;
+        }
+
+        Ok(Self {
+            inner: Arc::new(res),
+        })
+    }
+
+    /// Number of bits of the numerator's or the denominator's absolute value, whichever
+    /// is larger, a cheap measure of how large the number is
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::zero().bits(), 1);
+    /// assert_eq!(Number::from(-255).bits(), 8);
+    /// assert_eq!(Number::new(256, 3)?.bits(), 9);
+    /// assert_eq!(Number::new(3, 256)?.bits(), 9);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bits(&self) -> u64 {
+        self.inner.numer().bits().max(self.inner.denom().bits())
+    }
+
+    /// Get the number as a fraction `numer/denom` in lowest terms, integers are written
+    /// without the denominator
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(6, 8)?.to_fraction_string(Radix::Dec), "3/4");
+    /// assert_eq!(Number::new(-10, 4)?.to_fraction_string(Radix::Dec), "-5/2");
+    /// assert_eq!(Number::new(255, 16)?.to_fraction_string(Radix::Hex), "FF/10");
+    /// assert_eq!(Number::from(12).to_fraction_string(Radix::Dec), "12");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_fraction_string(&self, radix: Radix) -> String {
+        let (numer, denom) = self.as_ratio();
+        let numer = numer.to_str_radix(radix.base()).to_uppercase();
+
+        if denom == num::one() {
+            return numer;
+        }
+
+        format!(
+            "{numer}/{}",
+            denom.to_str_radix(radix.base()).to_uppercase()
+        )
+    }
+
+    /// Write the integer in the balanced ternary, base 3 with the digits -1, 0 and 1
+    /// written as `T`, `0` and `1`
+    ///
+    /// # Error
+    /// Error::InvalidArguments if the number isn't an integer
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// // 9 - 3 - 1
+    /// assert_eq!(Number::from(5).to_balanced_ternary_string()?, "1TT");
+    /// assert_eq!(Number::from(-5).to_balanced_ternary_string()?, "T11");
+    /// assert_eq!(Number::zero().to_balanced_ternary_string()?, "0");
+    /// assert!(Number::new(1, 2)?.to_balanced_ternary_string().is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_balanced_ternary_string(&self) -> Result<String> {
+        if !self.inner.is_integer() {
+            return Err(Error::InvalidArguments);
+        }
+
+        let three = BigInt::from(3);
+        let mut num = self.inner.to_integer();
+        let mut digits = Vec::new();
+
+        while num != BigInt::default() {
+            let (quot, rem) = num.div_mod_floor(&three);
+            let (digit, carry) = match rem.to_u8() {
+                Some(0) => ('0', 0),
+                Some(1) => ('1', 0),
+                _ => ('T', 1),
+            };
+
+            digits.push(digit);
+            num = quot + carry;
+        }
+
+        if digits.is_empty() {
+            return Ok(String::from("0"));
+        }
+
+        Ok(digits.into_iter().rev().collect())
+    }
+
+    /// Parse an integer written in the balanced ternary, see `Number::to_balanced_ternary_string`
+    ///
+    /// # Error
+    /// Error::InvalidArguments if the string is empty or contains other characters than `T`, `0`
+    /// and `1`
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from_balanced_ternary_str("1TT")?, Number::from(5));
+    /// assert_eq!(Number::from_balanced_ternary_str("T0")?, Number::from(-3));
+    /// assert!(Number::from_balanced_ternary_str("12").is_err());
+    /// assert!(Number::from_balanced_ternary_str("").is_err());
+    ///
+    /// for n in -40..=40 {
+    ///     let num = Number::from(n);
+    ///     let ternary = num.to_balanced_ternary_string()?;
+    ///     assert_eq!(Number::from_balanced_ternary_str(&ternary)?, num);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_balanced_ternary_str(s: &str) -> Result<Self> {
+        if s.is_empty() {
+            return Err(Error::InvalidArguments);
+        }
+
+        let num = s.chars().try_fold(BigInt::default(), |num, ch| {
+            let digit = match ch {
+                'T' => -1,
+                '0' => 0,
+                '1' => 1,
+                _ => return Err(Error::InvalidArguments),
+            };
+            Ok(num * 3 + digit)
+        })?;
+
+        Ok(Self::from(num))
+    }
+
+    /// Check whether the number is less than zero
+    ///
+    /// ```
+    /// # use math::Number;
+    /// assert!(Number::from(-3).is_negative());
+    /// assert!(!Number::from(3).is_negative());
+    /// assert!(!Number::zero().is_negative());
+    /// ```
+    pub fn is_negative(&self) -> bool {
+        self.inner.is_negative()
+    }
+
+    /// Check whether the number is at most `tol` away from the nearest integer
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// let tol = Number::guarantee_precision();
+    /// assert!(Number::new(2_999_999_999u64, 1_000_000_000)?.is_close_to_integer(&tol));
+    /// assert!(Number::new(-1_000_000_001i64, 1_000_000_000)?.is_close_to_integer(&tol));
+    /// assert!(Number::from(3).is_close_to_integer(&Number::zero()));
+    /// assert!(!Number::new(29, 10)?.is_close_to_integer(&tol));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_close_to_integer(&self, tol: &Number) -> bool {
+        let distance = (&*self.inner - self.inner.round()).abs();
+        distance <= *tol.inner
+    }
+
+    /// Check whether the number is a square of an integer
+    ///
+    /// ```
+    /// # use math::Number;
+    /// assert!(Number::from(144).is_perfect_square());
+    /// assert!(Number::zero().is_perfect_square());
+    /// assert!(!Number::from(145).is_perfect_square());
+    /// assert!(!Number::from(-4).is_perfect_square());
+    /// assert!(!Number::new_unchecked(1, 4).is_perfect_square());
+    /// ```
+    pub fn is_perfect_square(&self) -> bool {
+        self.inner.is_integer() && self.exact_root(2).is_some()
+    }
+
+    /// Get the base and the exponent, if the number is an integer power `base^exp` with `exp`
+    /// of at least 2. The greatest exponent (with the smallest base) is returned, `0`, `1`
+    /// and `-1` are powers of anything, so they are skipped
+    ///
+    /// ```
+    /// # use math::Number;
+    /// assert_eq!(Number::from(8).is_perfect_power(), Some((Number::from(2), 3)));
+    /// assert_eq!(Number::from(64).is_perfect_power(), Some((Number::from(2), 6)));
+    /// assert_eq!(Number::from(-27).is_perfect_power(), Some((Number::from(-3), 3)));
+    /// assert_eq!(Number::from(12).is_perfect_power(), None);
+    /// assert_eq!(Number::one().is_perfect_power(), None);
+    /// ```
+    pub fn is_perfect_power(&self) -> Option<(Self, u32)> {
+        if !self.inner.is_integer() || self.inner.numer().magnitude() <= &BigUint::from(1u8) {
+            return None;
+        }
+
+        let is_prime = |n: &u32| {
+            (2..)
+                .take_while(|d| d * d <= *n)
+                .all(|d| !n.is_multiple_of(&d))
+        };
+        let two = BigUint::from(2u8);
+
+        // Taking the prime roots one by one ends at the smallest base,
+        // the exponent is the product of the primes
+        let mut base = self.clone();
+        let mut exp = 1;
+        'roots: loop {
+            for prime in (2..).filter(is_prime) {
+                // Roots by the greater primes are even smaller
+                if base.inner.numer().magnitude().nth_root(prime) < two {
+                    break 'roots;
+                }
+
+                if let Some(root) = base.exact_root(prime) {
+                    base = root;
+                    exp *= prime;
+                    continue 'roots;
+                }
+            }
+        }
+
+        (exp > 1).then_some((base, exp))
+    }
+
+    /// Floor of the `n`th root of a non-negative integer, computed exactly on the integer
+    ///
+    /// # Error
+    /// Error::InvalidArguments if the number is not an integer
+    /// Error::NegativeRoot if the number is negative
+    /// Error::ZeroNthRoot if the `n` is 0
+    pub(crate) fn floor_root(&self, n: u32) -> Result<Self> {
+        if !self.inner.is_integer() {
+            return Err(Error::InvalidArguments);
+        }
+
+        if self.inner.is_negative() {
+            return Err(Error::NegativeRoot);
+        }
+
+        if n == 0 {
+            return Err(Error::ZeroNthRoot);
         }
 
-        Ok(Self {
-            inner: Arc::new(Ratio::new(num, denom)),
-        })
+        Ok(Self::from(self.inner.numer().nth_root(n)))
     }
 
-    /// Same as `Number::new` but bypass the zero check for denom
-    pub fn new_unchecked(num: impl Into<BigInt>, denom: impl Into<BigInt>) -> Self {
+    /// Get the sign of the number, `-1` for negative, `1` for positive and `0` for zero
+    ///
+    /// ```
+    /// # use math::Number;
+    /// assert_eq!(Number::from(-5).signum(), Number::from(-1));
+    /// assert_eq!(Number::new_unchecked(1, 3).signum(), Number::one());
+    /// assert_eq!(Number::zero().signum(), Number::zero());
+    /// ```
+    pub fn signum(&self) -> Self {
         Self {
-            inner: Arc::new(Ratio::new_raw(num.into(), denom.into())),
+            inner: Arc::new(self.inner.signum()),
         }
     }
 
-    /// Get the formatted string of a number
+    /// The Heaviside step function, `0` for negative numbers, `1` for positive numbers
+    /// and `at_zero` for zero. The common choices for `at_zero` are `0`, `1/2` and `1`
     ///
     /// ```
-    /// # use math::number::{Number, Radix};
-    /// let pi = Number::pi();
-    /// let zero = Number::zero();
-    /// let neg = Number::new_unchecked(-1, 10);
-    ///
-    /// let precision = 6;
-    ///
-    /// assert_eq!(zero.to_string(Radix::Bin, precision), "0");
-    /// assert_eq!(zero.to_string(Radix::Oct, precision), "0");
-    /// assert_eq!(zero.to_string(Radix::Dec, precision), "0");
-    /// assert_eq!(zero.to_string(Radix::Hex, precision), "0");
-    ///
-    /// assert_eq!(pi.to_string(Default::default(), precision), "3.141593");
-    /// assert_eq!(pi.to_string(Radix::Bin, precision), "11.001001");
-    /// assert_eq!(pi.to_string(Radix::Oct, precision), "3.110376");
-    /// assert_eq!(pi.to_string(Radix::Hex, precision), "3.243F6B");
-    ///
-    /// assert_eq!(neg.to_string(Default::default(), precision), "-0.1");
-    /// assert_eq!(neg.to_string(Radix::Bin, precision), "-0.00011");
-    /// assert_eq!(neg.to_string(Radix::Oct, precision), "-0.063146");
-    /// assert_eq!(neg.to_string(Radix::Hex, precision), "-0.19999A");
-    ///
-    /// assert_eq!(Number::new_unchecked(9, 10).to_string(Radix::Dec, 0), "1");
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// let half = Number::new(1, 2)?;
+    /// assert_eq!(Number::from(-3).heaviside(&half), Number::zero());
+    /// assert_eq!(Number::from(3).heaviside(&half), Number::one());
+    /// assert_eq!(Number::zero().heaviside(&half), half);
+    /// assert_eq!(Number::zero().heaviside(1), Number::one());
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn to_string(&self, radix: Radix, precision: u8) -> String {
-        let num = self.inner.abs();
-        let mut integer = num.to_integer();
-        let mut fract = num.fract();
-
-        let radix_len = match radix {
-            Radix::Bin => 2u32,
-            Radix::Oct => 8u32,
-            Radix::Dec => 10u32,
-            Radix::Hex => 16u32,
-        };
-
-        let mut fract_digits = Vec::new();
-
-        if fract != num::zero() {
-            let mut cnt = 0;
-            let big_radix = BigInt::from(radix_len);
-
-            while fract != num::zero() && cnt < precision {
-                let n = fract * &big_radix;
-                let whole = n.to_integer().to_u32().unwrap();
-                fract = n.fract();
-
-                fract_digits.push(whole);
-                cnt += 1;
-            }
-
-            let n = fract * &big_radix;
-            let whole = n.to_integer().to_u32().unwrap();
-
-            if whole >= (radix_len / 2) {
-                loop {
-                    match fract_digits.pop() {
-                        Some(mut end) => {
-                            end += 1;
-                            if end < radix_len {
-                                fract_digits.push(end);
-                                break;
-                            }
-                        }
-                        None => {
-                            integer += 1;
-                            break;
-                        }
-                    }
-                }
-            }
+    pub fn heaviside(&self, at_zero: impl Into<Self>) -> Self {
+        if self.inner.is_negative() {
+            Self::zero()
+        } else if self.inner.is_positive() {
+            Self::one()
+        } else {
+            at_zero.into()
         }
+    }
 
-        let mut res = match radix {
-            Radix::Bin => format!("{integer:b}"),
-            Radix::Oct => format!("{integer:o}"),
-            Radix::Dec => format!("{integer}"),
-            Radix::Hex => format!("{integer:X}"),
-        };
-
+    /// Get the sign of the number as a string, `"-"` for negative, `"+"` for positive
+    /// and empty string for zero
+    ///
+    /// ```
+    /// # use math::Number;
+    /// assert_eq!(Number::from(-3).signum_string(), "-");
+    /// assert_eq!(Number::from(3).signum_string(), "+");
+    /// assert_eq!(Number::zero().signum_string(), "");
+    /// ```
+    pub fn signum_string(&self) -> &'static str {
         if self.inner.is_negative() {
-            res.insert(0, '-');
+            "-"
+        } else if self.inner.is_positive() {
+            "+"
+        } else {
+            ""
         }
+    }
 
-        if !fract_digits.is_empty() {
-            res.push('.');
-
-            let digits = fract_digits
-                .into_iter()
-                .map(|v| char::from_digit(v, radix_len).unwrap())
-                .collect::<String>()
-                .to_ascii_uppercase();
-
-            res.push_str(&digits);
-            res = res.trim_end_matches('0').trim_end_matches('.').to_owned();
+    /// Multiply the number by `radix^places`, negative `places` divides instead
+    ///
+    /// This is cheaper than `Number::mul`/`Number::div` with a power of the radix, because only
+    /// one side of the fraction is scaled
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(3, 2)?.shift_radix(Radix::Dec, 2), Number::from(150));
+    /// assert_eq!(Number::from(150).shift_radix(Radix::Dec, -1), Number::from(15));
+    /// assert_eq!(Number::from(3).shift_radix(Radix::Bin, -1), Number::new(3, 2)?);
+    /// assert_eq!(Number::from(1).shift_radix(Radix::Hex, 2), Number::from(256));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shift_radix(&self, radix: Radix, places: i32) -> Self {
+        if places == 0 {
+            return self.clone();
         }
 
-        if res == "-0" {
-            res = String::from("0");
+        Self {
+            inner: Arc::new(shift_ratio(&self.inner, radix.base(), places).reduced()),
         }
+    }
 
-        res
+    /// Digits of the fractional part in the given `radix`, computed lazily by the long
+    /// division. The iterator ends after the last non-zero digit, it is infinite for the
+    /// repeating expansions. The sign and the integer part are ignored
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// let digits: Vec<u8> = Number::new(1, 7)?.digits(Radix::Dec).take(10).collect();
+    /// assert_eq!(digits, [1, 4, 2, 8, 5, 7, 1, 4, 2, 8]);
+    ///
+    /// let digits: Vec<u8> = Number::new(-13, 4)?.digits(Radix::Dec).collect();
+    /// assert_eq!(digits, [2, 5]);
+    /// let digits: Vec<u8> = Number::new(3, 16)?.digits(Radix::Hex).collect();
+    /// assert_eq!(digits, [3]);
+    /// let digits: Vec<u8> = Number::new(2, 3)?.digits(Radix::Bin).take(4).collect();
+    /// assert_eq!(digits, [1, 0, 1, 0]);
+    ///
+    /// assert_eq!(Number::from(42).digits(Radix::Dec).next(), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn digits(&self, radix: Radix) -> impl Iterator<Item = u8> {
+        let base = BigInt::from(radix.base());
+        let denom = self.inner.denom().clone();
+        let mut rem = (self.inner.numer() % &denom).abs();
+
+        std::iter::from_fn(move || {
+            if rem == BigInt::default() {
+                return None;
+            }
+
+            rem *= &base;
+            let (digit, next) = rem.div_rem(&denom);
+            rem = next;
+            digit.to_u8()
+        })
     }
 
     /// Display the number in degree (angle)
@@ -453,11 +1545,18 @@ impl Number {
 
     /// Raises self to the power of `exp`, using exponentiation by squaring.
     ///
+    /// Rational exponents `p/q` are computed as `root(q)` then `pow(p)`, the result is exact
+    /// when `self` is a perfect `q`th power
+    ///
     /// ```
     /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
     /// assert_eq!(Number::random().power(Number::zero()), Ok(Number::one()));
     /// assert_eq!(Number::from(5).power(2), Ok(Number::from(25)));
     /// assert_eq!(Number::from(5).power(Number::pi()).unwrap_or_default().to_string(Default::default(), 6), "156.992545");
+    /// assert_eq!(Number::from(8).power(Number::new(2, 3)?)?, Number::from(4));
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn power(&self, exp: impl Into<Self>) -> Result<Self> {
         let exp = exp.into();
@@ -478,63 +1577,379 @@ impl Number {
             return Ok(Self::zero());
         }
 
+        // Rational exponents of perfect powers like `8^(2/3)` are computed exactly
+        let exact = exp.inner.numer().to_i32().zip(exp.inner.denom().to_u32());
+        if let Some((to_pow, to_root)) = exact {
+            if let Some(root) = self.exact_root(to_root) {
+                return Ok(Self {
+                    inner: Arc::new(root.inner.pow(to_pow)),
+                });
+            }
+        }
+
         let exp_digits_points = Self {
             inner: Arc::new(exp.inner.fract()),
         }
         .to_string(Default::default(), 7)
         .len();
 
-        let Some(to_pow) = exp.inner.numer().to_i32().filter(|_| exp_digits_points <= 7) else {
-            let e = exp.inner.to_f64().ok_or_else(|| Error::Message(String::from("Exponent is too large")))?;
-            let x = self.inner.to_f64().ok_or_else(|| Error::Message(String::from("Exponent is too large")))?;
+        let Some(to_pow) = exp
+            .inner
+            .numer()
+            .to_i32()
+            .filter(|_| exp_digits_points <= 7)
+        else {
+            let e = exp
+                .inner
+                .to_f64()
+                .ok_or_else(|| Error::Message(String::from("Exponent is too large")))?;
+            let x = self
+                .inner
+                .to_f64()
+                .ok_or_else(|| Error::Message(String::from("Exponent is too large")))?;
             let f = libm::pow(x, e);
-            return Ok(Self {
-                inner: Arc::new(Ratio::from_float(f).unwrap_or_default())
-            });
+            return Ok(Self::from_f64(f));
         };
 
         let to_root = exp.inner.denom();
 
-        let mut res = self.clone();
+        let mut res = self.clone();
+
+        if to_root != &num::one() {
+            res = res.root(to_root.clone())?;
+        }
+
+        res.inner = Arc::new((*res.inner).pow(to_pow));
+
+        Ok(res)
+    }
+
+    /// Get the modulo of `self / other`
+    ///
+    /// The modulo is floored, `self - other * floor(self / other)`, so the result has the sign
+    /// of `other`. Fractional operands are computed exactly, `5.5 mod 2 = 1.5`
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from(5).modulo(2), Ok(Number::one()));
+    /// assert_eq!(Number::from(-5).modulo(2), Ok(Number::one()));
+    /// assert_eq!(Number::from(5).modulo(-2), Ok(Number::from(-1)));
+    /// assert_eq!(Number::from(-5).modulo(-2), Ok(Number::from(-1)));
+    /// assert_eq!(Number::from(-7).modulo(3), Ok(Number::from(2)));
+    ///
+    /// assert_eq!(Number::new(11, 2)?.modulo(2)?, Number::new(3, 2)?);
+    /// assert_eq!(Number::new(-11, 2)?.modulo(2)?, Number::new(1, 2)?);
+    /// assert_eq!(Number::new(11, 2)?.modulo(Number::new(5, 2)?)?, Number::new(1, 2)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn modulo(&self, other: impl Into<Self>) -> Result<Self> {
+        let divisor = other.into();
+        self.remainder(&divisor)?.add(&divisor)?.remainder(&divisor)
+    }
+
+    /// Get the remainder of `self / other`
+    ///
+    /// The remainder is truncated, `self - other * trunc(self / other)`, so the result has the
+    /// sign of `self`, same as `%` of the primitive types. Fractional operands are computed
+    /// exactly, `5.5 % 2 = 1.5`
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from(5).remainder(2), Ok(Number::one()));
+    /// assert_eq!(Number::from(5).remainder(-2), Ok(Number::one()));
+    /// assert_eq!(Number::from(-5).remainder(2), Ok(Number::from(-1)));
+    /// assert_eq!(Number::from(-7).remainder(3), Ok(Number::from(-1)));
+    /// assert_eq!(Number::from(7).remainder(-3), Ok(Number::from(1)));
+    ///
+    /// assert_eq!(Number::new(11, 2)?.remainder(2)?, Number::new(3, 2)?);
+    /// assert_eq!(Number::new(-11, 2)?.remainder(2)?, Number::new(-3, 2)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remainder(&self, other: impl Into<Self>) -> Result<Self> {
+        Ok(Self {
+            inner: Arc::new(&*self.inner % &*other.into().inner),
+        })
+    }
+
+    /// Greatest common divisor of `self` and `other`, always non-negative, `gcd(0, 0) = 0`
+    ///
+    /// # Error
+    /// Error::Message if either of the numbers isn't an integer
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from(12).gcd(18)?, Number::from(6));
+    /// assert_eq!(Number::from(0).gcd(5)?, Number::from(5));
+    /// assert_eq!(Number::from(-4).gcd(6)?, Number::from(2));
+    /// assert!(Number::new(1, 2)?.gcd(2).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn gcd(&self, other: impl Into<Self>) -> Result<Self> {
+        let other = other.into();
+        self.check_integers(&other, "gcd")?;
+        let gcd = self.inner.numer().gcd(other.inner.numer());
+
+        Ok(Self {
+            inner: Arc::new(Ratio::from_integer(gcd)),
+        })
+    }
+
+    /// Least common multiple of `self` and `other`, always non-negative,
+    /// `0` if either of them is `0`
+    ///
+    /// # Error
+    /// Error::Message if either of the numbers isn't an integer
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from(4).lcm(6)?, Number::from(12));
+    /// assert_eq!(Number::from(0).lcm(5)?, Number::zero());
+    /// assert_eq!(Number::from(-3).lcm(5)?, Number::from(15));
+    /// assert!(Number::from(2).lcm(Number::new(1, 2)?).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn lcm(&self, other: impl Into<Self>) -> Result<Self> {
+        let other = other.into();
+        self.check_integers(&other, "lcm")?;
+        let lcm = self.inner.numer().lcm(other.inner.numer());
+
+        Ok(Self {
+            inner: Arc::new(Ratio::from_integer(lcm)),
+        })
+    }
+
+    /// Check that both `self` and `other` are integers, `name` of the function is used in the error
+    fn check_integers(&self, other: &Self, name: &str) -> Result<()> {
+        if !self.inner.is_integer() || !other.inner.is_integer() {
+            let msg = format!("Arguments of {name} must be integers");
+            return Err(Error::Message(msg));
+        }
+
+        Ok(())
+    }
+
+    /// Get the Euclidean remainder of `self / other`, which is never negative,
+    /// same as `i64::rem_euclid`
+    ///
+    /// # Error
+    /// Error::DivisionZero if `other` is zero
+    ///
+    /// ```
+    /// # use math::Number;
+    /// assert_eq!(Number::from(7).rem_euclid(4), Ok(Number::from(3)));
+    /// assert_eq!(Number::from(-7).rem_euclid(4), Ok(Number::from(1)));
+    /// assert_eq!(Number::from(7).rem_euclid(-4), Ok(Number::from(3)));
+    /// assert_eq!(Number::from(-7).rem_euclid(-4), Ok(Number::from(1)));
+    /// assert_eq!(Number::new_unchecked(-1, 2).rem_euclid(2), Ok(Number::new_unchecked(3, 2)));
+    /// ```
+    pub fn rem_euclid(&self, other: impl Into<Self>) -> Result<Self> {
+        let divisor = other.into();
+
+        if divisor == Self::zero() {
+            return Err(Error::DivisionZero);
+        }
+
+        let rem = self.remainder(&divisor)?;
+
+        if rem.is_negative() {
+            rem.add(divisor.abs()?)
+        } else {
+            Ok(rem)
+        }
+    }
+
+    /// Get the Euclidean quotient of `self / other`, the integer `q` such that
+    /// `self == q * other + self.rem_euclid(other)`, same as `i64::div_euclid`
+    ///
+    /// # Error
+    /// Error::DivisionZero if `other` is zero
+    ///
+    /// ```
+    /// # use math::Number;
+    /// assert_eq!(Number::from(7).div_euclid(4), Ok(Number::from(1)));
+    /// assert_eq!(Number::from(-7).div_euclid(4), Ok(Number::from(-2)));
+    /// assert_eq!(Number::from(7).div_euclid(-4), Ok(Number::from(-1)));
+    /// assert_eq!(Number::from(-7).div_euclid(-4), Ok(Number::from(2)));
+    /// ```
+    pub fn div_euclid(&self, other: impl Into<Self>) -> Result<Self> {
+        let divisor = other.into();
+        self.sub(self.rem_euclid(&divisor)?)?.div(divisor)
+    }
+
+    /// Round to the nearest multiple of `step`, halfway cases are rounded away from zero.
+    /// The sign of `step` doesn't matter
+    ///
+    /// # Error
+    /// Error::DivisionZero if `step` is zero
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// let quarter = Number::new(1, 4)?;
+    /// assert_eq!(Number::new(11, 10)?.round_to_multiple(&quarter)?, Number::one());
+    /// assert_eq!(Number::new(12, 10)?.round_to_multiple(&quarter)?, Number::new(5, 4)?);
+    /// assert_eq!(Number::from(13).round_to_multiple(5)?, Number::from(15));
+    /// assert_eq!(Number::from(-12).round_to_multiple(-5)?, Number::from(-10));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn round_to_multiple(&self, step: impl Into<Self>) -> Result<Self> {
+        self.to_multiple(step, Ratio::round)
+    }
+
+    /// Round down to a multiple of `step`. The sign of `step` doesn't matter
+    ///
+    /// # Error
+    /// Error::DivisionZero if `step` is zero
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from(14).floor_to_multiple(5)?, Number::from(10));
+    /// assert_eq!(Number::from(-11).floor_to_multiple(5)?, Number::from(-15));
+    /// assert_eq!(Number::new(7, 8)?.floor_to_multiple(Number::new(1, 4)?)?, Number::new(3, 4)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn floor_to_multiple(&self, step: impl Into<Self>) -> Result<Self> {
+        self.to_multiple(step, Ratio::floor)
+    }
+
+    /// Round up to a multiple of `step`. The sign of `step` doesn't matter
+    ///
+    /// # Error
+    /// Error::DivisionZero if `step` is zero
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from(11).ceil_to_multiple(5)?, Number::from(15));
+    /// assert_eq!(Number::from(-14).ceil_to_multiple(5)?, Number::from(-10));
+    /// assert_eq!(Number::from(10).ceil_to_multiple(-5)?, Number::from(10));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ceil_to_multiple(&self, step: impl Into<Self>) -> Result<Self> {
+        self.to_multiple(step, Ratio::ceil)
+    }
 
-        if to_root != &num::one() {
-            res = res.root(to_root.clone())?;
+    /// Round down to the nearest integer
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(7, 2)?.floor(), Number::from(3));
+    /// assert_eq!(Number::new(-1, 2)?.floor(), Number::from(-1));
+    /// assert_eq!(Number::new(-7, 2)?.floor(), Number::from(-4));
+    /// assert_eq!(Number::from(-2).floor(), Number::from(-2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn floor(&self) -> Self {
+        Self {
+            inner: Arc::new(self.inner.floor()),
         }
+    }
 
-        res.inner = Arc::new((*res.inner).pow(to_pow));
+    /// Round up to the nearest integer
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(7, 2)?.ceil(), Number::from(4));
+    /// assert_eq!(Number::new(-1, 2)?.ceil(), Number::zero());
+    /// assert_eq!(Number::new(-7, 2)?.ceil(), Number::from(-3));
+    /// assert_eq!(Number::from(-2).ceil(), Number::from(-2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ceil(&self) -> Self {
+        Self {
+            inner: Arc::new(self.inner.ceil()),
+        }
+    }
 
-        Ok(res)
+    /// Round to the nearest integer, halfway cases are rounded away from zero
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(5, 2)?.round(), Number::from(3));
+    /// assert_eq!(Number::new(-5, 2)?.round(), Number::from(-3));
+    /// assert_eq!(Number::new(-12, 5)?.round(), Number::from(-2));
+    /// assert_eq!(Number::new(-1, 3)?.round(), Number::zero());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn round(&self) -> Self {
+        Self {
+            inner: Arc::new(self.inner.round()),
+        }
     }
 
-    /// Get the modulo of `self / other`
+    /// Round towards zero to the nearest integer, dropping the fractional part
     ///
     /// ```
     /// # use math::Number;
-    /// assert_eq!(Number::from(5).modulo(2), Ok(Number::one()));
-    /// assert_eq!(Number::from(-5).modulo(2), Ok(Number::one()));
-    /// assert_eq!(Number::from(5).modulo(-2), Ok(Number::from(-1)));
-    /// assert_eq!(Number::from(-5).modulo(-2), Ok(Number::from(-1)));
-    /// assert_eq!(Number::from(-7).modulo(3), Ok(Number::from(2)));
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(29, 10)?.trunc(), Number::from(2));
+    /// assert_eq!(Number::new(-29, 10)?.trunc(), Number::from(-2));
+    /// assert_eq!(Number::new(-1, 2)?.trunc(), Number::zero());
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn modulo(&self, other: impl Into<Self>) -> Result<Self> {
-        let divisor = other.into();
-        self.remainder(&divisor)?.add(&divisor)?.remainder(&divisor)
+    pub fn trunc(&self) -> Self {
+        Self {
+            inner: Arc::new(self.inner.trunc()),
+        }
     }
 
-    /// Get the remainder of `self / other`
+    /// Round to `decimals` decimal places, halfway cases are rounded away from zero. Unlike
+    /// `Number::to_string` the result stays exact, so it can be used for further computation
     ///
     /// ```
     /// # use math::Number;
-    /// assert_eq!(Number::from(5).modulo(2), Ok(Number::one()));
-    /// assert_eq!(Number::from(5).modulo(-2), Ok(Number::from(-1)));
-    /// assert_eq!(Number::from(-5).modulo(2), Ok(Number::from(1)));
-    /// assert_eq!(Number::from(-7).modulo(3), Ok(Number::from(2)));
-    /// assert_eq!(Number::from(7).modulo(-3), Ok(Number::from(-2)));
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(1, 3)?.round_to(4), Number::new(3333, 10000)?);
+    /// assert_eq!(Number::new(2, 3)?.round_to(2), Number::new(67, 100)?);
+    /// assert_eq!(Number::new(-1, 8)?.round_to(2), Number::new(-13, 100)?);
+    /// assert_eq!(Number::new(1, 3)?.round_to(0), Number::zero());
+    ///
+    /// // already exact values are unchanged
+    /// assert_eq!(Number::new(5, 4)?.round_to(2), Number::new(5, 4)?);
+    /// assert_eq!(Number::from(42).round_to(3), Number::from(42));
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn remainder(&self, other: impl Into<Self>) -> Result<Self> {
-        Ok(Self {
-            inner: Arc::new(&*self.inner % &*other.into().inner),
-        })
+    pub fn round_to(&self, decimals: u32) -> Self {
+        let scale = Ratio::from_integer(BigInt::from(10u32).pow(decimals));
+
+        Self {
+            inner: Arc::new((&*self.inner * &scale).round() / scale),
+        }
+    }
+
+    /// `round(self / |step|) * |step|`
+    fn to_multiple(
+        &self,
+        step: impl Into<Self>,
+        round: fn(&Ratio<BigInt>) -> Ratio<BigInt>,
+    ) -> Result<Self> {
+        let step = step.into().abs()?;
+        let quotient = self.div(&step)?;
+
+        Self {
+            inner: Arc::new(round(&quotient.inner)),
+        }
+        .mul(step)
     }
 
     /// Get the absolute value of the given number
@@ -599,12 +2014,43 @@ impl Number {
     }
 
     /// Calculate gamma function
+    /// Negative numbers are computed by the reflection formula `Γ(x)Γ(1 - x) = π / sin(πx)`
+    ///
+    /// # Error
+    /// Error::FactorialNegative if the number is a non-positive integer, a pole of the function
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # use math::number::Radix;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from(5).gamma()?.to_string(Radix::Dec, 6), "24");
+    /// assert_eq!(Number::new(1, 2)?.gamma()?.to_string(Radix::Dec, 6), "1.772454");
+    ///
+    /// // Γ(-1/2) == -2 * sqrt(π)
+    /// let minus_half = Number::new(-1, 2)?.gamma()?;
+    /// let expected = Number::pi().sqrt()?.mul(-2)?;
+    /// assert!(minus_half.sub(expected)?.abs()? < Number::guarantee_precision());
+    /// assert_eq!(Number::new(-3, 2)?.gamma()?.to_string(Radix::Dec, 6), "2.363272");
+    ///
+    /// assert!(Number::zero().gamma().is_err());
+    /// assert!(Number::from(-1).gamma().is_err());
+    /// #     Ok(())
+    /// # }
+    /// ```
     pub fn gamma(&self) -> Result<Self> {
+        if self.inner.is_integer() && !self.inner.is_positive() {
+            return Err(Error::FactorialNegative);
+        }
+
+        if self.inner.is_negative() {
+            let sin = Self::pi().mul(self)?.sin()?;
+            let reflected = Self::one().sub(self)?.gamma()?;
+            return Self::pi().div(sin.mul(reflected)?);
+        }
+
         let f = self.inner.to_f64().unwrap_or_default();
         let gamma = libm::tgamma(f);
-        Ok(Self {
-            inner: Arc::new(Ratio::from_float(gamma).unwrap_or_default()),
-        })
+        Ok(Self::from_f64(gamma))
 
         // let p = [
         //     Self::new_unchecked(9999999999998099i128, 10000000000000000i128),
@@ -701,12 +2147,8 @@ impl Number {
             return Ok(Self::one());
         }
 
-        let f = self.inner.to_f64().unwrap_or_default();
-        let base = base.inner.to_f64().unwrap_or_default();
-        let log = f.log(base);
-        let res = Self {
-            inner: Arc::new(Ratio::from_float(log).unwrap_or_default()),
-        };
+        let log = ln_ratio(&self.inner) / ln_ratio(&base.inner);
+        let res = Self::from_f64(log);
 
         Ok(res)
     }
@@ -721,11 +2163,282 @@ impl Number {
         self.log(Self::e())
     }
 
+    /// Same as `Number::ln`, but the error for arguments outside of the domain carries the
+    /// argument, so front-ends can explain why the logarithm is undefined
+    ///
+    /// # Error
+    /// Error::LogNonPositive if the number is less or equal zero
+    ///
+    /// ```
+    /// # use math::{Number, error::Error};
+    /// assert_eq!(Number::one().ln_checked(), Ok(Number::zero()));
+    /// assert_eq!(Number::from(-2).ln_checked(), Err(Error::LogNonPositive(Number::from(-2))));
+    /// ```
+    pub fn ln_checked(&self) -> Result<Self> {
+        if self <= &Self::zero() {
+            return Err(Error::LogNonPositive(self.clone()));
+        }
+
+        self.ln()
+    }
+
     /// Same as `Number::log` with `base` of 10
     pub fn log10(&self) -> Result<Self> {
         self.log(10)
     }
 
+    /// The logistic function `1 / (1 + e^-x)`
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::zero().sigmoid()?, Number::new(1, 2)?);
+    /// assert_eq!(Number::from(2).sigmoid()?.to_string(Radix::Dec, 6), "0.880797");
+    /// assert_eq!(Number::from(-2).sigmoid()?.to_string(Radix::Dec, 6), "0.119203");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sigmoid(&self) -> Result<Self> {
+        let exp = self.mul(-1)?.exp()?;
+        Self::one().div(exp.add(1)?)
+    }
+
+    /// The inverse of `Number::sigmoid`, `ln(p / (1 - p))`
+    ///
+    /// # Error
+    /// Error::OutOfRange if the number isn't between 0 and 1 (both excluded)
+    ///
+    /// ```
+    /// # use math::{Number, error::Error};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(1, 2)?.logit()?, Number::zero());
+    /// assert_eq!(Number::one().logit(), Err(Error::OutOfRange));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn logit(&self) -> Result<Self> {
+        if self <= &Self::zero() || self >= &Self::one() {
+            return Err(Error::OutOfRange);
+        }
+
+        self.div(Self::one().sub(self)?)?.ln()
+    }
+
+    /// The exponential function `e^x`, computed by its Taylor series, so it doesn't depend
+    /// on the precision of `Number::e`. `exp(0)` is exactly 1. The integer part of `x`
+    /// is raised separately as `e^n`, so the series converges quickly for large arguments
+    ///
+    /// # Error
+    /// Error::Overflow if `|x|` is larger than 10 000
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::zero().exp()?, Number::one());
+    /// assert_eq!(Number::one().exp()?.to_string(Radix::Dec, 10), "2.7182818285");
+    /// assert_eq!(Number::from(-2).exp()?.to_string(Radix::Dec, 10), "0.1353352832");
+    ///
+    /// // ln(exp(x)) == x
+    /// let x = Number::random();
+    /// assert!(x.exp()?.ln()?.sub(&x)?.abs()? < Number::guarantee_precision());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn exp(&self) -> Result<Self> {
+        if self == &Self::zero() {
+            return Ok(Self::one());
+        }
+
+        // The terms of negative numbers alternate, the reciprocal avoids the cancellation
+        if self.is_negative() {
+            return Self::one().div(self.abs()?.exp()?);
+        }
+
+        mark_inexact();
+
+        // e^x = e^n * e^(x - n)
+        let int = self.trunc();
+        if int != Self::zero() {
+            let mut n = int
+                .to_i32()
+                .filter(|n| *n <= MAX_EXP_ARGUMENT)
+                .ok_or(Error::Overflow)?;
+
+            // Exponentiation by squaring, rounded after every step, so the fractions stay small
+            let mut exp_int = Self::one();
+            let mut power = Self::e_precise(REDUCTION_DIGITS);
+            while n > 0 {
+                if n % 2 == 1 {
+                    exp_int = exp_int.mul(&power)?.round_to(REDUCTION_DIGITS);
+                }
+                power = power.mul(&power)?.round_to(REDUCTION_DIGITS);
+                n /= 2;
+            }
+
+            return exp_int.mul(self.sub(&int)?.exp()?);
+        }
+
+        let mut res = Self::one();
+        let mut tmp = Self::one();
+        let mut step = Self::one();
+
+        while tmp >= Self::epsilon() {
+            tmp = tmp.mul(self)?.div(&step)?;
+            res = res.add(&tmp)?;
+            step = step.add(1)?;
+        }
+
+        Ok(res)
+    }
+
+    /// `e^x` and `e^-x` used by the hyperbolic functions
+    fn exp_pair(&self) -> Result<(Self, Self)> {
+        let exp = self.exp()?;
+        let inv = Self::one().div(&exp)?;
+        Ok((exp, inv))
+    }
+
+    /// The hyperbolic sine `(e^x - e^-x) / 2`
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::zero().sinh()?, Number::zero());
+    /// assert_eq!(Number::one().sinh()?.to_string(Radix::Dec, 6), "1.175201");
+    /// assert_eq!(Number::from(-1).sinh()?.to_string(Radix::Dec, 6), "-1.175201");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sinh(&self) -> Result<Self> {
+        let (exp, inv) = self.exp_pair()?;
+        exp.sub(inv)?.div(2)
+    }
+
+    /// The hyperbolic cosine `(e^x + e^-x) / 2`
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::zero().cosh()?, Number::one());
+    ///
+    /// // cosh(x)^2 - sinh(x)^2 == 1
+    /// let x = Number::random();
+    /// let identity = x.cosh()?.power(2)?.sub(x.sinh()?.power(2)?)?;
+    /// assert!(identity.sub(1)?.abs()? < Number::guarantee_precision());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cosh(&self) -> Result<Self> {
+        let (exp, inv) = self.exp_pair()?;
+        exp.add(inv)?.div(2)
+    }
+
+    /// The hyperbolic tangent `sinh(x) / cosh(x)`, it approaches `1` (or `-1`) for large
+    /// (or small) numbers
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::zero().tanh()?, Number::zero());
+    /// assert_eq!(Number::one().tanh()?.to_string(Radix::Dec, 6), "0.761594");
+    /// assert_eq!(Number::from(1000).tanh()?, Number::one());
+    /// assert_eq!(Number::from(-1000).tanh()?, Number::from(-1));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tanh(&self) -> Result<Self> {
+        // `1 - tanh(50)` is about 1e-43, far below any displayed precision
+        if self.abs()? > Self::from(50) {
+            return Ok(self.signum());
+        }
+
+        // `(1 - e^-2|x|) / (1 + e^-2|x|)` doesn't overflow for large numbers
+        let inv = self.abs()?.mul(-2)?.exp()?;
+        let res = Self::one().sub(&inv)?.div(Self::one().add(inv)?)?;
+        res.mul(self.signum())
+    }
+
+    /// The hyperbolic cotangent `cosh(x) / sinh(x)`
+    ///
+    /// # Error
+    /// Error::DivisionZero if the number is 0
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::one().coth()?.to_string(Radix::Dec, 6), "1.313035");
+    /// assert!(Number::zero().coth().is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn coth(&self) -> Result<Self> {
+        Self::one().div(self.tanh()?)
+    }
+
+    /// The inverse of `Number::sinh`, `ln(x + sqrt(x^2 + 1))`
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::zero().arsinh()?, Number::zero());
+    /// assert_eq!(Number::one().arsinh()?.to_string(Radix::Dec, 6), "0.881374");
+    /// assert_eq!(Number::from(-1).arsinh()?.to_string(Radix::Dec, 6), "-0.881374");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn arsinh(&self) -> Result<Self> {
+        // `arsinh` is odd, computing it for the absolute value avoids the cancellation
+        // of `x + sqrt(x^2 + 1)` for negative numbers
+        let x = self.abs()?;
+        let res = x.add(x.power(2)?.add(1)?.sqrt()?)?.ln()?;
+        res.mul(self.signum())
+    }
+
+    /// The inverse of `Number::cosh`, `ln(x + sqrt(x^2 - 1))`
+    ///
+    /// # Error
+    /// Error::OutOfRange if the number is less than 1
+    ///
+    /// ```
+    /// # use math::{Number, error::Error, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::one().arcosh()?, Number::zero());
+    /// assert_eq!(Number::from(2).arcosh()?.to_string(Radix::Dec, 6), "1.316958");
+    /// assert_eq!(Number::new(1, 2)?.arcosh(), Err(Error::OutOfRange));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn arcosh(&self) -> Result<Self> {
+        if self < &Self::one() {
+            return Err(Error::OutOfRange);
+        }
+
+        self.add(self.power(2)?.sub(1)?.sqrt()?)?.ln()
+    }
+
+    /// The inverse of `Number::tanh`, `ln((1 + x) / (1 - x)) / 2`
+    ///
+    /// # Error
+    /// Error::OutOfRange if the number isn't between -1 and 1 (both excluded)
+    ///
+    /// ```
+    /// # use math::{Number, error::Error, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::zero().artanh()?, Number::zero());
+    /// assert_eq!(Number::new(1, 2)?.artanh()?.to_string(Radix::Dec, 6), "0.549306");
+    /// assert_eq!(Number::one().artanh(), Err(Error::OutOfRange));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn artanh(&self) -> Result<Self> {
+        if self.abs()? >= Self::one() {
+            return Err(Error::OutOfRange);
+        }
+
+        let ratio = Self::one().add(self)?.div(Self::one().sub(self)?)?;
+        ratio.ln()?.div(2)
+    }
+
     /// Returns the nth root of a number
     ///
     /// # Error
@@ -782,7 +2495,12 @@ impl Number {
 
         let mut res = self.clone();
 
-        if to_root != &num::one() {
+        // Roots of perfect powers like `root(3, 27/8)` are computed exactly
+        let exact = to_root.to_u32().and_then(|n| self.exact_root(n));
+
+        if let Some(exact) = exact {
+            res = exact;
+        } else if to_root != &num::one() {
             let x = self.inner.to_f64().unwrap_or_default();
             let n = to_root.to_i32().unwrap_or_default();
             let epsilon = Self::epsilon().inner.to_f64().unwrap();
@@ -791,20 +2509,54 @@ impl Number {
             while (result - prev).abs() > epsilon {
                 prev = result;
                 result = (n - 1) as f64 * prev;
-                result += x / prev.powi(n - 1) as f64;
+                result += x / prev.powi(n - 1);
                 result /= n as f64;
             }
 
-            res = Self {
-                inner: Arc::new(Ratio::from_float(result).unwrap_or_default()),
-            };
-        }
+            res = Self::from_f64(result);
+        }
+
+        if to_pow != &num::one() {
+            res = res.power(to_pow.clone())?;
+        }
+
+        Ok(res)
+    }
+
+    /// Returns all real nth roots of a number, `Number::root` returns only the principal one \
+    /// Even roots of a positive number come in pairs `[root, -root]`, even roots of a negative
+    /// number have no real solution
+    ///
+    /// # Error
+    /// Error::ZeroNthRoot if the `nth` is 0
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from(4).roots(2)?, vec![Number::from(2), Number::from(-2)]);
+    /// assert_eq!(Number::from(-8).roots(3)?, vec![Number::from(-2)]);
+    /// assert_eq!(Number::from(8).roots(3)?, vec![Number::from(2)]);
+    /// assert_eq!(Number::from(-4).roots(2)?, vec![]);
+    /// assert_eq!(Number::zero().roots(2)?, vec![Number::zero()]);
+    /// assert!(Number::from(4).roots(0).is_err());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn roots(&self, nth: impl Into<Self>) -> Result<Vec<Self>> {
+        let nth = nth.into();
+        let even = nth.inner.numer().is_even();
+
+        let root = match self.root(&nth) {
+            Err(Error::NegativeRoot) => return Ok(Vec::new()),
+            root => root?,
+        };
 
-        if to_pow != &num::one() {
-            res = res.power(to_pow.clone())?;
+        if !even || root == Self::zero() {
+            return Ok(vec![root]);
         }
 
-        Ok(res)
+        let negative = root.mul(-1)?;
+        Ok(vec![root, negative])
     }
 
     /// Returns the square root of a number.
@@ -813,6 +2565,56 @@ impl Number {
         self.root(2)
     }
 
+    /// Returns the integer square root, the square root of a non-negative integer rounded down.
+    /// It is computed exactly on the integer, so unlike `sqrt` it stays precise for huge numbers
+    ///
+    /// # Error
+    /// Error::InvalidArguments if the number is not an integer
+    /// Error::NegativeRoot if the number is negative
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from(15).isqrt()?, Number::from(3));
+    /// assert_eq!(Number::from(16).isqrt()?, Number::from(4));
+    /// assert!(Number::from(-16).isqrt().is_err());
+    /// assert!(Number::new(1, 2)?.isqrt().is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn isqrt(&self) -> Result<Self> {
+        self.floor_root(2)
+    }
+
+    /// Convert an angle in degrees to radians, the result is exact up to the precision
+    /// of `Number::pi`
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from(180).to_radians()?, Number::pi());
+    /// assert_eq!(Number::from(90).to_radians()?, Number::pi().div(2)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_radians(&self) -> Result<Self> {
+        self.mul(Self::pi())?.div(180)
+    }
+
+    /// Convert an angle in radians to degrees, the inverse of `Number::to_radians`
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::pi().to_degrees()?, Number::from(180));
+    /// assert_eq!(Number::from(30).to_radians()?.to_degrees()?, Number::from(30));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_degrees(&self) -> Result<Self> {
+        self.mul(180)?.div(Self::pi())
+    }
+
     /// Computes the sine of a number (in radians).
     ///
     /// ```
@@ -850,6 +2652,16 @@ impl Number {
             }
         }
 
+        mark_inexact();
+
+        // Rounded, so the terms of the series don't grow too large
+        let x = if self.abs()? >= Self::tau() {
+            let tau = Self::pi_precise(REDUCTION_DIGITS).mul(2)?;
+            self.modulo(tau)?.round_to(REDUCTION_DIGITS / 2)
+        } else {
+            x
+        };
+
         let mut res = x.clone();
         let mut tmp = x.clone();
 
@@ -955,9 +2767,7 @@ impl Number {
 
         let f = self.inner.to_f64().unwrap_or_default();
         let arcsin = f.asin();
-        let res = Self {
-            inner: Arc::new(Ratio::from_float(arcsin).unwrap_or_default()),
-        };
+        let res = Self::from_f64(arcsin);
 
         // let denom = Self::one().sub(self.power(2)?)?.sqrt()?;
         // self.div(denom)?.arctg()
@@ -1021,9 +2831,7 @@ impl Number {
     pub fn arctg(&self) -> Result<Self> {
         let f = self.inner.to_f64().unwrap_or_default();
         let arctan = f.atan();
-        let res = Self {
-            inner: Arc::new(Ratio::from_float(arctan).unwrap_or_default()),
-        };
+        let res = Self::from_f64(arctan);
 
         Ok(res)
     }
@@ -1044,17 +2852,235 @@ impl Number {
         Self::pi().div(2)?.sub(self.arctg()?)
     }
 
+    /// Computes the secant of a number (in radians), `1 / cos(x)`
+    ///
+    /// # Error
+    /// Error::DivisionZero if the cosine of the number is zero
+    ///
+    /// ```
+    /// # use math::{Number, error::Error};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::zero().sec()?, Number::one());
+    /// assert_eq!(Number::pi().sec()?, Number::minus_one());
+    /// assert_eq!(Number::pi().div(2)?.sec(), Err(Error::DivisionZero));
+    ///
+    /// let x = Number::random();
+    /// assert_eq!(x.sec()?, Number::one().div(x.cos()?)?);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn sec(&self) -> Result<Self> {
+        Self::one().div(self.cos()?)
+    }
+
+    /// Computes the cosecant of a number (in radians), `1 / sin(x)`
+    ///
+    /// # Error
+    /// Error::DivisionZero if the sine of the number is zero
+    ///
+    /// ```
+    /// # use math::{Number, error::Error};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::pi().div(2)?.csc()?, Number::one());
+    /// assert_eq!(Number::pi().div(6)?.csc()?, Number::from(2));
+    /// assert_eq!(Number::zero().csc(), Err(Error::DivisionZero));
+    ///
+    /// let x = Number::random();
+    /// assert_eq!(x.csc()?, Number::one().div(x.sin()?)?);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn csc(&self) -> Result<Self> {
+        Self::one().div(self.sin()?)
+    }
+
+    /// Computes the arcsecant of a number, `arccos(1 / x)`. Return value is in radians
+    /// in the range <0, pi>
+    ///
+    /// # Error
+    /// Error::OutOfRange if the number is in the range (-1, 1)
+    ///
+    /// ```
+    /// # use math::{Number, error::Error};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(1, 2)?.arcsec(), Err(Error::OutOfRange));
+    /// assert_eq!(Number::zero().arcsec(), Err(Error::OutOfRange));
+    ///
+    /// let x = Number::random();
+    /// let rev_sec_x = x.sec()?.arcsec()?;
+    ///
+    /// assert!(x.sub(rev_sec_x)?.abs()? < Number::guarantee_precision());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn arcsec(&self) -> Result<Self> {
+        if self.abs()? < Self::one() {
+            return Err(Error::OutOfRange);
+        }
+
+        Self::one().div(self)?.arccos()
+    }
+
+    /// Computes the arccosecant of a number, `arcsin(1 / x)`. Return value is in radians
+    /// in the range <-pi/2, pi/2>
+    ///
+    /// # Error
+    /// Error::OutOfRange if the number is in the range (-1, 1)
+    ///
+    /// ```
+    /// # use math::{Number, error::Error};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::new(-1, 2)?.arccsc(), Err(Error::OutOfRange));
+    /// assert_eq!(Number::zero().arccsc(), Err(Error::OutOfRange));
+    ///
+    /// let x = Number::random();
+    /// let rev_csc_x = x.csc()?.arccsc()?;
+    ///
+    /// assert!(x.sub(rev_csc_x)?.abs()? < Number::guarantee_precision());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn arccsc(&self) -> Result<Self> {
+        if self.abs()? < Self::one() {
+            return Err(Error::OutOfRange);
+        }
+
+        Self::one().div(self)?.arcsin()
+    }
+
+    /// Computes the angle of the point `[x, y]` from the positive x axis, the arctangent
+    /// of `y / x` in the quadrant of the point. Return value is in radians in the range (-pi, pi>
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// let deg = |y: i64, x: i64| -> math::Result<String> {
+    ///     let angle = Number::atan2(y, x)?.to_degrees()?;
+    ///     Ok(angle.to_string(Radix::Dec, 4))
+    /// };
+    ///
+    /// assert_eq!(deg(1, 1)?, "45");
+    /// assert_eq!(deg(1, -1)?, "135");
+    /// assert_eq!(deg(-1, -1)?, "-135");
+    /// assert_eq!(deg(-1, 1)?, "-45");
+    ///
+    /// assert_eq!(Number::atan2(0, -1)?, Number::pi());
+    /// assert_eq!(Number::atan2(-1, 0)?, Number::pi().div(-2)?);
+    /// assert_eq!(Number::atan2(0, 0)?, Number::zero());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn atan2(y: impl Into<Self>, x: impl Into<Self>) -> Result<Self> {
+        let y = y.into();
+        let x = x.into();
+        let zero = Self::zero();
+
+        match x.cmp(&zero) {
+            Ordering::Greater => y.div(x)?.arctg(),
+            Ordering::Equal => match y.cmp(&zero) {
+                Ordering::Greater => Self::pi().div(2),
+                Ordering::Less => Self::pi().div(-2),
+                Ordering::Equal => Ok(zero),
+            },
+            Ordering::Less => {
+                let arctg = y.div(x)?.arctg()?;
+                match y.is_negative() {
+                    true => arctg.sub(Self::pi()),
+                    false => arctg.add(Self::pi()),
+                }
+            }
+        }
+    }
+
+    /// Calculate the sum of digits of the number written in the given `radix`
+    ///
+    /// # Error
+    /// Error::InvalidArguments if the number is not a non-negative integer
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::from(123).digit_sum(Radix::Dec)?, Number::from(6));
+    /// assert_eq!(Number::from(255).digit_sum(Radix::Hex)?, Number::from(30));
+    /// assert_eq!(Number::from(7).digit_sum(Radix::Bin)?, Number::from(3));
+    /// assert_eq!(Number::zero().digit_sum(Radix::Oct)?, Number::zero());
+    /// assert!(Number::new(3, 2)?.digit_sum(Radix::Dec).is_err());
+    /// assert!(Number::from(-12).digit_sum(Radix::Dec).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn digit_sum(&self, radix: Radix) -> Result<Self> {
+        if !self.inner.is_integer() || self.inner.is_negative() {
+            return Err(Error::InvalidArguments);
+        }
+
+        let base = BigInt::from(radix.base());
+        let mut rest = self.inner.to_integer();
+        let mut sum = BigInt::default();
+
+        while rest != num::zero() {
+            let (quotient, digit) = rest.div_rem(&base);
+            sum += digit;
+            rest = quotient;
+        }
+
+        Ok(Self::from(sum))
+    }
+
+    /// Get the number of digits of an integer in the given `radix`, the sign isn't counted
+    /// and zero has a single digit \
+    /// Computed as `floor(log_radix(|self|)) + 1` on integers, so it's exact even for huge numbers
+    ///
+    /// # Error
+    /// Error::InvalidArguments if the number is not an integer
+    ///
+    /// ```
+    /// # use math::{Number, number::Radix};
+    /// # fn main() -> math::Result<()> {
+    /// assert_eq!(Number::zero().digit_count(Radix::Dec)?, 1);
+    /// assert_eq!(Number::from(999).digit_count(Radix::Dec)?, 3);
+    /// assert_eq!(Number::from(1000).digit_count(Radix::Dec)?, 4);
+    /// assert_eq!(Number::from(255).digit_count(Radix::Hex)?, 2);
+    /// assert_eq!(Number::from(256).digit_count(Radix::Hex)?, 3);
+    /// assert_eq!(Number::from(-8).digit_count(Radix::Bin)?, 4);
+    /// assert!(Number::new(3, 2)?.digit_count(Radix::Dec).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn digit_count(&self, radix: Radix) -> Result<u32> {
+        if !self.inner.is_integer() {
+            return Err(Error::InvalidArguments);
+        }
+
+        let num = self.inner.to_integer().abs();
+        let base = BigInt::from(radix.base());
+        let mut power = base.clone();
+        let mut count = 1;
+
+        while power <= num {
+            power *= &base;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
     /// Calculate combination number of the given `n` and `k`
     ///
     /// Since combination number is defined as `C(n, k)` mathematically
     /// Neither `n` nor `k` is considered the center of the function
     /// that's why this function does not taking `self` as parameter like other functions
     ///
+    /// Non-negative integers are computed exactly using factorials. Other numbers use the
+    /// generalized binomial coefficient `Γ(n + 1) / (Γ(k + 1) * Γ(n - k + 1))`
+    ///
     /// # Error
-    /// Error::FactorialNegative if either `n` or `k` is negative, because they will need to be factorialized
+    /// Error::FactorialNegative if either `n`, `k` or `n - k` is a negative integer, because the gamma
+    /// function isn't defined for them
     ///
     /// ```
     /// # use math::Number;
+    /// # use math::number::Radix;
     /// # fn main() -> math::Result<()> {
     /// assert!(Number::combination(-1, Number::random()).is_err());
     /// assert!(Number::combination(Number::random(), -1).is_err());
@@ -1069,18 +3095,23 @@ impl Number {
     /// assert_eq!(Number::combination(&n, &n)?, Number::one());
     /// // C(n, 1) == n
     /// assert_eq!(Number::combination(&n, 1)?, n);
+    ///
+    /// // C(4.5, 2) == 4.5 * 3.5 / 2
+    /// let real = Number::combination(Number::new(9, 2)?, 2)?;
+    /// assert_eq!(real.to_string(Radix::Dec, 6), "7.875");
+    /// // C(0.5, 2) == 0.5 * -0.5 / 2
+    /// let real = Number::combination(Number::new(1, 2)?, 2)?;
+    /// assert_eq!(real.to_string(Radix::Dec, 6), "-0.125");
     /// #     Ok(())
     /// # }
     /// ```
     pub fn combination(n: impl Into<Self>, k: impl Into<Self>) -> Result<Self> {
         let zero = Self::zero();
         let n = n.into();
-        if n < zero {
-            return Err(Error::FactorialNegative);
-        }
-
         let k = k.into();
-        if k < zero {
+
+        let is_negative_integer = |x: &Self| x.inner.is_integer() && x.inner.is_negative();
+        if is_negative_integer(&n) || is_negative_integer(&k) {
             return Err(Error::FactorialNegative);
         }
 
@@ -1092,6 +3123,11 @@ impl Number {
             return Ok(n);
         }
 
+        let is_natural = |x: &Self| x.inner.is_integer() && !x.inner.is_negative();
+        if !is_natural(&n) || !is_natural(&k) {
+            return Self::combination_gamma(&n, &k);
+        }
+
         if k > n {
             return Ok(Self::zero());
         }
@@ -1102,6 +3138,83 @@ impl Number {
 
         n.factorial()?.div(denom)
     }
+
+    /// Generalized binomial coefficient `Γ(n + 1) / (Γ(k + 1) * Γ(n - k + 1))`
+    fn combination_gamma(n: &Self, k: &Self) -> Result<Self> {
+        let n1 = n.add(1)?;
+        let k1 = k.add(1)?;
+        let nk1 = n.sub(k)?.add(1)?;
+
+        // Gamma function has poles in non-positive integers
+        let is_pole = |x: &Self| x.inner.is_integer() && !x.inner.is_positive();
+        if is_pole(&n1) || is_pole(&k1) || is_pole(&nk1) {
+            return Err(Error::FactorialNegative);
+        }
+
+        n1.gamma()?.div(k1.gamma()?.mul(nk1.gamma()?)?)
+    }
+
+    /// Number of `k`-permutations of `n` items `P(n, k) = n! / (n - k)!`
+    ///
+    /// Non-negative integers are computed exactly using factorials. Other numbers use
+    /// `Γ(n + 1) / Γ(n - k + 1)`
+    ///
+    /// # Error
+    /// Error::FactorialNegative if either `n`, `k` or `n - k` is a negative integer, because the gamma
+    /// function isn't defined for them
+    ///
+    /// ```
+    /// # use math::Number;
+    /// # use math::number::Radix;
+    /// # fn main() -> math::Result<()> {
+    /// assert!(Number::permutation(-1, Number::random()).is_err());
+    /// assert!(Number::permutation(Number::random(), -1).is_err());
+    ///
+    /// // if k > n => P(n, k) == 0
+    /// assert_eq!(Number::permutation(3, 5)?, Number::zero());
+    /// // P(n, 0) == 1
+    /// assert_eq!(Number::permutation(7, 0)?, Number::one());
+    /// // P(n, n) == n!
+    /// assert_eq!(Number::permutation(7, 7)?, Number::from(7).factorial()?);
+    /// // P(5, 2) == 5 * 4
+    /// assert_eq!(Number::permutation(5, 2)?, Number::from(20));
+    ///
+    /// // P(4.5, 2) == 4.5 * 3.5
+    /// let real = Number::permutation(Number::new(9, 2)?, 2)?;
+    /// assert_eq!(real.to_string(Radix::Dec, 6), "15.75");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn permutation(n: impl Into<Self>, k: impl Into<Self>) -> Result<Self> {
+        let n = n.into();
+        let k = k.into();
+
+        let is_natural = |x: &Self| x.inner.is_integer() && !x.inner.is_negative();
+        if !is_natural(&n) || !is_natural(&k) {
+            return Self::permutation_gamma(&n, &k);
+        }
+
+        if k > n {
+            return Ok(Self::zero());
+        }
+
+        n.factorial()?.div(n.sub(&k)?.factorial()?)
+    }
+
+    /// Generalized permutation `Γ(n + 1) / Γ(n - k + 1)`
+    fn permutation_gamma(n: &Self, k: &Self) -> Result<Self> {
+        let n1 = n.add(1)?;
+        let k1 = k.add(1)?;
+        let nk1 = n.sub(k)?.add(1)?;
+
+        // Gamma function has poles in non-positive integers
+        let is_pole = |x: &Self| x.inner.is_integer() && !x.inner.is_positive();
+        if is_pole(&n1) || is_pole(&k1) || is_pole(&nk1) {
+            return Err(Error::FactorialNegative);
+        }
+
+        n1.gamma()?.div(nk1.gamma()?)
+    }
 }
 
 impl Ord for Number {
@@ -1123,3 +3236,365 @@ impl PartialEq for Number {
 }
 
 impl Eq for Number {}
+
+impl Hash for Number {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
+
+/// Decimal representation with 12 fractional digits, or the precision given to the formatter
+///
+/// ```
+/// # use math::Number;
+/// # fn main() -> math::Result<()> {
+/// let third = Number::new(1, 3)?;
+/// assert_eq!(format!("{third}"), "0.333333333333");
+/// assert_eq!(format!("{third:.3}"), "0.333");
+/// assert_eq!(format!("{}", Number::new(-5, 2)?), "-2.5");
+/// # Ok(())
+/// # }
+/// ```
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f
+            .precision()
+            .map_or(DISPLAY_PRECISION, |p| p.min(u8::MAX.into()) as u8);
+        f.write_str(&self.to_string(Radix::Dec, precision))
+    }
+}
+
+/// Parse an exact decimal number like `-0.75`, or a fraction like `3/4`
+/// (see `Number::from_ratio_str`)
+///
+/// # Error
+/// Error::DivisionZero if the denominator of the fraction is 0 \
+/// Error::InvalidToken if the string isn't a decimal number or a fraction
+///
+/// ```
+/// # use math::{Number, error::Error};
+/// # fn main() -> math::Result<()> {
+/// assert_eq!("0.75".parse::<Number>()?, Number::new(3, 4)?);
+/// assert_eq!("3/4".parse::<Number>()?, Number::new(3, 4)?);
+/// assert_eq!(" -12.5 ".parse::<Number>()?, Number::new(-25, 2)?);
+/// assert_eq!(".5".parse::<Number>()?, Number::new(1, 2)?);
+/// assert_eq!("42".parse::<Number>()?, Number::from(42));
+///
+/// assert_eq!("1/0".parse::<Number>(), Err(Error::DivisionZero));
+/// assert_eq!("1.2.3".parse::<Number>(), Err(Error::InvalidToken));
+/// assert_eq!("-".parse::<Number>(), Err(Error::InvalidToken));
+/// assert_eq!("1e5".parse::<Number>(), Err(Error::InvalidToken));
+/// # Ok(())
+/// # }
+/// ```
+impl FromStr for Number {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.contains('/') {
+            return Self::from_ratio_str(s);
+        }
+
+        let s = s.trim();
+        let (sign, digits) = match s.strip_prefix(['-', '+']) {
+            Some(rest) => (&s[..1], rest),
+            None => ("", s),
+        };
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let is_digits = |part: &str| part.bytes().all(|ch| ch.is_ascii_digit());
+        if integer.len() + fraction.len() == 0 || !is_digits(integer) || !is_digits(fraction) {
+            return Err(Error::InvalidToken);
+        }
+
+        let numer: BigInt = format!("{sign}{integer}{fraction}")
+            .parse()
+            .map_err(|_| Error::InvalidToken)?;
+        let denom = BigInt::from(10).pow(fraction.len() as u32);
+
+        Self::new(numer, denom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatted_number_cache() {
+        let num = FormattedNumber::from(Number::new_unchecked(2, 3));
+        let cached = || num.cache.borrow().clone();
+        assert_eq!(cached(), None);
+
+        let first = num.to_string(Radix::Dec, 4);
+        assert_eq!(first, "0.6667");
+        assert_eq!(num.to_string(Radix::Dec, 4), first);
+        assert_eq!(cached(), Some((Radix::Dec, 4, first)));
+
+        assert_eq!(num.to_string(Radix::Dec, 2), "0.67");
+        assert_eq!(cached(), Some((Radix::Dec, 2, String::from("0.67"))));
+
+        assert_eq!(num.to_string(Radix::Bin, 2), "0.11");
+        assert_eq!(cached(), Some((Radix::Bin, 2, String::from("0.11"))));
+    }
+
+    #[test]
+    fn clone_shares_value() {
+        let num = Number::new_unchecked(2, 3);
+        assert_eq!(num.strong_count(), 1);
+
+        let shared = num.clone();
+        assert!(Arc::ptr_eq(&num.inner, &shared.inner));
+        assert_eq!(num.strong_count(), 2);
+
+        let unique = num.deep_clone();
+        assert!(!Arc::ptr_eq(&num.inner, &unique.inner));
+        assert_eq!(unique, num);
+        assert_eq!(unique.strong_count(), 1);
+        assert_eq!(num.strong_count(), 2);
+
+        drop(shared);
+        assert_eq!(num.strong_count(), 1);
+    }
+
+    #[test]
+    fn euclid_matches_std() -> Result<()> {
+        for a in -12i64..=12 {
+            for b in (-5i64..=5).filter(|&b| b != 0) {
+                let (x, y) = (Number::from(a), Number::from(b));
+                assert_eq!(
+                    x.rem_euclid(&y)?,
+                    Number::from(a.rem_euclid(b)),
+                    "{a} rem {b}"
+                );
+                assert_eq!(
+                    x.div_euclid(&y)?,
+                    Number::from(a.div_euclid(b)),
+                    "{a} div {b}"
+                );
+            }
+        }
+
+        assert_eq!(Number::from(1).rem_euclid(0), Err(Error::DivisionZero));
+        assert_eq!(Number::from(1).div_euclid(0), Err(Error::DivisionZero));
+        Ok(())
+    }
+
+    #[test]
+    fn number_roots() -> Result<()> {
+        assert_eq!(
+            Number::from(4).roots(2)?,
+            vec![Number::from(2), Number::from(-2)]
+        );
+        assert_eq!(Number::from(-8).roots(3)?, vec![Number::from(-2)]);
+        assert_eq!(Number::from(-16).roots(4)?, vec![]);
+        assert_eq!(Number::from(4).root(2)?, Number::from(2));
+        Ok(())
+    }
+
+    #[test]
+    fn ln_domain() {
+        assert_eq!(Number::zero().ln(), Err(Error::LogUndefinedNumber));
+        assert_eq!(
+            Number::zero().ln_checked(),
+            Err(Error::LogNonPositive(Number::zero()))
+        );
+
+        let err = Number::from(-1).ln_checked().unwrap_err();
+        assert_eq!(err, Error::LogNonPositive(Number::from(-1)));
+        assert_eq!(
+            err.to_string(),
+            "Real logarithm of -1 is undefined, the argument has to be greater than zero"
+        );
+    }
+
+    #[test]
+    fn digit_count_matches_to_string() -> Result<()> {
+        let radixes = [Radix::Bin, Radix::Oct, Radix::Dec, Radix::Hex];
+        for n in (0..=4100).chain([65535, 65536, 1_000_000, 999_999_999]) {
+            for radix in radixes {
+                let num = Number::from(n);
+                let digits = num.to_string(radix, 0).len() as u32;
+                assert_eq!(num.digit_count(radix)?, digits, "{n} in {radix:?}");
+            }
+        }
+
+        let huge = Number::from(10).power(100)?;
+        assert_eq!(huge.digit_count(Radix::Dec)?, 101);
+        assert_eq!(huge.sub(1)?.digit_count(Radix::Dec)?, 100);
+        Ok(())
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn decimal_round_trip() -> Result<()> {
+        use rust_decimal::Decimal;
+
+        for s in [
+            "0",
+            "1.5",
+            "-0.001",
+            "123456789.987654321",
+            "79228162514264337593543950335",
+        ] {
+            let dec = Decimal::from_str(s).unwrap();
+            let num = Number::from_decimal(dec);
+            assert_eq!(Decimal::try_from(num)?, dec);
+        }
+
+        assert_eq!(
+            Number::from_decimal(Decimal::from_str("0.75").unwrap()),
+            Number::new(3, 4)?
+        );
+        assert_eq!(
+            Decimal::try_from(Number::new(2, 3)?),
+            Err(Error::OutOfRange)
+        );
+        // doesn't fit into 96 bits of mantissa
+        let big = Number::from(2).power(100)?;
+        assert_eq!(Decimal::try_from(big), Err(Error::OutOfRange));
+        Ok(())
+    }
+
+    #[test]
+    fn round_to_multiple() -> Result<()> {
+        let quarter = Number::new(1, 4)?;
+
+        assert_eq!(
+            Number::new(11, 10)?.round_to_multiple(&quarter)?,
+            Number::one()
+        );
+        assert_eq!(
+            Number::new(12, 10)?.round_to_multiple(&quarter)?,
+            Number::new(5, 4)?
+        );
+        assert_eq!(Number::from(13).round_to_multiple(5)?, Number::from(15));
+        assert_eq!(Number::from(12).round_to_multiple(5)?, Number::from(10));
+        // halfway rounds away from zero
+        assert_eq!(
+            Number::new(-25, 2)?.round_to_multiple(5)?,
+            Number::from(-15)
+        );
+
+        assert_eq!(
+            Number::new(12, 10)?.floor_to_multiple(&quarter)?,
+            Number::one()
+        );
+        assert_eq!(
+            Number::new(11, 10)?.ceil_to_multiple(&quarter)?,
+            Number::new(5, 4)?
+        );
+        assert_eq!(Number::from(15).floor_to_multiple(5)?, Number::from(15));
+        assert_eq!(Number::from(15).ceil_to_multiple(5)?, Number::from(15));
+
+        assert_eq!(Number::one().round_to_multiple(0), Err(Error::DivisionZero));
+        assert_eq!(Number::one().floor_to_multiple(0), Err(Error::DivisionZero));
+        assert_eq!(Number::one().ceil_to_multiple(0), Err(Error::DivisionZero));
+        Ok(())
+    }
+
+    #[test]
+    fn repeating_decimals() -> Result<()> {
+        assert_eq!(Number::from_repeating_decimal("0.(3)")?, Number::new(1, 3)?);
+        assert_eq!(
+            Number::from_repeating_decimal("0.(142857)")?,
+            Number::new(1, 7)?
+        );
+        assert_eq!(Number::from_repeating_decimal("0.(9)")?, Number::one());
+        assert_eq!(
+            Number::from_repeating_decimal("12.5(0)")?,
+            Number::new(25, 2)?
+        );
+        assert_eq!(Number::from_repeating_decimal(".(6)")?, Number::new(2, 3)?);
+        assert_eq!(Number::from_repeating_decimal("42")?, Number::from(42));
+
+        // recognized runs
+        assert_eq!(
+            Number::from_repeating_decimal("0.142857142857")?,
+            Number::new(1, 7)?
+        );
+        assert_eq!(
+            Number::from_repeating_decimal("0.8333333")?,
+            Number::new(5, 6)?
+        );
+        assert_eq!(
+            Number::from_repeating_decimal("0.121212")?,
+            Number::new(4, 33)?
+        );
+        assert_eq!(
+            Number::from_repeating_decimal("0.12345")?,
+            Number::new(12345, 100000)?
+        );
+
+        for invalid in [
+            "", ".", "-", "0.(3", "0.()", "0.(3)4", "1a.5", "0.5.5", "0.3x",
+        ] {
+            assert_eq!(
+                Number::from_repeating_decimal(invalid),
+                Err(Error::InvalidArguments)
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn perfect_powers() -> Result<()> {
+        assert!(Number::from(144).is_perfect_square());
+        assert!(!Number::from(145).is_perfect_square());
+        assert_eq!(
+            Number::from(8).is_perfect_power(),
+            Some((Number::from(2), 3))
+        );
+        assert_eq!(Number::from(145).is_perfect_power(), None);
+
+        let huge = Number::from(7).power(120)?;
+        assert!(huge.is_perfect_square());
+        assert_eq!(huge.is_perfect_power(), Some((Number::from(7), 120)));
+
+        // prime exponent
+        let mersenne = Number::from(2).power(127)?;
+        assert_eq!(mersenne.is_perfect_power(), Some((Number::from(2), 127)));
+        assert_eq!(mersenne.add(1)?.is_perfect_power(), None);
+        assert_eq!(
+            Number::from(-6).power(35)?.is_perfect_power(),
+            Some((Number::from(-6), 35))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn integer_square_root() -> Result<()> {
+        assert_eq!(Number::from(15).isqrt()?, Number::from(3));
+        assert_eq!(Number::from(16).isqrt()?, Number::from(4));
+
+        let big = Number::from(10).power(20)?;
+        assert_eq!(big.mul(&big)?.isqrt()?, big);
+        // one below a huge square is rounded down, not up
+        assert_eq!(big.mul(&big)?.sub(1)?.isqrt()?, big.sub(1)?);
+
+        assert_eq!(Number::from(-1).isqrt(), Err(Error::NegativeRoot));
+        assert_eq!(Number::new(3, 2)?.isqrt(), Err(Error::InvalidArguments));
+        Ok(())
+    }
+
+    #[test]
+    fn exp_large_arguments() -> Result<()> {
+        let exp = |x: i32| Number::from(x).exp();
+
+        assert_eq!(
+            exp(50)?.to_string(Radix::Dec, 6),
+            "5184705528587072464087.453323"
+        );
+        assert_eq!(
+            Number::new(21, 2)?.exp()?.to_string(Radix::Dec, 6),
+            "36315.502674"
+        );
+        assert_eq!(exp(1000)?.mul(exp(-1000)?)?.to_string(Radix::Dec, 20), "1");
+        // e^10000 = 8.8068...e4342
+        let largest = exp(10_000)?.to_string(Radix::Dec, 0);
+        assert_eq!((&largest[..5], largest.len()), ("88068", 4343));
+        assert_eq!(exp(10_001), Err(Error::Overflow));
+        assert_eq!(exp(-10_001), Err(Error::Overflow));
+        Ok(())
+    }
+}
@@ -35,11 +35,16 @@
 //!
 
 use crate::error::Error;
-use crate::number::Number;
+use crate::number::{Number, Radix};
 use crate::Result;
 use num::BigUint;
+use std::fmt;
 use std::mem;
 
+/// Largest absolute value of the exponent in the scientific notation, larger numbers
+/// would take too long to construct
+const MAX_EXPONENT: i32 = 10_000;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// Representation of a bracket
 pub enum Bracket {
@@ -68,6 +73,26 @@ pub enum Operator {
     Modulo,
 }
 
+/// ```
+/// # use math::token::Operator;
+/// assert_eq!(Operator::Multiply.to_string(), "*");
+/// assert_eq!(Operator::Modulo.to_string(), "mod");
+/// ```
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Plus => "+",
+            Self::Minus => "-",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+            Self::Power => "^",
+            Self::Modulo => "mod",
+        };
+
+        f.write_str(s)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 /// Representation of a token
@@ -86,6 +111,46 @@ pub enum Token {
     Id(String),
 }
 
+/// What to do with integers written with a leading zero, like `0123`
+///
+/// Only decimal digits after the zero are affected, `0.5`, `0x1F`, `0b101` and `0o17`
+/// are scanned the same under every policy
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LeadingZeros {
+    /// The leading zero is ignored, `0123` is `123`
+    #[default]
+    Ignore,
+    /// The number is rejected with `Error::UnsupportedToken`
+    Error,
+    /// C-style octal literal, `0123` is `83`. Digits `8` and `9` and a fractional part
+    /// like `012.5` are rejected
+    Octal,
+}
+
+/// Where the digit group separator `_` is in the digits of a number
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Separator {
+    /// No digit to separate yet, after the radix prefix or the point
+    #[default]
+    Forbidden,
+    /// Allowed after a digit
+    Allowed,
+    /// Just read, a digit has to follow
+    Pending,
+}
+
+impl Separator {
+    /// The separator was read, fails if it isn't after a digit
+    fn read(&mut self) -> Result<()> {
+        if *self != Self::Allowed {
+            return Err(Error::UnsupportedToken(0));
+        }
+
+        *self = Self::Pending;
+        Ok(())
+    }
+}
+
 #[derive(Debug, Default, PartialEq)]
 enum State {
     #[default]
@@ -100,17 +165,35 @@ enum State {
     RightPar,
     VerticalLine,
     Comma,
+    /// Prefix root sign, holds the name of the applied function
+    Root(&'static str),
+    /// Line comment, skipped until the end of the line
+    Comment,
     Identifier(String),
     NumberStart,
     Number {
         radix: u32,
         num: BigUint,
+        separator: Separator,
+        /// The point can follow, not allowed in the legacy octal literal `012`
+        point_allowed: bool,
     },
     FractionStart,
     Fraction {
         radix: u32,
         num: BigUint,
         fract_cnt: u32,
+        separator: Separator,
+    },
+    /// `e` of the scientific notation, an optional sign is expected
+    ExponentSign {
+        mantissa: Number,
+    },
+    /// Digits of the exponent in the scientific notation
+    Exponent {
+        mantissa: Number,
+        negative: bool,
+        exp: i32,
     },
 }
 
@@ -132,14 +215,29 @@ impl State {
                 _ => Token::Id(s),
             },
             Self::NumberStart => Token::Number(Number::zero()),
+            // Separator at the end of the number
+            Self::Number {
+                separator: Separator::Pending,
+                ..
+            }
+            | Self::Fraction {
+                separator: Separator::Pending,
+                ..
+            } => return None,
             Self::Number { num, .. } => Token::Number(Number::from(num)),
             Self::Fraction {
                 num,
                 fract_cnt,
                 radix,
+                ..
+            } => Token::Number(fraction_value(num, radix, fract_cnt)),
+            Self::Exponent {
+                mantissa,
+                negative,
+                exp,
             } => {
-                let val = Number::new(num, BigUint::from(radix).pow(fract_cnt)).unwrap_or_default();
-                Token::Number(val)
+                let exp = if negative { -exp } else { exp };
+                Token::Number(mantissa.shift_radix(Radix::Dec, exp))
             }
 
             _ => return None,
@@ -148,8 +246,49 @@ impl State {
         Some(token)
     }
 
-    fn next_state(&mut self, ch: char) -> Result<Option<State>> {
+    /// `starts_exponent` is set, if `ch` is `e` followed by the (signed) exponent
+    fn next_state(
+        &mut self,
+        ch: char,
+        leading_zeros: LeadingZeros,
+        starts_exponent: bool,
+    ) -> Result<Option<State>> {
+        // Only a digit can follow the separator
+        if let Self::Number {
+            radix,
+            separator: Separator::Pending,
+            ..
+        }
+        | Self::Fraction {
+            radix,
+            separator: Separator::Pending,
+            ..
+        } = self
+        {
+            if !ch.is_digit(*radix) {
+                return Err(Error::UnsupportedToken(0));
+            }
+        }
+
         let next = match self {
+            Self::NumberStart
+            | Self::Number { radix: 10, .. }
+            | Self::Fraction { radix: 10, .. }
+                if starts_exponent =>
+            {
+                let mantissa = match mem::take(self) {
+                    Self::Number { num, .. } => Number::from(num),
+                    Self::Fraction {
+                        num,
+                        radix,
+                        fract_cnt,
+                        ..
+                    } => fraction_value(num, radix, fract_cnt),
+                    _ => Number::zero(),
+                };
+
+                Some(Self::ExponentSign { mantissa })
+            }
             Self::FactorialSign
             | Self::LeftPar
             | Self::RightPar
@@ -158,8 +297,16 @@ impl State {
             | Self::Add
             | Self::Sub
             | Self::Mul
-            | Self::Div
             | Self::Pow => Some(Self::Start),
+            // `//` starts a comment
+            Self::Div => match ch {
+                '/' => Some(Self::Comment),
+                _ => Some(Self::Start),
+            },
+            Self::Comment => match ch {
+                '\n' => Some(Self::Start),
+                _ => None,
+            },
             Self::Start => {
                 let next_state = match ch {
                     ')' => State::RightPar,
@@ -172,11 +319,16 @@ impl State {
                     '^' => State::Pow,
                     '!' => State::FactorialSign,
                     ',' => State::Comma,
+                    '#' => State::Comment,
+                    '√' => State::Root("sqrt"),
+                    '∛' => State::Root("cbrt"),
                     '0' => State::NumberStart,
-                    ' ' => return Ok(None),
+                    ' ' | '\n' => return Ok(None),
                     '1'..='9' => State::Number {
                         radix: 10,
-                        num: BigUint::from(ch.to_digit(10).unwrap()),
+                        num: BigUint::from(decimal_digit(ch)?),
+                        separator: Separator::Allowed,
+                        point_allowed: true,
                     },
                     '.' => State::FractionStart,
                     'a'..='z' | 'A'..='Z' | '_' => State::Identifier(ch.to_string()),
@@ -187,6 +339,12 @@ impl State {
                 Some(next_state)
             }
 
+            // The root sign has to be followed by a number or a bracket
+            Self::Root(_) => match ch {
+                '0'..='9' | '.' | '(' => Some(Self::Start),
+                _ => return Err(Error::UnsupportedToken(0)),
+            },
+
             Self::Identifier(ref mut s) => 'id: {
                 if !matches!(ch, 'a'..='z' | 'A'..='Z' | '0'..='9' | '_') {
                     break 'id Some(State::Start);
@@ -200,41 +358,92 @@ impl State {
                     radix: 10,
                     num: Default::default(),
                     fract_cnt: Default::default(),
+                    separator: Default::default(),
                 }),
                 'b' => Some(Self::Number {
                     radix: 2,
                     num: Default::default(),
+                    separator: Default::default(),
+                    point_allowed: true,
                 }),
                 'o' => Some(Self::Number {
                     radix: 8,
                     num: Default::default(),
+                    separator: Default::default(),
+                    point_allowed: true,
                 }),
                 'x' => Some(Self::Number {
                     radix: 16,
                     num: Default::default(),
+                    separator: Default::default(),
+                    point_allowed: true,
                 }),
-                '0'..='9' => Some(Self::Number {
-                    radix: 10,
-                    num: BigUint::from(ch.to_digit(10).unwrap()),
-                }),
+                // `0_1`, the zero is a digit too, unless the leading zeros aren't ignored
+                '_' => match leading_zeros {
+                    LeadingZeros::Ignore => Some(Self::Number {
+                        radix: 10,
+                        num: Default::default(),
+                        separator: Separator::Pending,
+                        point_allowed: true,
+                    }),
+                    LeadingZeros::Error | LeadingZeros::Octal => {
+                        return Err(Error::UnsupportedToken(0))
+                    }
+                },
+                '0'..='9' => match leading_zeros {
+                    LeadingZeros::Ignore => Some(Self::Number {
+                        radix: 10,
+                        num: BigUint::from(decimal_digit(ch)?),
+                        separator: Separator::Allowed,
+                        point_allowed: true,
+                    }),
+                    LeadingZeros::Error => return Err(Error::UnsupportedToken(0)),
+                    LeadingZeros::Octal => Some(Self::Number {
+                        radix: 8,
+                        num: BigUint::from(ch.to_digit(8).ok_or(Error::UnsupportedToken(0))?),
+                        separator: Separator::Allowed,
+                        point_allowed: false,
+                    }),
+                },
                 _ => Some(Self::Start),
             },
 
-            Self::Number { radix, ref mut num } => 'number: {
+            Self::Number {
+                radix,
+                ref mut num,
+                separator,
+                point_allowed,
+            } => 'number: {
                 if ch == '.' {
+                    if !*point_allowed {
+                        return Err(Error::UnsupportedToken(0));
+                    }
+
                     break 'number Some(Self::Fraction {
                         radix: *radix,
                         num: mem::take(num),
                         fract_cnt: num::zero(),
+                        separator: Separator::Forbidden,
                     });
                 }
 
+                if ch == '_' {
+                    separator.read()?;
+                    break 'number None;
+                }
+
                 let Some(val) = ch.to_digit(*radix) else {
+                    // Digit out of the radix, like `9` in the octal `0129`
+                    if ch.is_ascii_digit() {
+                        return Err(Error::UnsupportedToken(0));
+                    }
+
                     break 'number Some(Self::Start);
                 };
 
                 *num *= *radix;
                 *num += val;
+                *separator = Separator::Allowed;
 
                 None
             }
@@ -248,6 +457,7 @@ impl State {
                     radix: 10,
                     num: BigUint::from(num),
                     fract_cnt: 1,
+                    separator: Separator::Allowed,
                 })
             }
 
@@ -255,9 +465,18 @@ impl State {
                 radix,
                 ref mut num,
                 ref mut fract_cnt,
-                ..
+                separator,
             } => 'fraction: {
+                if ch == '_' {
+                    separator.read()?;
+                    break 'fraction None;
+                }
+
                 let Some(val) = ch.to_digit(*radix) else {
+                    if ch.is_ascii_digit() {
+                        return Err(Error::UnsupportedToken(0));
+                    }
+
                     break 'fraction Some(Self::Start);
                 };
 
@@ -265,6 +484,37 @@ impl State {
 
                 *num *= *radix;
                 *num += val;
+                *separator = Separator::Allowed;
+
+                None
+            }
+
+            // The sign or a digit is guaranteed by `starts_exponent`
+            Self::ExponentSign { mantissa } => {
+                let mantissa = mem::take(mantissa);
+                let (negative, exp) = match ch {
+                    '+' => (false, 0),
+                    '-' => (true, 0),
+                    _ => (false, decimal_digit(ch)? as i32),
+                };
+
+                Some(Self::Exponent {
+                    mantissa,
+                    negative,
+                    exp,
+                })
+            }
+
+            Self::Exponent { ref mut exp, .. } => 'exponent: {
+                let Some(val) = ch.to_digit(10) else {
+                    break 'exponent Some(Self::Start);
+                };
+
+                *exp = exp
+                    .checked_mul(10)
+                    .and_then(|exp| exp.checked_add(val as i32))
+                    .filter(|exp| *exp <= MAX_EXPONENT)
+                    .ok_or(Error::UnsupportedToken(0))?;
 
                 None
             }
@@ -274,6 +524,51 @@ impl State {
     }
 }
 
+/// Replace lookalikes of the supported characters (commonly found in text pasted from web pages
+/// or documents) with their ASCII counterpart, all kinds of whitespaces become `' '`
+fn normalize(ch: char) -> char {
+    match ch {
+        // Hyphen, non-breaking hyphen, figure dash, en dash, em dash, minus sign
+        '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2212}' => '-',
+        // Multiplication sign, middle dot, dot operator, asterisk operator
+        '\u{d7}' | '\u{b7}' | '\u{22c5}' | '\u{2217}' => '*',
+        // Division sign, division slash
+        '\u{f7}' | '\u{2215}' => '/',
+        // Fullwidth forms
+        '\u{ff08}' => '(',
+        '\u{ff09}' => ')',
+        '\u{ff0b}' => '+',
+        '\u{ff0c}' => ',',
+        // Zero width space and no-break space aren't covered by `char::is_whitespace`
+        '\u{200b}' | '\u{feff}' => ' ',
+        // Kept to end line comments
+        '\n' => '\n',
+        ch if ch.is_whitespace() => ' ',
+        ch => ch,
+    }
+}
+
+/// Value of the digits `num` with `fract_cnt` of them after the point
+fn fraction_value(num: BigUint, radix: u32, fract_cnt: u32) -> Number {
+    Number::new(num, BigUint::from(radix).pow(fract_cnt)).unwrap_or_default()
+}
+
+/// Whether the characters after `e` are the exponent of the scientific notation, digits
+/// optionally preceded by a sign
+fn is_exponent(mut rest: impl Iterator<Item = char>) -> bool {
+    let mut next = rest.next().map(normalize);
+    if matches!(next, Some('+' | '-')) {
+        next = rest.next().map(normalize);
+    }
+
+    next.is_some_and(|ch| ch.is_ascii_digit())
+}
+
+/// Value of a decimal digit, `ch` is expected to be already checked by the caller
+fn decimal_digit(ch: char) -> Result<u32> {
+    ch.to_digit(10).ok_or(Error::UnsupportedToken(0))
+}
+
 enum StepState {
     Token(Token),
     Inprogress,
@@ -281,11 +576,79 @@ enum StepState {
 }
 
 /// Scan tokens from string
+///
+/// Prefix root signs are rewritten to function calls, `√9` is scanned as `sqrt(9)`
+/// and `∛(27)` as `cbrt(27)`
+///
+/// Line comments start with `#` or `//` and are skipped up to the end of the line.
+/// Block comments aren't supported
+///
+/// Digits can be separated into groups by `_`, like `1_000_000` or `0xFF_FF`. The separator
+/// has to be between two digits
+///
+/// ```
+/// # use math::token::{Scanner, Token};
+/// # use math::Number;
+/// # fn main() -> math::Result<()> {
+/// let mut scanner = Scanner::new("1_000 0xFF_FF 0.000_1");
+/// assert_eq!(scanner.next_token()?, Some(Token::Number(Number::from(1000))));
+/// assert_eq!(scanner.next_token()?, Some(Token::Number(Number::from(0xFFFF))));
+/// assert_eq!(scanner.next_token()?, Some(Token::Number(Number::new(1, 10000)?)));
+///
+/// for s in ["1__2", "1_", "1_.5", "1._5", "0x_F", "1_e3"] {
+///     assert!(Scanner::new(s).next_token().is_err(), "{s}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Decimal numbers can be written in the scientific notation, `1.2e-2` is `0.012`.
+/// The `e` starts the exponent only when it's directly followed by the digits
+/// (or a sign and the digits), otherwise it's the identifier `e`
+///
+/// ```
+/// # use math::token::{Bracket, Scanner, Token};
+/// # use math::Number;
+/// # fn main() -> math::Result<()> {
+/// let mut scanner = Scanner::new("1e3 1.2e-2 2E+1");
+/// assert_eq!(scanner.next_token()?, Some(Token::Number(Number::from(1000))));
+/// assert_eq!(scanner.next_token()?, Some(Token::Number(Number::new(12, 1000)?)));
+/// assert_eq!(scanner.next_token()?, Some(Token::Number(Number::from(20))));
+/// assert_eq!(scanner.next_token()?, None);
+///
+/// let mut scanner = Scanner::new("2e() e(1)");
+/// assert_eq!(scanner.next_token()?, Some(Token::Number(Number::from(2))));
+/// assert_eq!(scanner.next_token()?, Some(Token::Id(String::from("e"))));
+/// assert_eq!(scanner.next_token()?, Some(Token::Bracket(Bracket::ParenLeft)));
+/// assert_eq!(scanner.next_token()?, Some(Token::Bracket(Bracket::ParenRight)));
+/// assert_eq!(scanner.next_token()?, Some(Token::Id(String::from("e"))));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```
+/// # use math::token::{Operator, Scanner, Token};
+/// # use math::Number;
+/// # fn main() -> math::Result<()> {
+/// let mut scanner = Scanner::new("1 # one\n// nothing here\n+ 2 // two");
+///
+/// assert_eq!(scanner.next_token()?, Some(Token::Number(Number::from(1))));
+/// assert_eq!(scanner.next_token()?, Some(Token::Operator(Operator::Plus)));
+/// assert_eq!(scanner.next_token()?, Some(Token::Number(Number::from(2))));
+/// assert_eq!(scanner.next_token()?, None);
+/// # Ok(())
+/// # }
+/// ```
 pub struct Scanner<'a> {
     iter: std::str::Chars<'a>,
     state: State,
     cnt: usize,
     buf: Option<char>,
+    /// Token to return before scanning further
+    pending: Option<Token>,
+    /// Wrap the next number in brackets, it's the argument of a root sign
+    wrap_number: bool,
+    leading_zeros: LeadingZeros,
 }
 
 impl<'a> Scanner<'a> {
@@ -296,15 +659,48 @@ impl<'a> Scanner<'a> {
             state: State::Start,
             cnt: 0,
             buf: None,
+            pending: None,
+            wrap_number: false,
+            leading_zeros: LeadingZeros::default(),
         }
     }
 
+    /// Select how integers with a leading zero are scanned, they are ignored by default
+    ///
+    /// ```
+    /// # use math::token::{LeadingZeros, Scanner, Token};
+    /// # use math::Number;
+    /// # fn main() -> math::Result<()> {
+    /// let mut scanner = Scanner::new("010").with_leading_zeros(LeadingZeros::Octal);
+    /// assert_eq!(scanner.next_token()?, Some(Token::Number(Number::from(8))));
+    ///
+    /// let mut scanner = Scanner::new("010").with_leading_zeros(LeadingZeros::Error);
+    /// assert!(scanner.next_token().is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_leading_zeros(mut self, leading_zeros: LeadingZeros) -> Self {
+        self.leading_zeros = leading_zeros;
+        self
+    }
+
     /// Scan for the next token
     pub fn next_token(&mut self) -> Result<Option<Token>> {
+        if let Some(token) = self.pending.take() {
+            return Ok(Some(token));
+        }
+
         loop {
             match self.step()? {
                 StepState::Inprogress => continue,
-                StepState::Token(token) => break Ok(Some(token)),
+                StepState::Token(token) => {
+                    if self.wrap_number && matches!(token, Token::Number(_)) {
+                        self.wrap_number = false;
+                        self.pending = Some(Token::Bracket(Bracket::ParenRight));
+                    }
+
+                    break Ok(Some(token));
+                }
                 StepState::End => break Ok(None),
             }
         }
@@ -313,22 +709,23 @@ impl<'a> Scanner<'a> {
     fn step(&mut self) -> Result<StepState> {
         self.cnt += 1;
 
-        let Some(ch) = self.buf.take().or_else(|| self.iter.next()) else {
+        let Some(ch) = self.buf.take().or_else(|| self.iter.next().map(normalize)) else {
             let state = mem::take(&mut self.state);
 
-            if state == State::Start {
+            if matches!(state, State::Start | State::Comment) {
                 return Ok(StepState::End);
             }
 
             return state
                 .into_token()
                 .ok_or(Error::UnsupportedToken(self.cnt))
-                .map(StepState::Token)
+                .map(StepState::Token);
         };
 
+        let starts_exponent = matches!(ch, 'e' | 'E') && is_exponent(self.iter.clone());
         let next_state = self
             .state
-            .next_state(ch)
+            .next_state(ch, self.leading_zeros, starts_exponent)
             .map_err(|_| Error::UnsupportedToken(self.cnt))?;
 
         let Some(mut state) = next_state else {
@@ -338,12 +735,21 @@ impl<'a> Scanner<'a> {
         mem::swap(&mut state, &mut self.state);
 
         if let State::Start = self.state {
-            if state == State::Start {
+            if matches!(state, State::Start | State::Comment) {
                 return Ok(StepState::Inprogress);
             }
 
             self.buf.replace(ch);
 
+            if let State::Root(name) = state {
+                if ch != '(' {
+                    self.wrap_number = true;
+                    self.pending = Some(Token::Bracket(Bracket::ParenLeft));
+                }
+
+                return Ok(StepState::Token(Token::Id(name.to_owned())));
+            }
+
             return state
                 .into_token()
                 .ok_or(Error::UnsupportedToken(self.cnt))
@@ -0,0 +1,102 @@
+use crate::error::Error;
+use crate::{Number, Result};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A single named unit raised to a non-zero integer exponent, like `meter^2` \
+/// Compound units (`meter/second`) are not supported
+pub struct Unit {
+    name: String,
+    exponent: i32,
+}
+
+impl Unit {
+    /// Create a new unit with exponent 1
+    ///
+    /// ```
+    /// # use math::unit::Unit;
+    /// let meter = Unit::new("meter");
+    /// assert_eq!(meter.name(), "meter");
+    /// assert_eq!(meter.exponent(), 1);
+    /// ```
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            exponent: 1,
+        }
+    }
+
+    /// Name of the unit
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Exponent of the unit, never zero
+    pub fn exponent(&self) -> i32 {
+        self.exponent
+    }
+
+    fn with_exponent(&self, exponent: i32) -> Option<Self> {
+        (exponent != 0).then(|| Self {
+            name: self.name.clone(),
+            exponent,
+        })
+    }
+}
+
+/// ```
+/// # use math::unit::Unit;
+/// assert_eq!(Unit::new("meter").to_string(), "meter");
+/// ```
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.exponent {
+            1 => write!(f, "{}", self.name),
+            exp => write!(f, "{}^{exp}", self.name),
+        }
+    }
+}
+
+/// Unit of a sum (difference, modulo), both operands have to be in the same unit
+pub(crate) fn add(lhs: Option<Unit>, rhs: Option<Unit>) -> Result<Option<Unit>> {
+    if lhs != rhs {
+        return Err(Error::UnitMismatch);
+    }
+
+    Ok(lhs)
+}
+
+/// Unit of a product, exponents of the same unit are added together
+pub(crate) fn mul(lhs: Option<Unit>, rhs: Option<Unit>) -> Result<Option<Unit>> {
+    match (lhs, rhs) {
+        (None, unit) | (unit, None) => Ok(unit),
+        (Some(lhs), Some(rhs)) if lhs.name == rhs.name => {
+            Ok(lhs.with_exponent(lhs.exponent + rhs.exponent))
+        }
+        _ => Err(Error::UnitMismatch),
+    }
+}
+
+/// Unit of a quotient
+pub(crate) fn div(lhs: Option<Unit>, rhs: Option<Unit>) -> Result<Option<Unit>> {
+    let rhs = rhs.and_then(|unit| unit.with_exponent(-unit.exponent));
+    mul(lhs, rhs)
+}
+
+/// Unit of a power, the exponent has to be a dimensionless integer when the base has a unit
+pub(crate) fn pow(lhs: Option<Unit>, exp: &Number, exp_unit: Option<Unit>) -> Result<Option<Unit>> {
+    if exp_unit.is_some() {
+        return Err(Error::UnitMismatch);
+    }
+
+    let Some(lhs) = lhs else {
+        return Ok(None);
+    };
+
+    let exponent = exp
+        .to_i32()
+        .and_then(|exp| lhs.exponent.checked_mul(exp))
+        .ok_or(Error::UnitMismatch)?;
+
+    Ok(lhs.with_exponent(exponent))
+}
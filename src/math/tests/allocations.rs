@@ -0,0 +1,50 @@
+use math::Calculator;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Allocator counting the number of allocations
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+#[test]
+fn evaluate_no_ans_allocates_less() {
+    const EXPR: &str = "1 + 2 * 3";
+    let mut calculator = Calculator::new();
+
+    // Warm up the reserved buffers and the `ans` entry
+    calculator.evaluate(EXPR).unwrap();
+    calculator.evaluate_no_ans(EXPR).unwrap();
+
+    let with_ans = count_allocations(|| {
+        calculator.evaluate(EXPR).unwrap();
+    });
+    let without_ans = count_allocations(|| {
+        calculator.evaluate_no_ans(EXPR).unwrap();
+    });
+
+    assert!(
+        without_ans < with_ans,
+        "evaluate: {with_ans} allocations, evaluate_no_ans: {without_ans} allocations"
+    );
+}
@@ -1,5 +1,8 @@
+use math::engine::ShuntingYardEngine;
+use math::error::Error;
 use math::evaluate;
 use math::number::Radix;
+use math::{AngleMode, Calculator, Engine, Number, Warning};
 
 fn eval_dec(s: &str, precision: u8) -> math::Result<String> {
     Ok(evaluate(s)?.to_string(Radix::Dec, precision))
@@ -81,6 +84,15 @@ fn evaluate_mod() -> math::Result<()> {
     Ok(())
 }
 #[test]
+fn evaluate_fractional_mod() -> math::Result<()> {
+    assert_eq!(eval_dec("5.5 mod 2", 3)?, "1.5");
+    assert_eq!(eval_dec("-5.5 mod 2", 3)?, "0.5");
+    assert_eq!(eval_dec("5.5 mod 2.5", 3)?, "0.5");
+    assert_eq!(eval_dec("5.5 mod -2", 3)?, "-0.5");
+    assert_eq!(eval_dec("(1/3) mod (1/4)", 5)?, "0.08333");
+    Ok(())
+}
+#[test]
 fn evaluate_root() -> math::Result<()> {
     assert_eq!(eval_dec("root(2, 64)", 0)?, "8");
     assert_eq!(eval_dec("root(3, -64)", 0)?, "-4");
@@ -117,6 +129,16 @@ fn evaluate_log() -> math::Result<()> {
     Ok(())
 }
 
+#[test]
+fn evaluate_log_out_of_f64_range() -> math::Result<()> {
+    assert_eq!(eval_dec("log(10, 10^400)", 6)?, "400");
+    assert_eq!(eval_dec("log(10, 3 * 10^400)", 6)?, "400.477121");
+    assert_eq!(eval_dec("log(10, 1 / 10^400)", 6)?, "-400");
+    assert_eq!(eval_dec("log(10^400, 10)", 6)?, "0.0025");
+    assert_eq!(eval_dec("ln(2^2000)", 3)?, "1386.294");
+    Ok(())
+}
+
 #[test]
 fn evaluate_sin() -> math::Result<()> {
     assert_eq!(eval_dec("sin(123)", 7)?, "-0.4599035");
@@ -173,6 +195,20 @@ fn evaluate_cotg() -> math::Result<()> {
     Ok(())
 }
 
+#[test]
+fn evaluate_sin_large() -> math::Result<()> {
+    let precision = Number::guarantee_precision();
+    for (expr, expected) in [
+        ("sin(1000000)", "-0.349993502171292"),
+        ("cos(1000000)", "0.936752127533145"),
+        ("sin(-1000000)", "0.349993502171292"),
+        ("sin(10^15)", "0.858272793170236"),
+    ] {
+        let diff = evaluate(expr)?.sub(expected.parse::<Number>()?)?;
+        assert!(diff.abs()? < precision, "{expr}");
+    }
+    Ok(())
+}
 #[test]
 fn evaluate_arcsin() -> math::Result<()> {
     assert_eq!(eval_dec("arcsin(0.3912)", 8)?, "0.40193515");
@@ -209,6 +245,111 @@ fn evaluate_arccotg() -> math::Result<()> {
     assert_eq!(eval_dec("arccotg(-99999999)", 5)?, eval_dec("pi()", 5)?);
     Ok(())
 }
+#[test]
+fn evaluate_sec_csc() -> math::Result<()> {
+    assert_eq!(eval_dec("sec(0)", 5)?, "1");
+    assert_eq!(eval_dec("csc(pi() / 2)", 5)?, "1");
+    assert_eq!(eval_dec("sec(1)", 5)?, eval_dec("1 / cos(1)", 5)?);
+    assert_eq!(eval_dec("csc(1)", 5)?, eval_dec("1 / sin(1)", 5)?);
+    assert_eq!(evaluate("sec(pi() / 2)"), Err(Error::DivisionZero));
+    assert_eq!(evaluate("csc(0)"), Err(Error::DivisionZero));
+
+    assert_eq!(eval_dec("arcsec(sec(0.123))", 3)?, "0.123");
+    assert_eq!(eval_dec("arccsc(csc(0.123))", 3)?, "0.123");
+    assert_eq!(eval_dec("arcsec(-1)", 5)?, eval_dec("pi()", 5)?);
+    assert_eq!(evaluate("arcsec(0.5)"), Err(Error::OutOfRange));
+    assert_eq!(evaluate("arccsc(-0.5)"), Err(Error::OutOfRange));
+    Ok(())
+}
+
+#[test]
+fn evaluate_degrees() -> math::Result<()> {
+    let mut calculator = Calculator::new();
+    calculator.set_angle_mode(AngleMode::Degrees);
+    let mut eval =
+        |expr| -> math::Result<String> { Ok(calculator.evaluate(expr)?.to_string(Radix::Dec, 5)) };
+
+    assert_eq!(eval("sin(90)")?, "1");
+    assert_eq!(eval("sin(30)")?, "0.5");
+    assert_eq!(eval("cos(60)")?, "0.5");
+    assert_eq!(eval("tg(45)")?, "1");
+    assert_eq!(eval("cotg(45)")?, "1");
+    assert_eq!(eval("arcsin(sin(30))")?, "30");
+    assert_eq!(eval("arccos(0)")?, "90");
+    assert_eq!(eval("arctg(1)")?, "45");
+    assert_eq!(eval("arccotg(1)")?, "45");
+    assert_eq!(eval("atan2(-1, -1)")?, "-135");
+    assert_eq!(eval("sec(60)")?, "2");
+    assert_eq!(eval("csc(30)")?, "2");
+    assert_eq!(eval("arcsec(2)")?, "60");
+    assert_eq!(eval("arccsc(1)")?, "90");
+
+    calculator.set_angle_mode(AngleMode::Radians);
+    assert_eq!(calculator.angle_mode(), AngleMode::Radians);
+    assert_eq!(calculator.evaluate("sin(pi() / 2)")?, Number::one());
+    assert_eq!(calculator.evaluate("atan2(0, -1)")?, Number::pi());
+    Ok(())
+}
+
+#[test]
+fn evaluate_gamma() -> math::Result<()> {
+    assert_eq!(eval_dec("gamma(6)", 6)?, "120");
+    assert_eq!(eval_dec("gamma(-0.5)", 6)?, eval_dec("-2 * sqrt(pi())", 6)?);
+    assert_eq!(eval_dec("gamma(-2.5)", 6)?, "-0.945309");
+    assert_eq!(evaluate("gamma(-1)"), Err(Error::FactorialNegative));
+    assert_eq!(evaluate("gamma(0)"), Err(Error::FactorialNegative));
+    // Factorial of negative number stays undefined
+    assert_eq!(evaluate("(-0.5)!"), Err(Error::FactorialNegative));
+    Ok(())
+}
+
+#[test]
+fn evaluate_exp() -> math::Result<()> {
+    assert_eq!(evaluate("exp(0)")?, Number::one());
+    assert_eq!(
+        eval_dec("exp(1)", 10)?,
+        Number::e_precise(10).to_string(Radix::Dec, 10)
+    );
+    assert_eq!(eval_dec("exp(10)", 6)?, "22026.465795");
+    assert_eq!(eval_dec("exp(-10)", 10)?, "0.0000453999");
+    assert_eq!(eval_dec("exp(2) * exp(3)", 6)?, eval_dec("exp(5)", 6)?);
+
+    let precision = Number::guarantee_precision();
+    for x in ["0.5", "-3.25", "7", "1/3"] {
+        let res = evaluate(&format!("ln(exp({x}))"))?;
+        assert!(res.sub(evaluate(x)?)?.abs()? < precision, "{x}");
+    }
+    Ok(())
+}
+
+#[test]
+fn evaluate_hyperbolic() -> math::Result<()> {
+    assert_eq!(evaluate("cosh(0)")?, Number::one());
+    assert_eq!(evaluate("sinh(0)")?, Number::zero());
+    assert_eq!(eval_dec("sinh(2)", 5)?, "3.62686");
+    assert_eq!(eval_dec("cosh(2)", 5)?, "3.7622");
+    assert_eq!(eval_dec("tanh(2)", 5)?, "0.96403");
+    assert_eq!(eval_dec("coth(2)", 5)?, "1.03731");
+    assert_eq!(eval_dec("tanh(30)", 10)?, "1");
+    assert_eq!(eval_dec("tanh(-30)", 10)?, "-1");
+    assert_eq!(evaluate("tanh(100000)")?, Number::one());
+
+    assert_eq!(eval_dec("sinh(1)", 10)?, "1.1752011936");
+    assert_eq!(eval_dec("cosh(1)", 10)?, "1.5430806348");
+    assert_eq!(eval_dec("tanh(1)", 10)?, "0.761594156");
+    assert_eq!(eval_dec("sinh(10)", 10)?, "11013.2328747034");
+    assert_eq!(eval_dec("cosh(-10)", 10)?, "11013.2329201033");
+    assert_eq!(eval_dec("tanh(0.1)", 10)?, "0.0996679946");
+
+    assert_eq!(eval_dec("arsinh(sinh(3))", 5)?, "3");
+    assert_eq!(eval_dec("arcosh(cosh(3))", 5)?, "3");
+    assert_eq!(eval_dec("artanh(tanh(-0.5))", 5)?, "-0.5");
+
+    assert_eq!(evaluate("arcosh(0.5)"), Err(Error::OutOfRange));
+    assert_eq!(evaluate("artanh(-1)"), Err(Error::OutOfRange));
+    assert_eq!(evaluate("coth(0)"), Err(Error::DivisionZero));
+    Ok(())
+}
 
 #[test]
 fn evaluate_abs() -> math::Result<()> {
@@ -220,10 +361,65 @@ fn evaluate_abs() -> math::Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn evaluate_negated_functions() -> math::Result<()> {
+    assert_eq!(evaluate("-sqrt(4)")?, Number::from(-2));
+    assert_eq!(evaluate("3 - sqrt(4)")?, Number::one());
+    assert_eq!(evaluate("-(-sqrt(4))")?, Number::from(2));
+    assert_eq!(evaluate("2 * -sqrt(4)")?, Number::from(-4));
+    assert_eq!(evaluate("2 * (-sqrt(4))")?, Number::from(-4));
+    assert_eq!(evaluate("2 - -sqrt(4)")?, Number::from(4));
+    assert_eq!(evaluate("-sqrt(4) * 3 + 1")?, Number::from(-5));
+    assert_eq!(evaluate("root(2, -sqrt(4) + 6)")?, Number::from(2));
+    assert_eq!(evaluate("2^-(1)")?, Number::new(1, 2)?);
+    assert_eq!(evaluate("2 * -|-3|")?, Number::from(-6));
+
+    // power binds tighter than the unary minus, factorial even more
+    assert_eq!(evaluate("-sqrt(4)^2")?, Number::from(-4));
+    assert_eq!(evaluate("2 * -(2)^2")?, Number::from(-8));
+    assert_eq!(evaluate("-sqrt(9)!")?, Number::from(-6));
+    Ok(())
+}
+
+#[test]
+fn evaluate_abs_bars() -> math::Result<()> {
+    assert_eq!(evaluate("|-5|")?, Number::from(5));
+    assert_eq!(evaluate("|3 - 7| + 1")?, Number::from(5));
+    assert_eq!(evaluate("-|-5|")?, Number::from(-5));
+    assert_eq!(evaluate("2 * |-3|")?, Number::from(6));
+    assert_eq!(evaluate("|2 - 5| * |1 - 3|")?, Number::from(6));
+    assert_eq!(evaluate("|sqrt(4) - 3|")?, Number::one());
+    assert_eq!(evaluate("||-2| - 5|")?, Number::from(3));
+    assert_eq!(evaluate("| 1 - |2 - 6| |")?, Number::from(3));
+    assert_eq!(evaluate("(|-2|)")?, Number::from(2));
+    assert_eq!(evaluate("root(2, |-4|)")?, Number::from(2));
+    assert_eq!(evaluate("|-3|!")?, Number::from(6));
+
+    // implicit multiplication
+    assert_eq!(evaluate("2|-3|")?, Number::from(6));
+    assert_eq!(evaluate("|-2||-3|")?, Number::from(6));
+    assert_eq!(evaluate("|-2|3")?, Number::from(6));
+
+    // unbalanced bars
+    assert_eq!(evaluate("|3"), Err(Error::InvalidToken));
+    assert_eq!(evaluate("3|"), Err(Error::InvalidToken));
+    assert_eq!(evaluate("|1| - |2"), Err(Error::InvalidToken));
+    assert!(evaluate("||").is_err());
+
+    // bars crossing the brackets
+    assert_eq!(evaluate("(|2)|"), Err(Error::InvalidToken));
+    assert_eq!(evaluate("|(2|)"), Err(Error::InvalidToken));
+    assert_eq!(evaluate("|1 + (2|-3|)|")?, Number::from(7));
+    Ok(())
+}
+
 #[test]
 fn evaluate_comb() -> math::Result<()> {
     assert!(eval_dec("comb(-1, 123)", 0).is_err());
     assert!(eval_dec("comb(123, -1)", 0).is_err());
+    assert!(eval_dec("comb(0, -1)", 0).is_err());
+    assert!(eval_dec("comb(-1, -1)", 0).is_err());
     assert_eq!(eval_dec("comb(3, 4)", 0)?, "0");
     assert_eq!(eval_dec("comb(3, 3)", 0)?, "1");
     assert_eq!(eval_dec("comb(4, 2)", 0)?, "6");
@@ -234,6 +430,67 @@ fn evaluate_comb() -> math::Result<()> {
     );
     Ok(())
 }
+#[test]
+fn evaluate_comb_real() -> math::Result<()> {
+    // Integer arguments follow the exact path, close non-integers approach the same value
+    assert_eq!(eval_dec("comb(10, 3)", 0)?, "120");
+    assert_eq!(eval_dec("comb(10.000001, 3)", 3)?, "120");
+    assert_eq!(eval_dec("comb(4.5, 2)", 6)?, "7.875");
+    assert_eq!(eval_dec("comb(-0.5, 2)", 6)?, "0.375");
+    assert_eq!(
+        eval_dec("comb(5, 2.5)", 5)?,
+        eval_dec("5! / (2.5! * 2.5!)", 5)?
+    );
+    assert!(eval_dec("comb(-2, 0.5)", 0).is_err());
+    Ok(())
+}
+#[test]
+fn evaluate_rounding() -> math::Result<()> {
+    assert_eq!(eval_dec("floor(-0.5)", 0)?, "-1");
+    assert_eq!(eval_dec("ceil(-0.5)", 0)?, "0");
+    assert_eq!(eval_dec("round(2.5)", 0)?, "3");
+    assert_eq!(eval_dec("round(-2.5)", 0)?, "-3");
+    assert_eq!(eval_dec("trunc(-2.9)", 0)?, "-2");
+    assert_eq!(eval_dec("floor(7/2) + ceil(7/2)", 0)?, "7");
+    Ok(())
+}
+#[test]
+fn evaluate_gcd_lcm() -> math::Result<()> {
+    assert_eq!(eval_dec("gcd(12, 18)", 0)?, "6");
+    assert_eq!(eval_dec("lcm(4, 6)", 0)?, "12");
+    assert_eq!(eval_dec("gcd(0, 5)", 0)?, "5");
+    assert_eq!(eval_dec("gcd(-12, 18)", 0)?, "6");
+    assert_eq!(eval_dec("lcm(0, 5)", 0)?, "0");
+    assert_eq!(
+        evaluate("gcd(1.5, 3)"),
+        Err(Error::Message(String::from(
+            "Arguments of gcd must be integers"
+        )))
+    );
+    assert!(evaluate("lcm(4, 2.5)").is_err());
+    Ok(())
+}
+#[test]
+fn evaluate_perm() -> math::Result<()> {
+    assert_eq!(evaluate("perm(-1, 2)"), Err(Error::FactorialNegative));
+    assert_eq!(evaluate("perm(4, -1)"), Err(Error::FactorialNegative));
+    assert_eq!(eval_dec("perm(3, 5)", 0)?, "0");
+    assert_eq!(eval_dec("perm(6, 0)", 0)?, "1");
+    assert_eq!(eval_dec("perm(6, 6)", 0)?, eval_dec("6!", 0)?);
+    assert_eq!(eval_dec("perm(10, 3)", 0)?, "720");
+    assert_eq!(eval_dec("perm(4.5, 2)", 6)?, "15.75");
+    Ok(())
+}
+
+#[test]
+fn evaluate_digitsum() -> math::Result<()> {
+    assert_eq!(eval_dec("digitsum(123)", 0)?, "6");
+    assert_eq!(eval_dec("digitsum(0)", 0)?, "0");
+    assert_eq!(eval_dec("digitsum(99 * 11)", 0)?, "18");
+    assert!(eval_dec("digitsum(1.5)", 0).is_err());
+    assert!(eval_dec("digitsum(-5)", 0).is_err());
+    Ok(())
+}
 
 #[test]
 fn evaluate_expr() -> math::Result<()> {
@@ -265,5 +522,677 @@ fn evaluate_expr_hidden_multiply_sign() -> math::Result<()> {
 fn evaluate_constants() -> math::Result<()> {
     assert_eq!(eval_dec("pi()", 6)?, "3.141593");
     assert_eq!(eval_dec("e()", 6)?, "2.718282");
+    assert_eq!(eval_dec("phi()", 6)?, "1.618034");
+    assert_eq!(eval_dec("phi() - 1", 6)?, eval_dec("1/phi()", 6)?);
+    assert_eq!(eval_dec("phi()^2 - phi()", 6)?, "1");
+    Ok(())
+}
+
+#[test]
+fn evaluate_empty() {
+    assert_eq!(evaluate(""), Err(Error::EmptyExpression));
+    assert_eq!(evaluate("   "), Err(Error::EmptyExpression));
+    assert_eq!(evaluate("1 +"), Err(Error::MissingOperand));
+}
+
+#[test]
+fn evaluate_non_finite() -> math::Result<()> {
+    // Float overflow is still reported as 0, `try_to_string` flags it
+    assert_eq!(eval_dec("200.5!", 6)?, "0");
+    assert_eq!(evaluate("200.5!")?.try_to_string(Radix::Dec, 6), None);
+    assert_eq!(
+        evaluate("0")?.try_to_string(Radix::Dec, 6),
+        Some(String::from("0"))
+    );
+    assert_eq!(
+        evaluate("0.5!")?.try_to_string(Radix::Dec, 3),
+        Some(String::from("0.886"))
+    );
+    Ok(())
+}
+
+#[test]
+fn evaluate_units() -> math::Result<()> {
+    let mut calculator = Calculator::new();
+    calculator.add_constant_with_unit("meter", 1, "meter");
+    let unit = |calculator: &Calculator| calculator.result_unit().map(ToString::to_string);
+
+    assert_eq!(calculator.evaluate("3meter * 2")?, Number::from(6));
+    assert_eq!(unit(&calculator), Some(String::from("meter")));
+
+    assert_eq!(calculator.evaluate("2meter * 3meter")?, Number::from(6));
+    assert_eq!(unit(&calculator), Some(String::from("meter^2")));
+
+    assert_eq!(calculator.evaluate("1 / meter")?, Number::from(1));
+    assert_eq!(unit(&calculator), Some(String::from("meter^-1")));
+
+    assert_eq!(calculator.evaluate("6meter / (2meter)")?, Number::from(3));
+    assert_eq!(unit(&calculator), None);
+
+    assert_eq!(calculator.evaluate("3meter + 2meter")?, Number::from(5));
+    assert_eq!(unit(&calculator), Some(String::from("meter")));
+
+    assert_eq!(calculator.evaluate("-meter")?, Number::from(-1));
+    assert_eq!(unit(&calculator), Some(String::from("meter")));
+
+    assert_eq!(calculator.evaluate("3 * meter()")?, Number::from(3));
+    assert_eq!(unit(&calculator), Some(String::from("meter")));
+
+    assert_eq!(calculator.evaluate("3meter + 2"), Err(Error::UnitMismatch));
+    assert_eq!(
+        calculator.evaluate("sqrt(4meter)"),
+        Err(Error::UnitMismatch)
+    );
+    assert_eq!(calculator.evaluate("1 + 2")?, Number::from(3));
+    assert_eq!(unit(&calculator), None);
+    Ok(())
+}
+
+#[test]
+fn evaluate_exactness() -> math::Result<()> {
+    let mut calculator = Calculator::new();
+
+    calculator.evaluate("2 + 2")?;
+    assert!(calculator.last_result_exact());
+
+    calculator.evaluate("sin(1)")?;
+    assert!(!calculator.last_result_exact());
+
+    calculator.evaluate("2^10 / 3 - 5!")?;
+    assert!(calculator.last_result_exact());
+
+    calculator.evaluate("2^0.5")?;
+    assert!(!calculator.last_result_exact());
+
+    calculator.evaluate("0.5!")?;
+    assert!(!calculator.last_result_exact());
+
+    // Failed evaluation keeps the previous state
+    assert!(calculator.evaluate("1 / 0").is_err());
+    assert!(!calculator.last_result_exact());
+    Ok(())
+}
+
+#[test]
+fn evaluate_without_ans() -> math::Result<()> {
+    let mut calculator = Calculator::new();
+
+    assert_eq!(calculator.evaluate_no_ans("2 * 3")?, Number::from(6));
+    assert_eq!(calculator.get_constant("ans"), None);
+    assert!(calculator.evaluate("ans()").is_err());
+
+    calculator.evaluate("4")?;
+    calculator.evaluate_no_ans("5")?;
+    assert_eq!(calculator.evaluate("ans()")?, Number::from(4));
+    Ok(())
+}
+
+#[test]
+fn evaluate_lookalikes() -> math::Result<()> {
+    assert_eq!(eval_dec("1\u{a0}+\u{a0}2", 3)?, eval_dec("1 + 2", 3)?);
+    assert_eq!(eval_dec("5 \u{2012} 3", 3)?, eval_dec("5 - 3", 3)?);
+    assert_eq!(eval_dec("5 \u{2212} 3", 3)?, eval_dec("5 - 3", 3)?);
+    assert_eq!(eval_dec("2 \u{d7} 3", 3)?, eval_dec("2 * 3", 3)?);
+    assert_eq!(eval_dec("6 \u{f7} 4", 3)?, eval_dec("6 / 4", 3)?);
+    assert_eq!(
+        eval_dec("\tsqrt(4)\n+\u{2009}1\u{200b}", 3)?,
+        eval_dec("sqrt(4) + 1", 3)?
+    );
+    assert_eq!(
+        eval_dec("root\u{ff08}8\u{ff0c} 3\u{ff09}", 3)?,
+        eval_dec("root(8, 3)", 3)?
+    );
+    Ok(())
+}
+
+#[test]
+fn evaluate_if() -> math::Result<()> {
+    assert_eq!(eval_dec("if(1, 10, 20)", 3)?, "10");
+    assert_eq!(eval_dec("if(0, 10, 20)", 3)?, "20");
+    assert_eq!(eval_dec("if(-0.5, 10, 20)", 3)?, "10");
+    assert_eq!(eval_dec("if(2 - 2, 1 + 1, 3 * 3) + 1", 3)?, "10");
+    Ok(())
+}
+
+#[test]
+fn evaluate_lazy_if() -> math::Result<()> {
+    assert_eq!(eval_dec("if(0, 1/0, 5)", 3)?, "5");
+    assert_eq!(eval_dec("if(1, 5, 1/0)", 3)?, "5");
+    assert_eq!(eval_dec("if(1, if(0, 1/0, 2), ln(0))", 3)?, "2");
+    assert_eq!(eval_dec("2if(1, 3, 0)(2) + 1", 3)?, "13");
+    assert_eq!(eval_dec("-if(1, (1 + 2) * 3, 0)", 3)?, "-9");
+    assert_eq!(evaluate("if(1, 1/0, 5)"), Err(Error::DivisionZero));
+    assert!(evaluate("if(1, 2)").is_err());
+    Ok(())
+}
+
+#[test]
+fn evaluate_lazy_if_settings() -> math::Result<()> {
+    let mut calculator = Calculator::new();
+    calculator.add_constant_with_unit("meter", 1, "meter");
+
+    assert_eq!(calculator.evaluate("if(1, 2meter, 3)")?, Number::from(2));
+    assert_eq!(calculator.result_unit().unwrap().to_string(), "meter");
+
+    assert_eq!(calculator.evaluate("if(0, 1/0, 2 * 3)")?, Number::from(6));
+    assert_eq!(calculator.last_op_count(), Some(2));
+    assert_eq!(
+        calculator.explain("if(1, 2 * 3, 0) + 1"),
+        "First, 2 * 3 = 6. Then 6 + 1 = 7."
+    );
+
+    calculator.set_angle_mode(AngleMode::Degrees);
+    assert_eq!(calculator.evaluate("if(1, sin(90), 0)")?, Number::one());
+    Ok(())
+}
+
+#[test]
+fn explain_steps() {
+    let mut calculator = Calculator::new();
+
+    let explanation = calculator.explain("1 + 2 * 3");
+    let mul = explanation.find("2 * 3 = 6").unwrap();
+    let add = explanation.find("1 + 6 = 7").unwrap();
+    assert!(mul < add, "{explanation}");
+
+    assert_eq!(
+        calculator.explain("(1 + 1) / 4"),
+        "First, 1 + 1 = 2. Then 2 / 4 = 0.5."
+    );
+    assert_eq!(
+        calculator.explain("2 * 1/0"),
+        "First, 2 * 1 = 2. Evaluation failed: Division zero."
+    );
+
+    // The result of `explain` isn't stored
+    assert!(calculator.evaluate("ans()").is_err());
+    assert!(!calculator.engine().tracing());
+
+    // Tracing enabled before stays enabled
+    let mut engine = ShuntingYardEngine::default();
+    engine.set_tracing(true);
+    calculator.set_engine(engine);
+    calculator.explain("1 + 1");
+    assert!(calculator.engine().tracing());
+}
+
+#[test]
+fn evaluate_root_sign() -> math::Result<()> {
+    assert_eq!(eval_dec("√9", 0)?, "3");
+    assert_eq!(eval_dec("√(16)", 0)?, "4");
+    assert_eq!(eval_dec("√(3 * 12) + 1", 0)?, "7");
+    assert_eq!(eval_dec("2√9", 0)?, "6");
+    assert_eq!(eval_dec("√9√16", 0)?, "12");
+    assert_eq!(eval_dec("√6.25", 1)?, "2.5");
+    assert_eq!(eval_dec("∛27", 0)?, "3");
+    assert_eq!(eval_dec("∛(-8)", 0)?, "-2");
+    assert_eq!(eval_dec("cbrt(64)", 0)?, "4");
+
+    assert!(eval_dec("√", 0).is_err());
+    assert!(eval_dec("√x", 0).is_err());
+    assert!(eval_dec("√-4", 0).is_err());
+    Ok(())
+}
+
+#[test]
+fn evaluate_exact_rational_power() -> math::Result<()> {
+    let mut calculator = Calculator::new();
+    let exact = [
+        ("8^(2/3)", Number::from(4)),
+        ("(27/8)^(2/3)", Number::new(9, 4)?),
+        ("(-8)^(1/3)", Number::from(-2)),
+        ("16^(-3/4)", Number::new(1, 8)?),
+        ("(1/32)^(3/5)", Number::new(1, 8)?),
+        ("root(3, 1000)", Number::from(10)),
+        ("sqrt(49/16)", Number::new(7, 4)?),
+    ];
+
+    for (expr, expected) in exact {
+        assert_eq!(calculator.evaluate(expr)?, expected, "{expr}");
+        assert!(calculator.last_result_exact(), "{expr}");
+    }
+
+    let inexact = [("2^(1/2)", "1.414214"), ("10^(2/3)", "4.641589")];
+    for (expr, expected) in inexact {
+        let res = calculator.evaluate(expr)?;
+        assert_eq!(res.to_string(Radix::Dec, 6), expected, "{expr}");
+        assert!(!calculator.last_result_exact(), "{expr}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fraction_lowest_terms() -> math::Result<()> {
+    let cases = [
+        ("6/8", "3/4"),
+        ("-12/18", "-2/3"),
+        ("10/5", "2"),
+        ("0.5 + 1/3", "5/6"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(evaluate(expr)?.to_fraction_string(Radix::Dec), expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn evaluate_implicit_ans() {
+    let mut calculator = Calculator::new();
+    calculator.set_implicit_ans(true);
+
+    // nothing to continue from, `-` is a sign
+    assert_eq!(calculator.evaluate("-3"), Ok(Number::from(-3)));
+
+    assert_eq!(calculator.evaluate("10"), Ok(Number::from(10)));
+    assert_eq!(calculator.evaluate("* 2"), Ok(Number::from(20)));
+    assert_eq!(calculator.evaluate("- 5"), Ok(Number::from(15)));
+    assert_eq!(calculator.evaluate("+ -5"), Ok(Number::from(10)));
+    assert_eq!(calculator.evaluate("^ 2 / 4"), Ok(Number::from(25)));
+    assert_eq!(calculator.evaluate("mod 7"), Ok(Number::from(4)));
+
+    calculator.set_implicit_ans(false);
+    assert_eq!(calculator.evaluate("-5"), Ok(Number::from(-5)));
+    assert!(calculator.evaluate("* 2").is_err());
+}
+
+#[test]
+fn evaluate_snap_to_integer() {
+    let mut calculator = Calculator::new();
+    let approx = calculator.evaluate("log(2, 8)").unwrap();
+    assert!(approx.is_close_to_integer(&Number::guarantee_precision()));
+
+    calculator.set_snap_to_integer(true);
+    assert_eq!(calculator.evaluate("log(2, 8)"), Ok(Number::from(3)));
+    assert_eq!(
+        calculator.evaluate("log(10, 1000) - 5"),
+        Ok(Number::from(-2))
+    );
+    assert_eq!(calculator.evaluate("1/3"), Number::new(1, 3));
+
+    calculator.set_snap_to_integer(false);
+    assert_eq!(calculator.evaluate("log(2, 8)"), Ok(approx));
+}
+
+#[test]
+fn evaluate_digit_separators() -> math::Result<()> {
+    assert_eq!(evaluate("1_000 + 1")?, Number::from(1001));
+    assert_eq!(evaluate("0xFF_FF")?, Number::from(0xFFFF));
+    assert_eq!(evaluate("0b1010_1010")?, Number::from(0b1010_1010));
+    assert_eq!(evaluate("1_000.000_5")?, Number::new(10_000_005, 10_000)?);
+    assert!(evaluate("1__2").is_err());
+    assert!(evaluate("1_ + 1").is_err());
+    // Identifier, not a number
+    assert!(evaluate("_1").is_err());
+    Ok(())
+}
+
+#[test]
+fn evaluate_scientific_notation() -> math::Result<()> {
+    assert_eq!(evaluate("1.5e3 + 2E-4")?, Number::new(15_000_002, 10_000)?);
+    assert_eq!(evaluate("-1e-3")?, Number::new(-1, 1000)?);
+    assert_eq!(evaluate("3e2!")?, evaluate("300!")?);
+    // `e` without the exponent is the constant
+    assert_eq!(evaluate("2e()")?, evaluate("2 * e()")?);
+    assert_eq!(evaluate("0x1e2")?, Number::from(0x1e2));
+    assert!(evaluate("1e").is_err());
+    Ok(())
+}
+
+#[test]
+fn evaluate_overflow() {
+    let mut calculator = Calculator::new();
+    assert_eq!(calculator.evaluate("2^1000000"), Err(Error::Overflow));
+    assert_eq!(
+        calculator.evaluate("-(2^1000000) / 3"),
+        Err(Error::Overflow)
+    );
+    assert_eq!(calculator.evaluate("1 / 2^1000000"), Err(Error::Overflow));
+    assert_eq!(calculator.evaluate("2^-1000000"), Err(Error::Overflow));
+    assert!(calculator.evaluate("2^100").is_ok());
+    assert!(calculator.evaluate("2^-100").is_ok());
+    // Only the result is bounded
+    assert_eq!(
+        calculator.evaluate("2^1000000 / 2^999999"),
+        Ok(Number::from(2))
+    );
+
+    calculator.set_max_result_bits(2_000_000);
+    assert!(calculator.evaluate("2^1000000").is_ok());
+    assert_eq!(evaluate("2^1000000"), Err(Error::Overflow));
+}
+
+#[test]
+fn evaluate_safe_catches_panic() {
+    let mut calculator = Calculator::new();
+    calculator.add_function("boom", 1, |_| panic!("boom"));
+
+    assert_eq!(
+        calculator.evaluate_safe("boom(1)"),
+        Err(Error::Message(String::from("Evaluation panicked: boom")))
+    );
+
+    // the calculator is still usable
+    assert_eq!(calculator.evaluate_safe("2 * 3"), Ok(Number::from(6)));
+    assert_eq!(calculator.evaluate_safe("!"), Err(Error::MissingOperand));
+}
+
+#[test]
+fn evaluate_sigmoid_logit() -> math::Result<()> {
+    assert_eq!(evaluate("sigmoid(0)")?, Number::new(1, 2)?);
+    assert_eq!(eval_dec("sigmoid(1)", 6)?, "0.731059");
+    assert_eq!(eval_dec("sigmoid(1)", 10)?, "0.7310585786");
+    assert_eq!(eval_dec("sigmoid(-5)", 10)?, "0.0066928509");
+    assert_eq!(evaluate("logit(0.5)")?, Number::zero());
+    assert_eq!(eval_dec("logit(sigmoid(3))", 6)?, "3");
+
+    assert_eq!(evaluate("logit(0)"), Err(Error::OutOfRange));
+    assert_eq!(evaluate("logit(1)"), Err(Error::OutOfRange));
+    assert_eq!(evaluate("logit(-2)"), Err(Error::OutOfRange));
+    Ok(())
+}
+
+#[test]
+fn percent_display() -> math::Result<()> {
+    assert_eq!(evaluate("0.25")?.to_percent_string(2), "25%");
+    assert_eq!(evaluate("1.5")?.to_percent_string(2), "150%");
+    assert_eq!(evaluate("-0.1")?.to_percent_string(2), "-10%");
+    assert_eq!(evaluate("0")?.to_percent_string(2), "0%");
+    assert_eq!(evaluate("12")?.to_percent_string(2), "1200%");
+    assert_eq!(evaluate("1/8")?.to_percent_string(1), "12.5%");
+    assert_eq!(evaluate("2/3")?.to_percent_string(3), "66.667%");
+    Ok(())
+}
+
+#[test]
+fn padded_display() -> math::Result<()> {
+    assert_eq!(evaluate("1.5")?.to_string_padded(Radix::Dec, 4), "1.5000");
+    assert_eq!(evaluate("3")?.to_string_padded(Radix::Dec, 2), "3.00");
+    assert_eq!(evaluate("-1/4")?.to_string_padded(Radix::Dec, 3), "-0.250");
+    assert_eq!(evaluate("1/3")?.to_string_padded(Radix::Dec, 3), "0.333");
+    assert_eq!(evaluate("0.9999")?.to_string_padded(Radix::Dec, 2), "1.00");
+    assert_eq!(evaluate("5/2")?.to_string_padded(Radix::Bin, 3), "10.100");
+
+    // rounded to zero
+    let tiny = evaluate("-1/1000")?;
+    assert_eq!(tiny.to_string_padded(Radix::Dec, 2), "0.00");
+    assert_eq!(tiny.to_string(Radix::Dec, 2), "0");
+    assert_eq!(tiny.to_string_signed_zero(Radix::Dec, 2), "-0");
+    Ok(())
+}
+
+#[test]
+fn evaluate_step_sign() -> math::Result<()> {
+    assert_eq!(evaluate("step(-1)")?, Number::zero());
+    assert_eq!(evaluate("step(1)")?, Number::one());
+    assert_eq!(evaluate("step(0)")?, Number::new(1, 2)?);
+    assert_eq!(evaluate("step(-1/1000000)")?, Number::zero());
+
+    assert_eq!(evaluate("sign(-5)")?, Number::from(-1));
+    assert_eq!(evaluate("sign(0)")?, Number::zero());
+    assert_eq!(evaluate("sign(2/3)")?, Number::one());
+    assert_eq!(evaluate("sign(-2) * abs(-2)")?, Number::from(-2));
+    Ok(())
+}
+
+#[test]
+fn evaluate_isqrt() -> math::Result<()> {
+    assert_eq!(evaluate("isqrt(144)")?, Number::from(12));
+    assert_eq!(evaluate("isqrt(145)")?, Number::from(12));
+    assert_eq!(evaluate("isqrt(0)")?, Number::zero());
+    assert!(evaluate("isqrt(-4)").is_err());
+    assert!(evaluate("isqrt(2.5)").is_err());
+    assert_eq!(evaluate("isqrt(10^40)")?, evaluate("10^20")?);
+    Ok(())
+}
+
+#[test]
+fn constant_dependencies() -> math::Result<()> {
+    let mut calculator = Calculator::new();
+    calculator.add_constant("a", 10);
+    calculator.add_constant("b", 20);
+
+    calculator.add_constant_expr("sum", "a() + b() + a()")?;
+    calculator.add_constant_expr("twice", "2 * sum() + sqrt(4)")?;
+    calculator.add_constant_expr("plain", "1 + 2")?;
+
+    assert_eq!(calculator.get_constant("twice"), Some(Number::from(82)));
+    assert_eq!(calculator.constant_dependencies("sum"), ["a", "b"]);
+    assert_eq!(calculator.constant_dependencies("Twice"), ["sum"]);
+    assert!(calculator.constant_dependencies("plain").is_empty());
+    assert!(calculator.constant_dependencies("unknown").is_empty());
+    assert_eq!(calculator.constant_dependents("a"), ["sum"]);
+
+    // failed definition doesn't change anything
+    assert!(calculator.add_constant_expr("broken", "a() / 0").is_err());
+    assert_eq!(calculator.get_constant("broken"), None);
+    assert!(calculator.add_constant_expr("sqrt", "1").is_err());
+
+    // removal of the used constant is blocked
+    assert_eq!(
+        calculator.remove_constant_checked("a"),
+        Err(Error::ConstantInUse(vec![String::from("sum")]))
+    );
+    assert_eq!(calculator.get_constant("a"), Some(Number::from(10)));
+
+    // redefinition by value drops the dependencies
+    calculator.add_constant("sum", 0);
+    assert!(calculator.constant_dependencies("sum").is_empty());
+    assert_eq!(
+        calculator.remove_constant_checked("a")?,
+        Some(Number::from(10))
+    );
+    assert_eq!(
+        calculator.remove_constant_checked("sum"),
+        Err(Error::ConstantInUse(vec![String::from("twice")]))
+    );
+    Ok(())
+}
+
+#[test]
+fn evaluate_report_approximate() -> math::Result<()> {
+    let mut calculator = Calculator::new();
+
+    let report = calculator.evaluate_report("sin(1)")?;
+    assert!(!report.exact);
+    assert!(!report.tokens.is_empty());
+    assert!(report.warnings.is_empty());
+    assert_eq!(report.result, calculator.evaluate("sin(1)")?);
+
+    let report = calculator.evaluate_report("1/3")?;
+    assert!(report.exact);
+    assert!(report.warnings.is_empty());
+
+    assert_eq!(
+        calculator.evaluate_report("phi()")?.warnings,
+        [Warning::LowPrecisionConstant(String::from("phi"))]
+    );
+
+    calculator.set_constant_precision(20);
+    assert!(calculator.evaluate_report("pi()")?.warnings.is_empty());
+    assert!(calculator.evaluate_report("phi()")?.warnings.is_empty());
+    assert_eq!(
+        calculator.evaluate_report("phi()")?.result,
+        Number::phi_precise(20)
+    );
+    assert!(calculator.evaluate_report("1/0").is_err());
+    assert!(!calculator.engine().tracing());
+
+    // Tracing enabled before stays enabled
+    let mut engine = ShuntingYardEngine::default();
+    engine.set_tracing(true);
+    calculator.set_engine(engine);
+    calculator.evaluate_report("1 + 1")?;
+    assert!(calculator.engine().tracing());
+    Ok(())
+}
+
+#[test]
+fn evaluate_op_count() -> math::Result<()> {
+    let mut calculator = Calculator::new();
+    assert_eq!(calculator.last_op_count(), Some(0));
+
+    calculator.evaluate("1+2+3")?;
+    assert_eq!(calculator.last_op_count(), Some(2));
+
+    calculator.evaluate("(1+2)*(3+4) + (1+2)*(3+4)")?;
+    let repeated = calculator.last_op_count().unwrap();
+    assert_eq!(repeated, 7);
+
+    // Folding the repeated sub-expression into a constant saves its operations
+    calculator.add_constant_expr("x", "(1+2)*(3+4)")?;
+    calculator.evaluate("x() + x()")?;
+    assert!(calculator.last_op_count().unwrap() < repeated);
+
+    calculator.evaluate("3! + |-2| + sqrt(4)")?;
+    assert_eq!(calculator.last_op_count(), Some(5));
+    Ok(())
+}
+
+#[test]
+fn evaluate_comments() {
+    assert_eq!(evaluate("1 + 2 # add them"), Ok(Number::from(3)));
+    assert_eq!(evaluate("6 // half of it\n / 2"), Ok(Number::from(3)));
+    assert_eq!(evaluate("# price\n10\n# tax\n* 1.5"), Ok(Number::from(15)));
+    assert_eq!(evaluate("# nothing"), Err(Error::EmptyExpression));
+}
+
+#[test]
+fn number_into_integers() -> math::Result<()> {
+    let num = |s| evaluate(s).unwrap();
+
+    assert_eq!(i64::try_from(num("-(2^40)")), Ok(-(1 << 40)));
+    assert_eq!(u64::try_from(num("2^64 - 1")), Ok(u64::MAX));
+    assert_eq!(i32::try_from(num("-2^31")), Ok(i32::MIN));
+    assert_eq!(u32::try_from(num("10/2")), Ok(5));
+    let idx: usize = num("3!").try_into()?;
+    assert_eq!(idx, 6);
+
+    for s in ["1/2", "-0.5", "2^64 + 1/3"] {
+        assert_eq!(i64::try_from(num(s)), Err(Error::InvalidArguments));
+        assert_eq!(u64::try_from(num(s)), Err(Error::InvalidArguments));
+        assert_eq!(i32::try_from(num(s)), Err(Error::InvalidArguments));
+        assert_eq!(u32::try_from(num(s)), Err(Error::InvalidArguments));
+        assert_eq!(usize::try_from(num(s)), Err(Error::InvalidArguments));
+    }
+
+    assert_eq!(i64::try_from(num("2^63")), Err(Error::OutOfRange));
+    assert_eq!(u64::try_from(num("2^64")), Err(Error::OutOfRange));
+    assert_eq!(u64::try_from(num("-1")), Err(Error::OutOfRange));
+    assert_eq!(i32::try_from(num("-2^31 - 1")), Err(Error::OutOfRange));
+    assert_eq!(u32::try_from(num("2^32")), Err(Error::OutOfRange));
+    assert_eq!(usize::try_from(num("-1")), Err(Error::OutOfRange));
+    assert_eq!(usize::try_from(num("2^128")), Err(Error::OutOfRange));
+    Ok(())
+}
+
+#[test]
+fn evaluate_chained_implicit_multiplication() -> math::Result<()> {
+    assert_eq!(eval_dec("(1 + 2)(3 + 4)", 0)?, "21");
+    assert_eq!(eval_dec("3(2)(4)", 0)?, "24");
+    assert_eq!(eval_dec("(2)(3)(4)", 0)?, "24");
+    assert_eq!(eval_dec("(2)3(4)5", 0)?, "120");
+    assert_eq!(eval_dec("2!(3)(2)", 0)?, "12");
+    assert_eq!(eval_dec("sqrt(4)(3)(1 - 2)", 0)?, "-6");
+    assert_eq!(eval_dec("1 + (2)(3) * 4", 0)?, "25");
+
+    let mut calculator = Calculator::new();
+    assert!(calculator.validate("(1 + 2)(3 + 4)").is_ok());
+    assert!(calculator.validate("3(2)(4)").is_ok());
+    Ok(())
+}
+
+#[test]
+fn number_string_fit() -> math::Result<()> {
+    let fit = |s, width| evaluate(s).map(|num| num.to_string_fit(Radix::Dec, width));
+
+    assert_eq!(fit("123456789012", 8)?, "1.235e11");
+    assert_eq!(fit("-123456789012", 8)?, "-1.23e11");
+    assert_eq!(fit("2^200", 8)?, "1.607e60");
+    assert_eq!(fit("12345678", 8)?, "12345678");
+    assert_eq!(fit("123456789", 8)?, "1.2346e8");
+    assert_eq!(fit("1/7", 8)?, "0.142857");
+    assert_eq!(fit("9999.99", 7)?, "9999.99");
+    assert_eq!(fit("9999.99", 6)?, "10000");
+    assert_eq!(fit("1/10^9", 8)?, "1e-9");
+    assert_eq!(fit("0", 8)?, "0");
+
+    // the width is too small even for the scientific notation
+    assert_eq!(fit("2^64", 3)?, "2e19");
+    assert_eq!(fit("-12", 1)?, "-1e1");
+
+    for width in 6..20 {
+        for s in ["pi()", "-2^70", "1/3^30", "10^10 - 1"] {
+            assert!(fit(s, width)?.len() <= width, "{s} doesn't fit {width}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn set_and_get_variable() -> math::Result<()> {
+    let mut calculator = Calculator::new();
+
+    let constant = math::Variable::Constant(Number::new(3, 2)?);
+    assert!(calculator.set_variable("Half_Three", constant));
+    let hypot = math::Variable::Function {
+        argc: 2,
+        ptr: |nums| nums[0].mul(&nums[0])?.add(nums[1].mul(&nums[1])?)?.sqrt(),
+    };
+    assert!(calculator.set_variable("hypot", hypot));
+
+    assert!(matches!(
+        calculator.get_variable("half_three"),
+        Some(math::Variable::Constant(num)) if *num == Number::new(3, 2)?
+    ));
+    assert_eq!(
+        calculator.get_constant("HALF_THREE"),
+        Some(Number::new(3, 2)?)
+    );
+    assert!(matches!(
+        calculator.get_variable("Hypot"),
+        Some(math::Variable::Function { argc: 2, .. })
+    ));
+    assert_eq!(calculator.get_constant("hypot"), None);
+    assert_eq!(
+        calculator.evaluate("hypot(3, 4) * half_three() * 2")?,
+        Number::from(15)
+    );
+
+    // built-in names are reserved
+    assert!(!calculator.set_variable("sqrt", math::Variable::Constant(Number::one())));
+    assert!(!calculator.set_variable("mod", math::Variable::Constant(Number::one())));
+    assert_eq!(calculator.evaluate("sqrt(9)")?, Number::from(3));
+
+    // add_constant is the same as setting a constant variable
+    assert!(calculator.add_constant("hypot", 5));
+    assert!(matches!(
+        calculator.get_variable("hypot"),
+        Some(math::Variable::Constant(..))
+    ));
+    Ok(())
+}
+
+#[test]
+fn signed_zero_display() -> math::Result<()> {
+    let num = evaluate("-0.0001")?;
+    assert_eq!(num.to_string_signed_zero(Radix::Dec, 2), "-0");
+    assert_eq!(num.to_string(Radix::Dec, 2), "0");
+    assert_eq!(num.to_string_signed_zero(Radix::Dec, 5), "-0.0001");
+
+    assert_eq!(
+        evaluate("-1/100")?.to_string_signed_zero(Radix::Hex, 1),
+        "-0"
+    );
+    assert_eq!(
+        evaluate("1/3 - 1/3")?.to_string_signed_zero(Radix::Dec, 2),
+        "0"
+    );
+    assert_eq!(
+        evaluate("0.0001")?.to_string_signed_zero(Radix::Dec, 2),
+        "0"
+    );
+    assert_eq!(evaluate("-0.6")?.to_string_signed_zero(Radix::Dec, 0), "-1");
     Ok(())
 }
@@ -0,0 +1,160 @@
+use math::error::Error;
+use math::token::{LeadingZeros, Scanner, Token};
+use math::Number;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Characters the scanner knows about, so the random input gets past the first character
+const ALPHABET: &[u8] = b"0123456789abcdefxobXOB.,+-*/^!()| _mod#\n";
+
+/// Scan the whole `s`, the scanner has to either finish or fail, never panic or loop forever
+fn scan(s: &str) {
+    let mut scanner = Scanner::new(s);
+
+    // Every token consumes at least one character, except the last one
+    for _ in 0..=s.chars().count() + 1 {
+        match scanner.next_token() {
+            Ok(Some(_)) => continue,
+            Ok(None) | Err(_) => return,
+        }
+    }
+
+    panic!("Scanner didn't finish {s:?}");
+}
+
+#[test]
+fn scanner_fuzz_bytes() {
+    let mut rng = StdRng::seed_from_u64(0x5ca9);
+
+    for _ in 0..10_000 {
+        let len = rng.gen_range(0..32);
+        let bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        scan(&String::from_utf8_lossy(&bytes));
+    }
+}
+
+#[test]
+fn scanner_fuzz_alphabet() {
+    let mut rng = StdRng::seed_from_u64(0x1f5);
+
+    for _ in 0..10_000 {
+        let len = rng.gen_range(0..32);
+        let s: String = (0..len)
+            .map(|_| char::from(ALPHABET[rng.gen_range(0..ALPHABET.len())]))
+            .collect();
+        scan(&s);
+    }
+}
+
+#[test]
+fn scanner_edge_cases() {
+    for s in [
+        "", ".", "0x", "0b", "0.", "0x.", "0b.2", "..", "9.", "_", "é", "\0", "1 2", "#", "//",
+        "/", "1#", "1//\n", "#\n#\n",
+    ] {
+        scan(s);
+    }
+}
+
+/// Tokens of the whole `s`
+fn tokens(s: &str) -> math::Result<Vec<Token>> {
+    let mut scanner = Scanner::new(s);
+    let mut tokens = Vec::new();
+
+    while let Some(token) = scanner.next_token()? {
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+#[test]
+fn scanner_line_comments() -> math::Result<()> {
+    let expected = tokens("1 + 2")?;
+
+    assert_eq!(tokens("1 + 2 # add them")?, expected);
+    assert_eq!(tokens("1 + 2 // add them")?, expected);
+    assert_eq!(tokens("# first\n1 +\n// second\n2")?, expected);
+    assert_eq!(tokens("1 + # comment # in comment // too\n2")?, expected);
+    assert_eq!(tokens("1 +#\n2")?, expected);
+
+    assert_eq!(tokens("# only comment")?, []);
+    assert_eq!(tokens("1 / 2")?.len(), 3);
+    // no block comments
+    assert_eq!(tokens("1 /* 2 */")?.len(), 6);
+    Ok(())
+}
+
+#[test]
+fn scanner_leading_zeros() -> math::Result<()> {
+    let scan = |s, policy| {
+        let mut scanner = Scanner::new(s).with_leading_zeros(policy);
+        scanner.next_token()
+    };
+    let number = |n| Ok(Some(Token::Number(Number::from(n))));
+
+    assert_eq!(scan("0123", LeadingZeros::Ignore), number(123));
+    assert!(scan("0123", LeadingZeros::Error).is_err());
+    assert_eq!(scan("0123", LeadingZeros::Octal), number(83));
+    assert!(scan("09", LeadingZeros::Octal).is_err());
+    // No fractional part in the legacy octal literal, `0o12.4` is allowed
+    assert!(matches!(
+        scan("012.5", LeadingZeros::Octal),
+        Err(Error::UnsupportedToken(_))
+    ));
+    assert_eq!(
+        scan("0o12.4", LeadingZeros::Octal),
+        Ok(Some(Token::Number(Number::new(21, 2)?)))
+    );
+    assert_eq!(
+        scan("012.5", LeadingZeros::Ignore),
+        Ok(Some(Token::Number(Number::new(25, 2)?)))
+    );
+
+    // Separated leading zero
+    assert_eq!(scan("0_17", LeadingZeros::Ignore), number(17));
+    for policy in [LeadingZeros::Error, LeadingZeros::Octal] {
+        assert!(
+            matches!(scan("0_17", policy), Err(Error::UnsupportedToken(_))),
+            "{policy:?}"
+        );
+    }
+    for s in ["0129", "0178", "0o19"] {
+        assert!(
+            matches!(
+                scan(s, LeadingZeros::Octal),
+                Err(Error::UnsupportedToken(_))
+            ),
+            "{s}"
+        );
+    }
+
+    // unaffected by the policy
+    for policy in [
+        LeadingZeros::Ignore,
+        LeadingZeros::Error,
+        LeadingZeros::Octal,
+    ] {
+        assert_eq!(scan("0", policy), number(0));
+        assert_eq!(scan("0x1F", policy), number(31));
+        assert_eq!(
+            scan("0.5", policy),
+            Ok(Some(Token::Number(Number::new(1, 2)?)))
+        );
+        assert_eq!(scan("123", policy), number(123));
+    }
+    Ok(())
+}
+
+#[test]
+fn scanner_exponent_limit() -> math::Result<()> {
+    let scan = |s| Scanner::new(s).next_token();
+    let power = |exp| Number::from(10).power(exp);
+
+    assert_eq!(scan("1e10000")?, Some(Token::Number(power(10_000)?)));
+    assert_eq!(scan("1e-10000")?, Some(Token::Number(power(-10_000)?)));
+    for s in ["1e10001", "1e-10001", "1e1000000", "1e99999999999999999999"] {
+        assert!(matches!(scan(s), Err(Error::UnsupportedToken(_))), "{s}");
+    }
+    Ok(())
+}
@@ -38,13 +38,13 @@ fn main() -> Result<()> {
     let n = nums.len();
 
     let avg = sum.div(n)?;
-    let sum_squared = nums.into_iter().fold(Result::Ok(Number::zero()), |v, x| {
-        v?.add(x.sub(&avg)?.power(2)?)
-    })?;
+    let sum_squared = nums
+        .into_iter()
+        .try_fold(Number::zero(), |v, x| v.add(x.sub(&avg)?.power(2)?))?;
 
     let standard_deviation = sum_squared.div(n - 1)?.sqrt()?;
 
-    println!("{}", standard_deviation.to_string(Default::default(), 12));
+    println!("{standard_deviation}");
 
     Ok(())
 }